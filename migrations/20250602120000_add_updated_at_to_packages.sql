@@ -0,0 +1,5 @@
+ALTER TABLE packages ADD COLUMN updated_at TIMESTAMPTZ NOT NULL DEFAULT now();
+
+CREATE INDEX packages_downloads_id_idx ON packages (downloads, id);
+CREATE INDEX packages_name_id_idx ON packages (name, id);
+CREATE INDEX packages_updated_at_id_idx ON packages (updated_at, id);
@@ -0,0 +1,45 @@
+CREATE TABLE package_downloads (
+    package_id UUID NOT NULL REFERENCES packages (id) ON DELETE CASCADE,
+    downloads BIGINT NOT NULL CHECK (downloads >= 0),
+    recorded_at TIMESTAMPTZ NOT NULL,
+    PRIMARY KEY (package_id, recorded_at)
+);
+
+CREATE MATERIALIZED VIEW package_rankings AS
+WITH growth AS (
+    SELECT
+        p.id AS package_id,
+        p.registry,
+        p.downloads,
+        p.downloads - COALESCE(
+            (
+                SELECT d.downloads
+                FROM package_downloads d
+                WHERE d.package_id = p.id AND d.recorded_at <= now() - INTERVAL '7 days'
+                ORDER BY d.recorded_at DESC
+                LIMIT 1
+            ),
+            (
+                SELECT d.downloads
+                FROM package_downloads d
+                WHERE d.package_id = p.id
+                ORDER BY d.recorded_at ASC
+                LIMIT 1
+            ),
+            p.downloads
+        ) AS downloads_growth
+    FROM packages p
+)
+SELECT
+    package_id,
+    registry,
+    downloads_growth,
+    RANK() OVER (PARTITION BY registry ORDER BY downloads DESC) AS downloads_rank,
+    CUME_DIST() OVER (PARTITION BY registry ORDER BY downloads ASC) * 100 AS downloads_percentile,
+    RANK() OVER (PARTITION BY registry ORDER BY downloads_growth DESC) AS growth_rank,
+    CUME_DIST() OVER (PARTITION BY registry ORDER BY downloads_growth ASC) * 100 AS growth_percentile
+FROM growth;
+
+CREATE UNIQUE INDEX package_rankings_package_id_idx ON package_rankings (package_id);
+CREATE INDEX package_rankings_registry_downloads_rank_idx ON package_rankings (registry, downloads_rank);
+CREATE INDEX package_rankings_registry_growth_rank_idx ON package_rankings (registry, growth_rank);
@@ -0,0 +1,2 @@
+ALTER TABLE jobs ADD COLUMN force BOOLEAN NOT NULL DEFAULT false;
+ALTER TABLE jobs ADD COLUMN skipped_version TEXT NULL;
@@ -0,0 +1,7 @@
+ALTER TABLE packages ADD COLUMN description TEXT NULL;
+ALTER TABLE packages ADD COLUMN license TEXT NULL;
+ALTER TABLE packages ADD COLUMN repository_url TEXT NULL;
+ALTER TABLE packages ADD COLUMN homepage TEXT NULL;
+ALTER TABLE packages ADD COLUMN keywords TEXT[] NOT NULL DEFAULT '{}';
+ALTER TABLE packages ADD COLUMN published_at TIMESTAMPTZ NULL;
+ALTER TABLE packages ADD COLUMN deprecated BOOLEAN NOT NULL DEFAULT false;
@@ -0,0 +1,9 @@
+CREATE TABLE package_dependencies (
+    package_id UUID NOT NULL REFERENCES packages (id) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    requirement TEXT NOT NULL,
+    kind TEXT NOT NULL,
+    PRIMARY KEY (package_id, name, kind)
+);
+
+CREATE INDEX package_dependencies_name_idx ON package_dependencies (name);
@@ -0,0 +1,12 @@
+ALTER TABLE packages ADD COLUMN created_at TIMESTAMPTZ NOT NULL DEFAULT now();
+ALTER TABLE packages ADD COLUMN last_refreshed_at TIMESTAMPTZ NULL;
+UPDATE packages SET last_refreshed_at = updated_at;
+ALTER TABLE packages ALTER COLUMN last_refreshed_at SET NOT NULL;
+ALTER TABLE packages ALTER COLUMN last_refreshed_at SET DEFAULT now();
+ALTER TABLE packages ADD COLUMN last_job_id UUID NULL REFERENCES jobs (id) ON DELETE SET NULL;
+
+CREATE INDEX packages_registry_last_refreshed_at_idx ON packages (registry, last_refreshed_at);
+
+ALTER TABLE jobs ADD COLUMN source TEXT NOT NULL DEFAULT 'api' CHECK (source IN ('api', 'refresher'));
+
+CREATE INDEX jobs_registry_package_name_status_idx ON jobs (registry, package_name, status);
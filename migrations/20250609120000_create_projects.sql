@@ -0,0 +1,17 @@
+CREATE FUNCTION normalize_repository_url(url TEXT) RETURNS TEXT
+LANGUAGE SQL IMMUTABLE RETURNS NULL ON NULL INPUT
+AS $$
+    SELECT lower(regexp_replace(regexp_replace(btrim(url), '^git\+', ''), '(\.git)?/*$', ''));
+$$;
+
+CREATE TABLE projects (
+    id UUID PRIMARY KEY,
+    name TEXT NOT NULL,
+    repository_url TEXT UNIQUE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+ALTER TABLE packages ADD COLUMN project_id UUID REFERENCES projects (id) ON DELETE SET NULL;
+
+CREATE INDEX packages_project_id_idx ON packages (project_id);
+CREATE INDEX packages_normalized_repository_url_idx ON packages (normalize_repository_url(repository_url));
@@ -0,0 +1,40 @@
+CREATE TABLE watchlists (
+    id UUID PRIMARY KEY,
+    name TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE TABLE watchlist_packages (
+    watchlist_id UUID NOT NULL REFERENCES watchlists (id) ON DELETE CASCADE,
+    registry TEXT NOT NULL,
+    name TEXT NOT NULL,
+    PRIMARY KEY (watchlist_id, registry, name)
+);
+
+CREATE INDEX watchlist_packages_registry_name_idx ON watchlist_packages (registry, name);
+
+CREATE TABLE watchlist_targets (
+    id UUID PRIMARY KEY,
+    watchlist_id UUID NOT NULL REFERENCES watchlists (id) ON DELETE CASCADE,
+    channel TEXT NOT NULL CHECK (channel IN ('webhook', 'email')),
+    target TEXT NOT NULL,
+    UNIQUE (watchlist_id, channel, target)
+);
+
+CREATE TABLE notifications (
+    id UUID PRIMARY KEY,
+    target_id UUID NOT NULL REFERENCES watchlist_targets (id) ON DELETE CASCADE,
+    registry TEXT NOT NULL,
+    package_name TEXT NOT NULL,
+    old_version TEXT NOT NULL,
+    new_version TEXT NOT NULL,
+    status TEXT NOT NULL DEFAULT 'pending' CHECK (status IN ('pending', 'delivered', 'failed')),
+    attempts INT NOT NULL DEFAULT 0,
+    last_error TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    next_attempt_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    delivered_at TIMESTAMPTZ
+);
+
+CREATE INDEX notifications_target_id_idx ON notifications (target_id);
+CREATE INDEX notifications_pending_idx ON notifications (next_attempt_at) WHERE status = 'pending';
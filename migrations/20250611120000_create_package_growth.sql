@@ -0,0 +1,56 @@
+CREATE MATERIALIZED VIEW package_growth AS
+WITH baselines AS (
+    SELECT
+        p.id AS package_id,
+        p.registry,
+        p.downloads,
+        COALESCE(
+            (
+                SELECT d.downloads
+                FROM package_downloads d
+                WHERE d.package_id = p.id AND d.recorded_at <= now() - INTERVAL '7 days'
+                ORDER BY d.recorded_at DESC
+                LIMIT 1
+            ),
+            first.downloads,
+            p.downloads
+        ) AS downloads_7d_ago,
+        COALESCE(
+            (
+                SELECT d.downloads
+                FROM package_downloads d
+                WHERE d.package_id = p.id AND d.recorded_at <= now() - INTERVAL '30 days'
+                ORDER BY d.recorded_at DESC
+                LIMIT 1
+            ),
+            first.downloads,
+            p.downloads
+        ) AS downloads_30d_ago
+    FROM packages p
+    LEFT JOIN LATERAL (
+        SELECT d.downloads
+        FROM package_downloads d
+        WHERE d.package_id = p.id
+        ORDER BY d.recorded_at ASC
+        LIMIT 1
+    ) first ON TRUE
+    WHERE p.deleted_at IS NULL
+)
+SELECT
+    package_id,
+    registry,
+    downloads_7d_ago,
+    downloads_30d_ago,
+    downloads - downloads_7d_ago AS growth_7d,
+    downloads - downloads_30d_ago AS growth_30d,
+    CASE WHEN downloads_7d_ago > 0
+        THEN (downloads - downloads_7d_ago)::DOUBLE PRECISION / downloads_7d_ago
+    END AS relative_growth_7d,
+    CASE WHEN downloads_30d_ago > 0
+        THEN (downloads - downloads_30d_ago)::DOUBLE PRECISION / downloads_30d_ago
+    END AS relative_growth_30d
+FROM baselines;
+
+CREATE UNIQUE INDEX package_growth_package_id_idx ON package_growth (package_id);
+CREATE INDEX package_growth_registry_growth_7d_idx ON package_growth (registry, growth_7d);
+CREATE INDEX package_growth_registry_growth_30d_idx ON package_growth (registry, growth_30d);
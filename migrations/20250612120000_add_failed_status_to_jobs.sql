@@ -0,0 +1,3 @@
+ALTER TABLE jobs DROP CONSTRAINT jobs_status_check;
+ALTER TABLE jobs ADD CONSTRAINT jobs_status_check CHECK (status IN ('processing', 'completed', 'failed'));
+ALTER TABLE jobs ADD COLUMN errors TEXT[] NULL;
@@ -0,0 +1 @@
+ALTER TABLE jobs ADD COLUMN output_key TEXT NULL;
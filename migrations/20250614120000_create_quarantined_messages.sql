@@ -0,0 +1,10 @@
+CREATE TABLE quarantined_messages (
+    id UUID PRIMARY KEY,
+    routing_key TEXT NOT NULL,
+    headers JSONB NOT NULL DEFAULT '{}',
+    error TEXT NOT NULL,
+    object_key TEXT NOT NULL,
+    size BIGINT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    reinjected_at TIMESTAMPTZ
+);
@@ -0,0 +1,40 @@
+-- Revisions back the ETags of packages and watchlists, and let writes check If-Match in the
+-- same statement. They are bumped by triggers so that no write can forget them.
+ALTER TABLE packages ADD COLUMN revision BIGINT NOT NULL DEFAULT 1;
+ALTER TABLE watchlists ADD COLUMN revision BIGINT NOT NULL DEFAULT 1;
+
+CREATE FUNCTION bump_revision() RETURNS TRIGGER
+LANGUAGE plpgsql
+AS $$
+BEGIN
+    NEW.revision := OLD.revision + 1;
+    RETURN NEW;
+END;
+$$;
+
+CREATE TRIGGER packages_bump_revision
+BEFORE UPDATE ON packages
+FOR EACH ROW EXECUTE FUNCTION bump_revision();
+
+CREATE TRIGGER watchlists_bump_revision
+BEFORE UPDATE ON watchlists
+FOR EACH ROW EXECUTE FUNCTION bump_revision();
+
+-- Watched packages and targets are part of a watchlist's representation.
+CREATE FUNCTION bump_watchlist_revision() RETURNS TRIGGER
+LANGUAGE plpgsql
+AS $$
+BEGIN
+    UPDATE watchlists SET revision = revision + 1
+    WHERE id = COALESCE(NEW.watchlist_id, OLD.watchlist_id);
+    RETURN NULL;
+END;
+$$;
+
+CREATE TRIGGER watchlist_packages_bump_revision
+AFTER INSERT OR DELETE ON watchlist_packages
+FOR EACH ROW EXECUTE FUNCTION bump_watchlist_revision();
+
+CREATE TRIGGER watchlist_targets_bump_revision
+AFTER INSERT OR DELETE ON watchlist_targets
+FOR EACH ROW EXECUTE FUNCTION bump_watchlist_revision();
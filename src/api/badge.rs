@@ -0,0 +1,233 @@
+use axum::{
+    http::header,
+    response::{IntoResponse, Response},
+};
+
+const LABEL_COLOR: &str = "#555";
+const HORIZONTAL_PADDING: u32 = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BadgeColor {
+    Blue,
+    Orange,
+    Green,
+    Grey,
+}
+
+impl BadgeColor {
+    fn hex(&self) -> &'static str {
+        match self {
+            BadgeColor::Blue => "#007ec6",
+            BadgeColor::Orange => "#fe7d37",
+            BadgeColor::Green => "#4c1",
+            BadgeColor::Grey => "#9f9f9f",
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Badge {
+    label: String,
+    message: String,
+    color: BadgeColor,
+    max_age: u32,
+}
+
+impl Badge {
+    pub fn new(label: impl Into<String>, message: impl Into<String>, color: BadgeColor) -> Self {
+        Self {
+            label: label.into(),
+            message: message.into(),
+            color,
+            max_age: 3600,
+        }
+    }
+
+    // Unknown packages may appear once ingested, so they are cached briefly.
+    pub fn unknown(label: impl Into<String>) -> Self {
+        Self {
+            max_age: 300,
+            ..Self::new(label, "unknown", BadgeColor::Grey)
+        }
+    }
+
+    pub fn version(label: impl Into<String>, version: &str, deprecated: bool) -> Self {
+        let color = if deprecated || is_prerelease(version) {
+            BadgeColor::Orange
+        } else {
+            BadgeColor::Blue
+        };
+        let message = if version.starts_with(|c: char| c.is_ascii_digit()) {
+            format!("v{}", version)
+        } else {
+            version.to_string()
+        };
+
+        Self::new(label, message, color)
+    }
+
+    pub fn downloads(downloads: i64) -> Self {
+        Self::new("downloads", format_count(downloads), BadgeColor::Green)
+    }
+
+    pub fn render(&self) -> String {
+        let label = escape(&self.label);
+        let message = escape(&self.message);
+        let label_width = text_width(&self.label) + HORIZONTAL_PADDING;
+        let message_width = text_width(&self.message) + HORIZONTAL_PADDING;
+        let width = label_width + message_width;
+        // Text is laid out at 10x scale for sub-pixel centering.
+        let label_x = label_width * 5;
+        let message_x = (label_width * 2 + message_width) * 5;
+        let label_length = (label_width - HORIZONTAL_PADDING) * 10;
+        let message_length = (message_width - HORIZONTAL_PADDING) * 10;
+
+        format!(
+            concat!(
+                r##"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="20" role="img" aria-label="{label}: {message}">"##,
+                r##"<title>{label}: {message}</title>"##,
+                r##"<linearGradient id="s" x2="0" y2="100%"><stop offset="0" stop-color="#bbb" stop-opacity=".1"/><stop offset="1" stop-opacity=".1"/></linearGradient>"##,
+                r##"<clipPath id="r"><rect width="{width}" height="20" rx="3" fill="#fff"/></clipPath>"##,
+                r##"<g clip-path="url(#r)"><rect width="{label_width}" height="20" fill="{label_color}"/><rect x="{label_width}" width="{message_width}" height="20" fill="{color}"/><rect width="{width}" height="20" fill="url(#s)"/></g>"##,
+                r##"<g fill="#fff" text-anchor="middle" font-family="Verdana,Geneva,DejaVu Sans,sans-serif" text-rendering="geometricPrecision" font-size="110">"##,
+                r##"<text x="{label_x}" y="150" fill="#010101" fill-opacity=".3" transform="scale(.1)" textLength="{label_length}">{label}</text>"##,
+                r##"<text x="{label_x}" y="140" transform="scale(.1)" textLength="{label_length}">{label}</text>"##,
+                r##"<text x="{message_x}" y="150" fill="#010101" fill-opacity=".3" transform="scale(.1)" textLength="{message_length}">{message}</text>"##,
+                r##"<text x="{message_x}" y="140" transform="scale(.1)" textLength="{message_length}">{message}</text>"##,
+                r##"</g></svg>"##,
+            ),
+            width = width,
+            label = label,
+            message = message,
+            label_width = label_width,
+            message_width = message_width,
+            label_color = LABEL_COLOR,
+            color = self.color.hex(),
+            label_x = label_x,
+            message_x = message_x,
+            label_length = label_length,
+            message_length = message_length,
+        )
+    }
+}
+
+impl IntoResponse for Badge {
+    fn into_response(self) -> Response {
+        (
+            [
+                (
+                    header::CONTENT_TYPE,
+                    "image/svg+xml;charset=utf-8".to_string(),
+                ),
+                (
+                    header::CACHE_CONTROL,
+                    format!("public, max-age={}", self.max_age),
+                ),
+            ],
+            self.render(),
+        )
+            .into_response()
+    }
+}
+
+fn is_prerelease(version: &str) -> bool {
+    version.contains('-') || version.starts_with("0.")
+}
+
+// Formats counts the way shields.io does: 999, 1.2k, 12k, 3.4M.
+pub fn format_count(count: i64) -> String {
+    const SUFFIXES: [&str; 5] = ["", "k", "M", "G", "T"];
+
+    let mut value = count.max(0) as f64;
+    let mut suffix = 0;
+    while value >= 999.5 && suffix < SUFFIXES.len() - 1 {
+        value /= 1000.0;
+        suffix += 1;
+    }
+
+    if suffix == 0 {
+        return format!("{}", value as i64);
+    }
+    if value < 9.95 {
+        let formatted = format!("{:.1}", value);
+        let formatted = formatted.strip_suffix(".0").unwrap_or(&formatted);
+        return format!("{}{}", formatted, SUFFIXES[suffix]);
+    }
+
+    format!("{}{}", value.round() as i64, SUFFIXES[suffix])
+}
+
+// Approximates the rendered width of Verdana at 11px.
+fn text_width(text: &str) -> u32 {
+    text.chars()
+        .map(|c| match c {
+            'i' | 'j' | 'l' | '.' | ',' | ':' | ';' | '\'' | '|' | '!' => 3,
+            'f' | 'r' | 't' | 'I' | '(' | ')' | '[' | ']' | ' ' | '-' | '/' => 5,
+            'm' | 'w' | 'M' | 'W' | '@' => 10,
+            'A'..='Z' => 8,
+            _ => 7,
+        })
+        .sum()
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            _ => escaped.push(c),
+        }
+    }
+
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_count() {
+        assert_eq!(format_count(0), "0");
+        assert_eq!(format_count(999), "999");
+        assert_eq!(format_count(1000), "1k");
+        assert_eq!(format_count(1234), "1.2k");
+        assert_eq!(format_count(12_345), "12k");
+        assert_eq!(format_count(999_999), "1M");
+        assert_eq!(format_count(3_450_000), "3.5M");
+        assert_eq!(format_count(7_000_000_000), "7G");
+    }
+
+    #[test]
+    fn test_version_badge_marks_prereleases() {
+        assert_eq!(
+            Badge::version("crates.io", "1.2.3", false).color,
+            BadgeColor::Blue
+        );
+        assert_eq!(
+            Badge::version("crates.io", "0.4.0", false).color,
+            BadgeColor::Orange
+        );
+        assert_eq!(
+            Badge::version("crates.io", "1.0.0", true).color,
+            BadgeColor::Orange
+        );
+        assert_eq!(
+            Badge::version("crates.io", "1.2.3", false).message,
+            "v1.2.3"
+        );
+    }
+
+    #[test]
+    fn test_render_escapes_text() {
+        let svg = Badge::new("a<b", "\"c\" & d", BadgeColor::Blue).render();
+
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.contains("a&lt;b: &quot;c&quot; &amp; d"));
+        assert!(!svg.contains("a<b"));
+    }
+}
@@ -0,0 +1,207 @@
+use std::{future::Future, io};
+
+use anyhow::Result;
+use axum::{
+    body::Body,
+    http::header,
+    response::{IntoResponse, Response},
+};
+use bytes::Bytes;
+use futures::{stream, Stream, StreamExt};
+use serde::Serialize;
+use tokio::sync::mpsc;
+use tracing::{Instrument, Span};
+
+use crate::{api::types::DataFormat, types::CsvRecord};
+
+const CHUNK_ROWS: usize = 500;
+const CHANNEL_CAPACITY: usize = 16;
+
+type Chunk = Result<Bytes, io::Error>;
+
+pub fn stream_export<F, Fut>(format: DataFormat, filename: &str, export: F) -> Response
+where
+    F: FnOnce(ExportSink) -> Fut,
+    Fut: Future<Output = Result<()>> + Send + 'static,
+{
+    let (sender, receiver) = mpsc::channel::<Chunk>(CHANNEL_CAPACITY);
+    let task = export(ExportSink::new(format, sender.clone()));
+    tokio::spawn(
+        async move {
+            if let Err(error) = task.await {
+                tracing::error!(error = ?error, "Export failed");
+                // Abort the chunked body so clients do not mistake a partial export for a full one.
+                let _ = sender.send(Err(io::Error::other("Export failed"))).await;
+            }
+        }
+        .instrument(Span::current()),
+    );
+
+    let body = Body::from_stream(stream::unfold(receiver, |mut receiver| async move {
+        receiver.recv().await.map(|chunk| (chunk, receiver))
+    }));
+
+    (
+        [
+            (header::CONTENT_TYPE, format.content_type().to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!(
+                    "attachment; filename=\"{}.{}\"",
+                    filename,
+                    format.extension()
+                ),
+            ),
+        ],
+        body,
+    )
+        .into_response()
+}
+
+pub struct ExportSink {
+    format: DataFormat,
+    sender: mpsc::Sender<Chunk>,
+    csv: csv::Writer<Vec<u8>>,
+    ndjson: Vec<u8>,
+    rows: usize,
+}
+
+impl ExportSink {
+    fn new(format: DataFormat, sender: mpsc::Sender<Chunk>) -> Self {
+        Self {
+            format,
+            sender,
+            csv: csv::Writer::from_writer(Vec::new()),
+            ndjson: Vec::new(),
+            rows: 0,
+        }
+    }
+
+    pub async fn send_all<T, S>(mut self, mut rows: S) -> Result<()>
+    where
+        T: CsvRecord + Serialize,
+        S: Stream<Item = Result<T>> + Unpin,
+    {
+        if self.format == DataFormat::Csv {
+            self.csv.write_record(T::HEADER)?;
+        }
+
+        while let Some(row) = rows.next().await {
+            self.write(&row?)?;
+
+            if self.rows >= CHUNK_ROWS && !self.flush().await? {
+                tracing::debug!("Export client disconnected");
+                return Ok(());
+            }
+        }
+
+        self.flush().await?;
+
+        Ok(())
+    }
+
+    fn write<T>(&mut self, row: &T) -> Result<()>
+    where
+        T: CsvRecord + Serialize,
+    {
+        match self.format {
+            DataFormat::Csv => self.csv.write_record(row.record())?,
+            DataFormat::Ndjson => {
+                serde_json::to_writer(&mut self.ndjson, row)?;
+                self.ndjson.push(b'\n');
+            }
+        }
+        self.rows += 1;
+
+        Ok(())
+    }
+
+    fn take_chunk(&mut self) -> Result<Vec<u8>> {
+        self.rows = 0;
+        let chunk = match self.format {
+            DataFormat::Csv => {
+                std::mem::replace(&mut self.csv, csv::Writer::from_writer(Vec::new()))
+                    .into_inner()
+                    .map_err(|error| error.into_error())?
+            }
+            DataFormat::Ndjson => std::mem::take(&mut self.ndjson),
+        };
+
+        Ok(chunk)
+    }
+
+    // Returns false once the client has gone away.
+    async fn flush(&mut self) -> Result<bool> {
+        let chunk = self.take_chunk()?;
+        if chunk.is_empty() {
+            return Ok(true);
+        }
+
+        Ok(self.sender.send(Ok(Bytes::from(chunk))).await.is_ok())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Serialize;
+
+    use super::*;
+
+    #[derive(Serialize)]
+    struct Row {
+        name: String,
+        downloads: i64,
+    }
+
+    impl CsvRecord for Row {
+        const HEADER: &'static [&'static str] = &["name", "downloads"];
+
+        fn record(&self) -> Vec<String> {
+            vec![self.name.clone(), self.downloads.to_string()]
+        }
+    }
+
+    fn rows() -> impl Stream<Item = Result<Row>> + Unpin {
+        stream::iter(vec![
+            Ok(Row {
+                name: "serde".to_string(),
+                downloads: 10,
+            }),
+            Ok(Row {
+                name: "a, \"b\"".to_string(),
+                downloads: 5,
+            }),
+        ])
+    }
+
+    async fn collect(format: DataFormat) -> String {
+        let (sender, mut receiver) = mpsc::channel(CHANNEL_CAPACITY);
+        ExportSink::new(format, sender)
+            .send_all(rows())
+            .await
+            .unwrap();
+
+        let mut output = Vec::new();
+        while let Some(chunk) = receiver.recv().await {
+            output.extend_from_slice(&chunk.unwrap());
+        }
+        String::from_utf8(output).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_send_all_writes_csv_with_header() {
+        let output = collect(DataFormat::Csv).await;
+
+        assert_eq!(output, "name,downloads\nserde,10\n\"a, \"\"b\"\"\",5\n");
+    }
+
+    #[tokio::test]
+    async fn test_send_all_writes_ndjson() {
+        let output = collect(DataFormat::Ndjson).await;
+
+        assert_eq!(
+            output,
+            "{\"name\":\"serde\",\"downloads\":10}\n{\"name\":\"a, \\\"b\\\"\",\"downloads\":5}\n"
+        );
+    }
+}
@@ -0,0 +1,399 @@
+use anyhow::Result;
+use axum::body::Bytes;
+use chrono::{DateTime, Utc};
+use futures::{Stream, StreamExt};
+use serde::{Deserialize, Serialize};
+use sqlx::{Pool, Postgres};
+use uuid::Uuid;
+
+use crate::{api::types::DataFormat, db, error::Error, models::package::Package};
+
+const BATCH_SIZE: usize = 1000;
+const MAX_REPORTED_ERRORS: usize = 1000;
+
+#[derive(Debug, Default, Serialize)]
+pub struct ImportReport {
+    pub imported: u64,
+    pub failed: u64,
+    pub errors: Vec<ImportError>,
+}
+
+#[derive(Debug, Serialize, PartialEq)]
+pub struct ImportError {
+    pub line: u64,
+    pub message: String,
+}
+
+impl ImportReport {
+    fn fail(&mut self, line: u64, message: impl Into<String>) {
+        self.failed += 1;
+        if self.errors.len() < MAX_REPORTED_ERRORS {
+            self.errors.push(ImportError {
+                line,
+                message: message.into(),
+            });
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct ImportRow {
+    registry: String,
+    name: String,
+    version: String,
+    downloads: i64,
+    description: Option<String>,
+    license: Option<String>,
+    #[serde(alias = "repository")]
+    repository_url: Option<String>,
+    homepage: Option<String>,
+    keywords: Option<Keywords>,
+    published_at: Option<DateTime<Utc>>,
+    deprecated: Option<bool>,
+}
+
+// CSV rows carry keywords as a single comma separated field.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum Keywords {
+    List(Vec<String>),
+    Text(String),
+}
+
+impl ImportRow {
+    fn into_package(self) -> Result<Package, String> {
+        let registry = required("registry", self.registry)?;
+        let name = required("name", self.name)?;
+        let version = required("version", self.version)?;
+        if self.downloads < 0 {
+            return Err("downloads must not be negative".to_string());
+        }
+
+        let keywords = match self.keywords {
+            Some(Keywords::List(keywords)) => keywords,
+            Some(Keywords::Text(keywords)) => keywords
+                .split(',')
+                .map(str::trim)
+                .filter(|keyword| !keyword.is_empty())
+                .map(String::from)
+                .collect(),
+            None => Vec::new(),
+        };
+
+        let now = Utc::now();
+        Ok(Package {
+            id: Uuid::now_v7(),
+            registry,
+            name,
+            version,
+            downloads: self.downloads,
+            updated_at: now,
+            description: optional(self.description),
+            license: optional(self.license),
+            repository_url: optional(self.repository_url),
+            homepage: optional(self.homepage),
+            keywords,
+            published_at: self.published_at,
+            deprecated: self.deprecated.unwrap_or_default(),
+            created_at: now,
+            last_refreshed_at: now,
+            last_job_id: None,
+            deleted_at: None,
+            deletion_reason: None,
+            project_id: None,
+            revision: 1,
+        })
+    }
+}
+
+fn required(field: &str, value: String) -> Result<String, String> {
+    let value = value.trim();
+    if value.is_empty() {
+        return Err(format!("{} must not be empty", field));
+    }
+
+    Ok(value.to_string())
+}
+
+fn optional(value: Option<String>) -> Option<String> {
+    value.filter(|value| !value.trim().is_empty())
+}
+
+pub async fn import_packages<S, E>(
+    db_pool: &Pool<Postgres>,
+    format: DataFormat,
+    mut body: S,
+) -> Result<ImportReport, Error>
+where
+    S: Stream<Item = Result<Bytes, E>> + Unpin,
+    E: std::fmt::Display,
+{
+    let mut importer = Importer::new(db_pool, format);
+    let mut splitter = RecordSplitter::new(format);
+
+    while let Some(chunk) = body.next().await {
+        let chunk = chunk.map_err(|error| {
+            Error::InvalidInput(format!("Failed to read request body: {}", error))
+        })?;
+        for (line, record) in splitter.push(&chunk) {
+            importer.push(line, &record).await?;
+        }
+    }
+    if let Some((line, record)) = splitter.finish() {
+        importer.push(line, &record).await?;
+    }
+
+    importer.finish().await
+}
+
+struct Importer<'a> {
+    db_pool: &'a Pool<Postgres>,
+    format: DataFormat,
+    headers: Option<csv::StringRecord>,
+    batch: Vec<(u64, Package)>,
+    report: ImportReport,
+}
+
+impl<'a> Importer<'a> {
+    fn new(db_pool: &'a Pool<Postgres>, format: DataFormat) -> Self {
+        Self {
+            db_pool,
+            format,
+            headers: None,
+            batch: Vec::with_capacity(BATCH_SIZE),
+            report: ImportReport::default(),
+        }
+    }
+
+    async fn push(&mut self, line: u64, record: &[u8]) -> Result<(), Error> {
+        if record.iter().all(u8::is_ascii_whitespace) {
+            return Ok(());
+        }
+
+        let row = match self.format {
+            DataFormat::Ndjson => {
+                serde_json::from_slice::<ImportRow>(record).map_err(|error| error.to_string())
+            }
+            DataFormat::Csv => {
+                let record = parse_csv_record(record);
+                let Some(headers) = &self.headers else {
+                    let headers = record.map_err(|error| {
+                        Error::InvalidInput(format!("Invalid CSV header: {}", error))
+                    })?;
+                    self.headers = Some(headers);
+                    return Ok(());
+                };
+                record.and_then(|record| {
+                    record
+                        .deserialize::<ImportRow>(Some(headers))
+                        .map_err(|error| error.to_string())
+                })
+            }
+        };
+
+        match row.and_then(ImportRow::into_package) {
+            Ok(package) => self.batch.push((line, package)),
+            Err(message) => self.report.fail(line, message),
+        }
+
+        if self.batch.len() >= BATCH_SIZE {
+            self.flush().await?;
+        }
+
+        Ok(())
+    }
+
+    async fn flush(&mut self) -> Result<(), Error> {
+        if self.batch.is_empty() {
+            return Ok(());
+        }
+
+        let (lines, packages): (Vec<u64>, Vec<Package>) = self.batch.drain(..).unzip();
+        match self.store(&packages).await {
+            Ok(imported) => self.report.imported += imported,
+            Err(error) => {
+                tracing::error!(error = ?error, "Failed to import batch");
+                for line in lines {
+                    self.report.fail(line, "Failed to store row");
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn store(&self, packages: &[Package]) -> Result<u64> {
+        let mut transaction = self.db_pool.begin().await?;
+        let imported = db::import_packages(&mut transaction, packages).await?;
+        transaction.commit().await?;
+
+        Ok(imported)
+    }
+
+    async fn finish(mut self) -> Result<ImportReport, Error> {
+        self.flush().await?;
+
+        Ok(self.report)
+    }
+}
+
+fn parse_csv_record(record: &[u8]) -> Result<csv::StringRecord, String> {
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .from_reader(record);
+    let mut parsed = csv::StringRecord::new();
+    reader
+        .read_record(&mut parsed)
+        .map_err(|error| error.to_string())?;
+
+    Ok(parsed)
+}
+
+// Splits a byte stream into records, keeping the line each record starts on.
+// CSV records may span lines when a quoted field contains a newline.
+struct RecordSplitter {
+    format: DataFormat,
+    buffer: Vec<u8>,
+    line: u64,
+    record_line: u64,
+    quotes: usize,
+}
+
+impl RecordSplitter {
+    fn new(format: DataFormat) -> Self {
+        Self {
+            format,
+            buffer: Vec::new(),
+            line: 0,
+            record_line: 1,
+            quotes: 0,
+        }
+    }
+
+    fn push(&mut self, chunk: &[u8]) -> Vec<(u64, Vec<u8>)> {
+        let mut records = Vec::new();
+        for &byte in chunk {
+            if byte != b'\n' {
+                if byte == b'"' {
+                    self.quotes += 1;
+                }
+                self.buffer.push(byte);
+                continue;
+            }
+
+            self.line += 1;
+            if self.format == DataFormat::Csv && self.quotes % 2 == 1 {
+                self.buffer.push(byte);
+                continue;
+            }
+
+            records.push(self.take());
+        }
+
+        records
+    }
+
+    fn finish(mut self) -> Option<(u64, Vec<u8>)> {
+        if self.buffer.is_empty() {
+            return None;
+        }
+
+        Some(self.take())
+    }
+
+    fn take(&mut self) -> (u64, Vec<u8>) {
+        let mut record = std::mem::take(&mut self.buffer);
+        if record.last() == Some(&b'\r') {
+            record.pop();
+        }
+        let line = self.record_line;
+        self.record_line = self.line + 1;
+        self.quotes = 0;
+
+        (line, record)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn split(format: DataFormat, chunks: &[&str]) -> Vec<(u64, String)> {
+        let mut splitter = RecordSplitter::new(format);
+        let mut records: Vec<(u64, Vec<u8>)> = chunks
+            .iter()
+            .flat_map(|chunk| splitter.push(chunk.as_bytes()))
+            .collect();
+        records.extend(splitter.finish());
+
+        records
+            .into_iter()
+            .map(|(line, record)| (line, String::from_utf8(record).unwrap()))
+            .collect()
+    }
+
+    #[test]
+    fn test_splitter_joins_records_across_chunks() {
+        let records = split(DataFormat::Ndjson, &["{\"a\":", "1}\r\n{\"b\"", ":2}"]);
+
+        assert_eq!(
+            records,
+            vec![(1, "{\"a\":1}".to_string()), (2, "{\"b\":2}".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_splitter_keeps_quoted_newlines_in_csv() {
+        let records = split(
+            DataFormat::Csv,
+            &["name,description\n", "a,\"multi\nline\"\nb,c\n"],
+        );
+
+        assert_eq!(
+            records,
+            vec![
+                (1, "name,description".to_string()),
+                (2, "a,\"multi\nline\"".to_string()),
+                (4, "b,c".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_import_row_validates_required_fields() {
+        let row: ImportRow = serde_json::from_str(
+            r#"{"registry":"crates.io","name":" ","version":"1.0.0","downloads":1}"#,
+        )
+        .unwrap();
+
+        assert_eq!(row.into_package().unwrap_err(), "name must not be empty");
+
+        let row: ImportRow = serde_json::from_str(
+            r#"{"registry":"crates.io","name":"serde","version":"1.0.0","downloads":-1}"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            row.into_package().unwrap_err(),
+            "downloads must not be negative"
+        );
+    }
+
+    #[test]
+    fn test_import_row_reads_csv_keywords() {
+        let headers =
+            parse_csv_record(b"registry,name,version,downloads,keywords,license").unwrap();
+        let record =
+            parse_csv_record(b"crates.io,serde,1.0.0,10,\"serde, serialization\",").unwrap();
+
+        let package = record
+            .deserialize::<ImportRow>(Some(&headers))
+            .unwrap()
+            .into_package()
+            .unwrap();
+
+        assert_eq!(package.keywords, vec!["serde", "serialization"]);
+        assert_eq!(package.license, None);
+        assert!(!package.deprecated);
+    }
+}
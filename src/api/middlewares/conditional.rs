@@ -0,0 +1,311 @@
+use std::convert::Infallible;
+
+use axum::{
+    async_trait,
+    body::{to_bytes, Body, HttpBody},
+    extract::{FromRequestParts, Request},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use http::{
+    header::{CONTENT_TYPE, ETAG, IF_MATCH, IF_NONE_MATCH},
+    request::Parts,
+    HeaderMap, HeaderValue, Method, StatusCode,
+};
+use sha2::{Digest, Sha256};
+
+// Handlers of resources with a revision column (packages, watchlists) set their ETag from it,
+// so their body is neither buffered nor hashed here. Other small JSON responses, such as jobs
+// and list pages, are tagged with a hash of the body: If-None-Match then saves the transfer,
+// but not the query.
+pub async fn conditional_requests(request: Request, next: Next) -> Response {
+    if !matches!(*request.method(), Method::GET | Method::HEAD) {
+        return next.run(request).await;
+    }
+
+    let if_none_match = request.headers().get(IF_NONE_MATCH).cloned();
+    let (mut response, etag) = match with_etag(next.run(request).await).await {
+        Ok(tagged) => tagged,
+        Err(response) => return response,
+    };
+    let Some(etag) = etag else {
+        return response;
+    };
+
+    if if_none_match.is_some_and(|header| matches(&header, &etag)) {
+        return (StatusCode::NOT_MODIFIED, [(ETAG, etag)]).into_response();
+    }
+    response.headers_mut().insert(ETAG, etag);
+
+    response
+}
+
+pub fn revision_etag(revision: i64) -> HeaderValue {
+    HeaderValue::from_str(&format!("\"{}\"", revision)).expect("digits are a valid header value")
+}
+
+// The revisions listed in If-Match, or none when the header is absent or `*`. Handlers pass
+// them to the statement performing the write, so that two writers holding the same ETag
+// cannot both succeed.
+#[derive(Debug, Default)]
+pub struct IfMatch(Option<Vec<i64>>);
+
+impl IfMatch {
+    pub fn revisions(&self) -> Option<&[i64]> {
+        self.0.as_deref()
+    }
+
+    pub fn allows(&self, revision: i64) -> bool {
+        self.revisions()
+            .is_none_or(|revisions| revisions.contains(&revision))
+    }
+}
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for IfMatch {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        Ok(parse_if_match(parts.headers.get(IF_MATCH)))
+    }
+}
+
+// If-Match uses the strong comparison, so weak and foreign validators never match (RFC 9110).
+fn parse_if_match(header: Option<&HeaderValue>) -> IfMatch {
+    let Some(header) = header else {
+        return IfMatch(None);
+    };
+    let Ok(header) = header.to_str() else {
+        return IfMatch(Some(Vec::new()));
+    };
+
+    let mut revisions = Vec::new();
+    for candidate in header.split(',').map(str::trim) {
+        if candidate == "*" {
+            return IfMatch(None);
+        }
+        if let Some(revision) = candidate
+            .strip_prefix('"')
+            .and_then(|candidate| candidate.strip_suffix('"'))
+            .and_then(|candidate| candidate.parse().ok())
+        {
+            revisions.push(revision);
+        }
+    }
+
+    IfMatch(Some(revisions))
+}
+
+// Buffers successful JSON responses of a known, bounded size to tag them; anything else,
+// such as streamed exports, is passed through untouched.
+async fn with_etag(response: Response) -> Result<(Response, Option<HeaderValue>), Response> {
+    if response.status() != StatusCode::OK || !is_json(response.headers()) {
+        return Ok((response, None));
+    }
+    if let Some(etag) = response.headers().get(ETAG).cloned() {
+        return Ok((response, Some(etag)));
+    }
+    let size = response.body().size_hint().exact();
+    if size.is_none_or(|size| size > MAX_HASHED_BODY_BYTES as u64) {
+        return Ok((response, None));
+    }
+
+    let (parts, body) = response.into_parts();
+    let bytes = match to_bytes(body, MAX_HASHED_BODY_BYTES).await {
+        Ok(bytes) => bytes,
+        Err(error) => {
+            tracing::error!(error = ?error, "Failed to buffer response body");
+            return Err(StatusCode::INTERNAL_SERVER_ERROR.into_response());
+        }
+    };
+    let etag = compute_etag(&bytes);
+
+    Ok((Response::from_parts(parts, Body::from(bytes)), Some(etag)))
+}
+
+const MAX_HASHED_BODY_BYTES: usize = 1024 * 1024;
+
+fn is_json(headers: &HeaderMap) -> bool {
+    headers
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with(mime::APPLICATION_JSON.as_ref()))
+}
+
+fn compute_etag(body: &[u8]) -> HeaderValue {
+    let digest = Sha256::digest(body);
+    let etag = format!("\"{}\"", URL_SAFE_NO_PAD.encode(&digest[..16]));
+
+    HeaderValue::from_str(&etag).expect("base64 is a valid header value")
+}
+
+// If-None-Match uses the weak comparison (RFC 9110).
+fn matches(header: &HeaderValue, etag: &HeaderValue) -> bool {
+    let Ok(header) = header.to_str() else {
+        return false;
+    };
+    let etag = etag.as_bytes();
+
+    header
+        .split(',')
+        .map(str::trim)
+        .any(|candidate| candidate == "*" || candidate.trim_start_matches("W/").as_bytes() == etag)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{
+        atomic::{AtomicI64, Ordering},
+        Arc,
+    };
+
+    use axum::{extract::State, middleware::from_fn, routing::get, Json, Router};
+    use tokio::net::TcpListener;
+
+    use super::*;
+
+    async fn spawn_resource() -> String {
+        let revision = Arc::new(AtomicI64::new(1));
+        let router = Router::new()
+            .route(
+                "/resource",
+                get(|State(revision): State<Arc<AtomicI64>>| async move {
+                    let revision = revision.load(Ordering::SeqCst);
+                    (
+                        [(ETAG, revision_etag(revision))],
+                        Json(serde_json::json!({})),
+                    )
+                })
+                .delete(
+                    |if_match: IfMatch, State(revision): State<Arc<AtomicI64>>| async move {
+                        let current = revision.load(Ordering::SeqCst);
+                        let expected = if_match.revisions().map_or(current, |revisions| {
+                            revisions
+                                .iter()
+                                .copied()
+                                .find(|r| *r == current)
+                                .unwrap_or(-1)
+                        });
+                        match revision.compare_exchange(
+                            expected,
+                            current + 1,
+                            Ordering::SeqCst,
+                            Ordering::SeqCst,
+                        ) {
+                            Ok(_) => StatusCode::NO_CONTENT,
+                            Err(_) => StatusCode::PRECONDITION_FAILED,
+                        }
+                    },
+                ),
+            )
+            .with_state(revision)
+            .layer(from_fn(conditional_requests));
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, router).await });
+
+        format!("http://{}/resource", address)
+    }
+
+    #[tokio::test]
+    async fn test_conditional_requests_layer() {
+        // Arrange
+        let url = spawn_resource().await;
+        let client = reqwest::Client::new();
+        let etag = client.get(&url).send().await.unwrap().headers()[ETAG].clone();
+
+        // Act
+        let not_modified = client
+            .get(&url)
+            .header(IF_NONE_MATCH, etag.clone())
+            .send()
+            .await
+            .unwrap();
+        let updated = client
+            .delete(&url)
+            .header(IF_MATCH, etag.clone())
+            .send()
+            .await
+            .unwrap();
+        let conflict = client
+            .delete(&url)
+            .header(IF_MATCH, etag.clone())
+            .send()
+            .await
+            .unwrap();
+        let modified = client
+            .get(&url)
+            .header(IF_NONE_MATCH, etag.clone())
+            .send()
+            .await
+            .unwrap();
+
+        // Assert
+        assert_eq!(not_modified.status(), StatusCode::NOT_MODIFIED);
+        assert_eq!(updated.status(), StatusCode::NO_CONTENT);
+        assert_eq!(conflict.status(), StatusCode::PRECONDITION_FAILED);
+        assert_eq!(modified.status(), StatusCode::OK);
+        assert_ne!(modified.headers()[ETAG], etag);
+    }
+
+    #[tokio::test]
+    async fn test_with_etag_skips_streamed_and_large_bodies() {
+        let json = |body: Body| {
+            Response::builder()
+                .header(CONTENT_TYPE, "application/json")
+                .body(body)
+                .unwrap()
+        };
+        let stream = futures::stream::iter([Ok::<_, Infallible>("{}")]);
+
+        let (_, small) = with_etag(json(Body::from("{}"))).await.unwrap();
+        let (_, streamed) = with_etag(json(Body::from_stream(stream))).await.unwrap();
+        let (_, large) = with_etag(json(Body::from(vec![b' '; MAX_HASHED_BODY_BYTES + 1])))
+            .await
+            .unwrap();
+
+        assert!(small.is_some());
+        assert!(streamed.is_none());
+        assert!(large.is_none());
+    }
+
+    #[test]
+    fn test_compute_etag_is_stable_and_quoted() {
+        let etag = compute_etag(b"{\"data\":1}");
+
+        assert_eq!(etag, compute_etag(b"{\"data\":1}"));
+        assert_ne!(etag, compute_etag(b"{\"data\":2}"));
+        assert!(etag.to_str().unwrap().starts_with('"'));
+        assert!(etag.to_str().unwrap().ends_with('"'));
+    }
+
+    #[test]
+    fn test_matches_lists_and_wildcards() {
+        let etag = HeaderValue::from_static("\"abc\"");
+
+        assert!(matches(
+            &HeaderValue::from_static("\"xyz\", \"abc\""),
+            &etag
+        ));
+        assert!(matches(&HeaderValue::from_static("*"), &etag));
+        assert!(!matches(&HeaderValue::from_static("\"xyz\""), &etag));
+    }
+
+    #[test]
+    fn test_matches_weak_validators() {
+        let etag = HeaderValue::from_static("\"abc\"");
+
+        assert!(matches(&HeaderValue::from_static("W/\"abc\""), &etag));
+    }
+
+    #[test]
+    fn test_parse_if_match_keeps_only_strong_revisions() {
+        let parse = |value: &'static str| parse_if_match(Some(&HeaderValue::from_static(value)));
+
+        assert_eq!(parse("\"3\", \"7\"").revisions(), Some(&[3, 7][..]));
+        assert_eq!(parse("W/\"3\", \"abc\"").revisions(), Some(&[][..]));
+        assert_eq!(parse("*").revisions(), None);
+        assert_eq!(parse_if_match(None).revisions(), None);
+    }
+}
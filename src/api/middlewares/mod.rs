@@ -1,4 +1,6 @@
+mod conditional;
 mod record_metrics;
 pub mod tracing;
 
+pub use conditional::*;
 pub use record_metrics::*;
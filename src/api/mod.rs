@@ -8,15 +8,18 @@ use axum::{
     Router,
 };
 use axum_tracing_opentelemetry::middleware::{OtelAxumLayer, OtelInResponseLayer};
-use lapin::Connection;
 use reqwest::StatusCode;
 use sqlx::{Pool, Postgres};
 use tokio::net::TcpListener;
+use tokio_util::sync::CancellationToken;
 use tower_http::trace::TraceLayer;
 use types::AppState;
 
-use crate::{config::Config, telemetry::Metrics};
+use crate::{config::Config, dispatcher::Dispatcher, quarantine::Quarantine, telemetry::Metrics};
 
+mod badge;
+mod export;
+mod import;
 mod middlewares;
 mod routes;
 pub mod types;
@@ -30,33 +33,29 @@ impl Api {
     pub async fn build(
         configuration: &Config,
         db_pool: Pool<Postgres>,
-        rabbitmq_connection: Arc<Connection>,
+        dispatcher: Dispatcher,
         integration_queues: HashMap<String, String>,
+        quarantine: Quarantine,
         metrics: Arc<Metrics>,
     ) -> Result<Self> {
-        let address = format!(
-            "{}:{}",
-            configuration.application.host, configuration.application.port
-        );
-        let listener = TcpListener::bind(&address)
-            .await
-            .context("Failed to bind address")?;
-        let port = listener
-            .local_addr()
-            .context("Failed to get local address")?
-            .port();
-
         let app_state = Arc::new(AppState {
             db_pool: db_pool.clone(),
-            rabbitmq_connection,
+            dispatcher,
             integration_queues,
-            exchange_name: configuration.rabbitmq.exchange_name.clone(),
+            quarantine,
+            consumer_queue: configuration.rabbitmq.queue_consumer.clone(),
         });
 
         let router = Router::new()
+            .merge(routes::badges::create_router(app_state.clone()))
             .merge(routes::jobs::create_router(app_state.clone()))
             .merge(routes::packages::create_router(app_state.clone()))
+            .merge(routes::projects::create_router(app_state.clone()))
+            .merge(routes::quarantine::create_router(app_state.clone()))
+            .merge(routes::registries::create_router(app_state.clone()))
+            .merge(routes::watchlists::create_router(app_state.clone()))
             .merge(routes::openapi::create_router())
+            .layer(from_fn(middlewares::conditional_requests))
             .layer(TraceLayer::new_for_http())
             .layer(from_fn(middlewares::tracing::attach_trace_id))
             .layer(from_fn_with_state(
@@ -64,8 +63,31 @@ impl Api {
                 middlewares::record_metrics,
             ))
             .layer(OtelInResponseLayer)
-            .layer(OtelAxumLayer::default())
-            .merge(routes::metrics::create_router(metrics.clone()))
+            .layer(OtelAxumLayer::default());
+
+        Self::serve(configuration, router, metrics).await
+    }
+
+    // Worker-only processes still expose health and metrics for probes and scraping.
+    pub async fn build_operational(configuration: &Config, metrics: Arc<Metrics>) -> Result<Self> {
+        Self::serve(configuration, Router::new(), metrics).await
+    }
+
+    async fn serve(configuration: &Config, router: Router, metrics: Arc<Metrics>) -> Result<Self> {
+        let address = format!(
+            "{}:{}",
+            configuration.application.host, configuration.application.port
+        );
+        let listener = TcpListener::bind(&address)
+            .await
+            .context("Failed to bind address")?;
+        let port = listener
+            .local_addr()
+            .context("Failed to get local address")?
+            .port();
+
+        let router = router
+            .merge(routes::metrics::create_router(metrics))
             .route("/health", get(health_check))
             .fallback(not_found);
 
@@ -74,8 +96,11 @@ impl Api {
         Ok(Self { port, server })
     }
 
-    pub async fn run_until_stopped(self) -> Result<()> {
-        self.server.await.context("Server failed to start")
+    pub async fn run_until_stopped(self, shutdown: CancellationToken) -> Result<()> {
+        self.server
+            .with_graceful_shutdown(shutdown.cancelled_owned())
+            .await
+            .context("Server failed to start")
     }
 
     pub fn port(&self) -> u16 {
@@ -0,0 +1,56 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, State},
+    response::IntoResponse,
+    routing::get,
+    Router,
+};
+use tracing::instrument;
+
+use crate::{
+    api::{badge::Badge, types::AppState},
+    db,
+    error::Error,
+};
+
+pub fn create_router(app_state: Arc<AppState>) -> Router {
+    Router::new()
+        .route(
+            "/badges/:registry/:name/version.svg",
+            get(get_version_badge),
+        )
+        .route(
+            "/badges/:registry/:name/downloads.svg",
+            get(get_downloads_badge),
+        )
+        .with_state(app_state)
+}
+
+#[instrument(name = "get_version_badge", skip(app_state))]
+pub async fn get_version_badge(
+    Path((registry, name)): Path<(String, String)>,
+    State(app_state): State<Arc<AppState>>,
+) -> Result<impl IntoResponse, Error> {
+    let mut conn = app_state.db_pool.acquire().await?;
+    let badge = match db::get_package(&mut conn, &registry, &name).await? {
+        Some(package) => Badge::version(registry, &package.version, package.deprecated),
+        None => Badge::unknown(registry),
+    };
+
+    Ok(badge)
+}
+
+#[instrument(name = "get_downloads_badge", skip(app_state))]
+pub async fn get_downloads_badge(
+    Path((registry, name)): Path<(String, String)>,
+    State(app_state): State<Arc<AppState>>,
+) -> Result<impl IntoResponse, Error> {
+    let mut conn = app_state.db_pool.acquire().await?;
+    let badge = match db::get_package(&mut conn, &registry, &name).await? {
+        Some(package) => Badge::downloads(package.downloads),
+        None => Badge::unknown("downloads"),
+    };
+
+    Ok(badge)
+}
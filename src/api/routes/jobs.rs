@@ -7,26 +7,28 @@ use axum::{
     routing::{get, post},
     Json, Router,
 };
-use axum_tracing_opentelemetry::tracing_opentelemetry_instrumentation_sdk::find_current_trace_id;
-use chrono::Utc;
 use http::StatusCode;
 use serde::Deserialize;
 use tracing::instrument;
 use uuid::Uuid;
 
 use crate::{
-    api::types::{ApiResponse, ApiResponseList, AppState, Limit, PaginationQuery},
+    api::{
+        export::stream_export,
+        types::{
+            ApiResponse, ApiResponseList, AppState, DataFormat, Limit, Order, PaginationQuery,
+        },
+    },
     db,
     error::Error,
-    models::job::{Job, JobStatus},
-    services::rabbitmq,
-    types::JobMessage,
+    models::job::JobSource,
 };
 
 pub fn create_router(app_state: Arc<AppState>) -> Router {
     Router::new()
         .route("/jobs", post(create_job))
         .route("/jobs", get(get_jobs))
+        .route("/jobs/export", get(export_jobs))
         .route("/jobs/:id", get(get_job_by_id))
         .with_state(app_state)
 }
@@ -35,6 +37,8 @@ pub fn create_router(app_state: Arc<AppState>) -> Router {
 pub struct CreateJobPayload {
     pub registry: String,
     pub package_name: String,
+    #[serde(default)]
+    pub force: bool,
 }
 
 #[instrument(name = "create_job", skip(app_state))]
@@ -42,42 +46,15 @@ pub async fn create_job(
     State(app_state): State<Arc<AppState>>,
     Json(payload): Json<CreateJobPayload>,
 ) -> Result<impl IntoResponse, Error> {
-    let id = Uuid::now_v7();
-    let registry = payload.registry;
-    let package_name = payload.package_name;
-    let trace_id = find_current_trace_id();
-
-    let mut transaction = app_state.db_pool.begin().await?;
-
-    let routing_key = app_state
-        .integration_queues
-        .get(&registry)
-        .context("Registry not found")?
-        .clone();
-
-    let job = db::insert_job(
-        &mut transaction,
-        Job {
-            id,
-            registry,
-            package_name,
-            status: JobStatus::Processing,
-            trace_id: trace_id.clone(),
-            created_at: Utc::now(),
-        },
-    )
-    .await?;
-
-    transaction.commit().await?;
-
-    let message = JobMessage {
-        job_id: job.id,
-        registry: job.registry.clone(),
-        package_name: job.package_name.clone(),
-    };
-    let channel = app_state.rabbitmq_connection.create_channel().await?;
-
-    rabbitmq::publish_message(&channel, &app_state.exchange_name, &routing_key, &message).await?;
+    let job = app_state
+        .dispatcher
+        .dispatch(
+            payload.registry,
+            payload.package_name,
+            payload.force,
+            JobSource::Api,
+        )
+        .await?;
 
     Ok((StatusCode::CREATED, Json(ApiResponse::new(job))))
 }
@@ -97,6 +74,28 @@ pub async fn get_jobs(
     Ok(Json(ApiResponseList::new(jobs, limit)))
 }
 
+#[derive(Debug, Deserialize)]
+pub struct ExportJobsQuery {
+    #[serde(default)]
+    pub format: DataFormat,
+    #[serde(default)]
+    pub order: Order,
+}
+
+#[instrument(name = "export_jobs", skip(app_state))]
+pub async fn export_jobs(
+    Query(query): Query<ExportJobsQuery>,
+    State(app_state): State<Arc<AppState>>,
+) -> Result<impl IntoResponse, Error> {
+    let order = query.order.into();
+    let db_pool = app_state.db_pool.clone();
+
+    Ok(stream_export(query.format, "jobs", |sink| async move {
+        let mut conn = db_pool.acquire().await?;
+        sink.send_all(db::stream_jobs(&mut conn, order)).await
+    }))
+}
+
 #[instrument(name = "get_job_by_id", skip(app_state))]
 pub async fn get_job_by_id(
     Path(id): Path<String>,
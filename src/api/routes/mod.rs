@@ -1,4 +1,9 @@
+pub mod badges;
 pub mod jobs;
 pub mod metrics;
 pub mod openapi;
 pub mod packages;
+pub mod projects;
+pub mod quarantine;
+pub mod registries;
+pub mod watchlists;
@@ -1,36 +1,339 @@
 use std::sync::Arc;
 
 use axum::{
-    extract::{Query, State},
+    body::Body,
+    extract::{Path, Query, State},
     response::IntoResponse,
-    routing::get,
+    routing::{get, post},
     Json, Router,
 };
+use chrono::Utc;
+use http::header::ETAG;
+use serde::Deserialize;
 use tracing::instrument;
+use uuid::Uuid;
 
 use crate::{
-    api::types::{ApiResponseList, AppState, Limit, PaginationQuery},
-    db,
+    api::{
+        export::stream_export,
+        import,
+        middlewares::{revision_etag, IfMatch},
+        types::{
+            ApiResponse, ApiResponseList, AppState, DataFormat, GrowthBy, Limit, Order,
+            PackageSort, TrendingWindow,
+        },
+    },
+    db::{self, PackageCursor, PackageFilter},
     error::Error,
 };
 
 pub fn create_router(app_state: Arc<AppState>) -> Router {
     Router::new()
         .route("/packages", get(get_packages))
+        .route("/packages/export", get(export_packages))
+        .route("/packages/import", post(import_packages))
+        .route("/packages/trending", get(get_trending_packages))
+        .route(
+            "/packages/:registry/:name",
+            get(get_package).delete(delete_package),
+        )
+        .route(
+            "/packages/:registry/:name/dependencies",
+            get(get_package_dependencies),
+        )
+        .route(
+            "/packages/:registry/:name/dependents",
+            get(get_package_dependents),
+        )
         .with_state(app_state)
 }
 
+#[derive(Debug, Deserialize)]
+pub struct PackagesQuery {
+    pub limit: Option<u64>,
+    pub after: Option<String>,
+    #[serde(default)]
+    pub order: Order,
+    #[serde(default)]
+    pub sort: PackageSort,
+    pub registry: Option<String>,
+    pub min_downloads: Option<i64>,
+    pub max_downloads: Option<i64>,
+    #[serde(default)]
+    pub include_deleted: bool,
+}
+
 #[instrument(name = "get_packages", skip(app_state))]
 pub async fn get_packages(
-    Query(query): Query<PaginationQuery>,
+    Query(query): Query<PackagesQuery>,
     State(app_state): State<Arc<AppState>>,
 ) -> Result<impl IntoResponse, Error> {
     let limit: Limit = query.limit.unwrap_or(100).try_into()?;
-    let after = query.after;
+    let sort: db::PackageSort = query.sort.into();
+    let after = query
+        .after
+        .as_deref()
+        .map(|after| parse_cursor(after, sort))
+        .transpose()?;
     let order = query.order.into();
+    let filter = package_filter(
+        query.registry,
+        query.min_downloads,
+        query.max_downloads,
+        query.include_deleted,
+    )?;
 
     let mut conn = app_state.db_pool.acquire().await?;
-    let packages = db::get_packages(&mut conn, limit.as_u64() + 1, after, order).await?;
+    let packages =
+        db::get_packages(&mut conn, &filter, limit.as_u64() + 1, sort, after, order).await?;
+
+    Ok(Json(ApiResponseList::sorted(packages, limit, sort)))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ExportPackagesQuery {
+    #[serde(default)]
+    pub format: DataFormat,
+    #[serde(default)]
+    pub order: Order,
+    pub registry: Option<String>,
+    pub min_downloads: Option<i64>,
+    pub max_downloads: Option<i64>,
+    #[serde(default)]
+    pub include_deleted: bool,
+}
+
+#[instrument(name = "export_packages", skip(app_state))]
+pub async fn export_packages(
+    Query(query): Query<ExportPackagesQuery>,
+    State(app_state): State<Arc<AppState>>,
+) -> Result<impl IntoResponse, Error> {
+    let order = query.order.into();
+    let filter = package_filter(
+        query.registry,
+        query.min_downloads,
+        query.max_downloads,
+        query.include_deleted,
+    )?;
+    let db_pool = app_state.db_pool.clone();
+
+    Ok(stream_export(query.format, "packages", |sink| async move {
+        let mut conn = db_pool.acquire().await?;
+        sink.send_all(db::stream_packages(&mut conn, &filter, order))
+            .await
+    }))
+}
+
+const DEFAULT_TRENDING_MIN_DOWNLOADS: i64 = 1000;
+
+#[derive(Debug, Deserialize)]
+pub struct TrendingPackagesQuery {
+    pub limit: Option<u64>,
+    pub registry: Option<String>,
+    #[serde(default)]
+    pub window: TrendingWindow,
+    #[serde(default)]
+    pub by: GrowthBy,
+    pub min_downloads: Option<i64>,
+}
+
+#[instrument(name = "get_trending_packages", skip(app_state))]
+pub async fn get_trending_packages(
+    Query(query): Query<TrendingPackagesQuery>,
+    State(app_state): State<Arc<AppState>>,
+) -> Result<impl IntoResponse, Error> {
+    let limit: Limit = query.limit.unwrap_or(10).try_into()?;
+    let min_downloads = query
+        .min_downloads
+        .unwrap_or(DEFAULT_TRENDING_MIN_DOWNLOADS);
+    if min_downloads < 0 {
+        return Err(Error::InvalidInput(
+            "min_downloads must not be negative".to_string(),
+        ));
+    }
+
+    let mut conn = app_state.db_pool.acquire().await?;
+    let packages = db::get_trending_packages(
+        &mut conn,
+        query.registry.as_deref(),
+        query.window.into(),
+        query.by.into(),
+        min_downloads,
+        limit.as_u64(),
+    )
+    .await?;
+
+    Ok(Json(ApiResponse::new(packages)))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ImportPackagesQuery {
+    #[serde(default)]
+    pub format: DataFormat,
+}
+
+// TODO: restrict to admin credentials once the API has authentication.
+#[instrument(name = "import_packages", skip(app_state, body))]
+pub async fn import_packages(
+    Query(query): Query<ImportPackagesQuery>,
+    State(app_state): State<Arc<AppState>>,
+    body: Body,
+) -> Result<impl IntoResponse, Error> {
+    let report =
+        import::import_packages(&app_state.db_pool, query.format, body.into_data_stream()).await?;
+
+    Ok(Json(ApiResponse::new(report)))
+}
+
+fn package_filter(
+    registry: Option<String>,
+    min_downloads: Option<i64>,
+    max_downloads: Option<i64>,
+    include_deleted: bool,
+) -> Result<PackageFilter, Error> {
+    if let (Some(min), Some(max)) = (min_downloads, max_downloads) {
+        if min > max {
+            return Err(Error::InvalidInput(
+                "min_downloads must be less than or equal to max_downloads".to_string(),
+            ));
+        }
+    }
+
+    Ok(PackageFilter {
+        registry,
+        min_downloads,
+        max_downloads,
+        include_deleted,
+    })
+}
+
+#[instrument(name = "get_package", skip(app_state))]
+pub async fn get_package(
+    Path((registry, name)): Path<(String, String)>,
+    State(app_state): State<Arc<AppState>>,
+) -> Result<impl IntoResponse, Error> {
+    let mut conn = app_state.db_pool.acquire().await?;
+    let Some(package) = db::get_package(&mut conn, &registry, &name).await? else {
+        return Err(Error::NotFound("Not found".to_string()));
+    };
+
+    Ok((
+        [(ETAG, revision_etag(package.revision))],
+        Json(ApiResponse::new(package)),
+    ))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DeletePackagePayload {
+    pub reason: String,
+}
+
+#[instrument(name = "delete_package", skip(app_state))]
+pub async fn delete_package(
+    Path((registry, name)): Path<(String, String)>,
+    if_match: IfMatch,
+    State(app_state): State<Arc<AppState>>,
+    Json(payload): Json<DeletePackagePayload>,
+) -> Result<impl IntoResponse, Error> {
+    let reason = payload.reason.trim();
+    if reason.is_empty() {
+        return Err(Error::InvalidInput("Reason must not be empty".to_string()));
+    }
+
+    let mut conn = app_state.db_pool.acquire().await?;
+    let deleted = db::delete_package(
+        &mut conn,
+        &registry,
+        &name,
+        reason,
+        Utc::now(),
+        if_match.revisions(),
+    )
+    .await?;
+    let Some(package) = deleted else {
+        return Err(match db::get_package(&mut conn, &registry, &name).await? {
+            Some(package) => Error::PreconditionFailed(revision_etag(package.revision)),
+            None => Error::NotFound("Not found".to_string()),
+        });
+    };
+
+    Ok(Json(ApiResponse::new(package)))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DependencyQuery {
+    pub depth: Option<i32>,
+}
+
+impl DependencyQuery {
+    fn depth(&self) -> Result<i32, Error> {
+        let depth = self.depth.unwrap_or(1);
+        if !(1..=MAX_DEPENDENCY_DEPTH).contains(&depth) {
+            return Err(Error::InvalidInput(format!(
+                "Depth must be between 1 and {}",
+                MAX_DEPENDENCY_DEPTH
+            )));
+        }
+
+        Ok(depth)
+    }
+}
+
+const MAX_DEPENDENCY_DEPTH: i32 = 10;
+
+#[instrument(name = "get_package_dependencies", skip(app_state))]
+pub async fn get_package_dependencies(
+    Path((registry, name)): Path<(String, String)>,
+    Query(query): Query<DependencyQuery>,
+    State(app_state): State<Arc<AppState>>,
+) -> Result<impl IntoResponse, Error> {
+    let depth = query.depth()?;
+
+    let mut conn = app_state.db_pool.acquire().await?;
+    if db::get_package(&mut conn, &registry, &name)
+        .await?
+        .is_none()
+    {
+        return Err(Error::NotFound("Not found".to_string()));
+    }
+    let dependencies = db::get_dependencies(&mut conn, &registry, &name, depth).await?;
+
+    Ok(Json(ApiResponse::new(dependencies)))
+}
+
+#[instrument(name = "get_package_dependents", skip(app_state))]
+pub async fn get_package_dependents(
+    Path((registry, name)): Path<(String, String)>,
+    Query(query): Query<DependencyQuery>,
+    State(app_state): State<Arc<AppState>>,
+) -> Result<impl IntoResponse, Error> {
+    let depth = query.depth()?;
+
+    let mut conn = app_state.db_pool.acquire().await?;
+    if db::get_package(&mut conn, &registry, &name)
+        .await?
+        .is_none()
+    {
+        return Err(Error::NotFound("Not found".to_string()));
+    }
+    let dependents = db::get_dependents(&mut conn, &registry, &name, depth).await?;
+
+    Ok(Json(ApiResponse::new(dependents)))
+}
+
+fn parse_cursor(after: &str, sort: db::PackageSort) -> Result<PackageCursor, Error> {
+    // Cursors issued before sorting existed were bare package ids.
+    let cursor = match Uuid::parse_str(after) {
+        Ok(id) => PackageCursor::Id(id),
+        Err(_) => PackageCursor::decode(after)
+            .ok_or_else(|| Error::InvalidInput("Invalid cursor".to_string()))?,
+    };
+
+    if cursor.sort() != sort {
+        return Err(Error::InvalidInput(
+            "Cursor does not match the requested sort".to_string(),
+        ));
+    }
 
-    Ok(Json(ApiResponseList::new(packages, limit)))
+    Ok(cursor)
 }
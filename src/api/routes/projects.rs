@@ -0,0 +1,109 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, State},
+    response::IntoResponse,
+    routing::{get, post},
+    Json, Router,
+};
+use chrono::Utc;
+use http::StatusCode;
+use serde::Deserialize;
+use tracing::instrument;
+use uuid::Uuid;
+
+use crate::{
+    api::types::{ApiResponse, AppState},
+    db,
+    error::Error,
+    models::project::{Project, ProjectSummary},
+};
+
+pub fn create_router(app_state: Arc<AppState>) -> Router {
+    Router::new()
+        .route("/projects", post(create_project))
+        .route("/projects/:id", get(get_project))
+        .route("/projects/:id/packages", post(link_package))
+        .with_state(app_state)
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateProjectPayload {
+    pub name: String,
+    pub repository_url: Option<String>,
+}
+
+#[instrument(name = "create_project", skip(app_state))]
+pub async fn create_project(
+    State(app_state): State<Arc<AppState>>,
+    Json(payload): Json<CreateProjectPayload>,
+) -> Result<impl IntoResponse, Error> {
+    if payload.name.trim().is_empty() {
+        return Err(Error::InvalidInput("Name must not be empty".to_string()));
+    }
+
+    let mut transaction = app_state.db_pool.begin().await?;
+    if let Some(repository_url) = payload.repository_url.as_deref() {
+        if db::get_project_by_repository_url(&mut transaction, repository_url)
+            .await?
+            .is_some()
+        {
+            return Err(Error::InvalidInput(
+                "A project already exists for this repository".to_string(),
+            ));
+        }
+    }
+
+    let project = Project {
+        id: Uuid::now_v7(),
+        name: payload.name,
+        repository_url: payload.repository_url,
+        created_at: Utc::now(),
+    };
+    let project = db::insert_project(&mut transaction, project).await?;
+    if let Some(repository_url) = project.repository_url.as_deref() {
+        db::link_packages_by_repository(&mut transaction, project.id, repository_url).await?;
+    }
+    transaction.commit().await?;
+
+    Ok((StatusCode::CREATED, Json(ApiResponse::new(project))))
+}
+
+#[instrument(name = "get_project", skip(app_state))]
+pub async fn get_project(
+    Path(id): Path<Uuid>,
+    State(app_state): State<Arc<AppState>>,
+) -> Result<impl IntoResponse, Error> {
+    let mut conn = app_state.db_pool.acquire().await?;
+    let Some(project) = db::get_project(&mut conn, id).await? else {
+        return Err(Error::NotFound("Not found".to_string()));
+    };
+    let packages = db::get_project_packages(&mut conn, project.id).await?;
+
+    Ok(Json(ApiResponse::new(ProjectSummary::new(
+        project, packages,
+    ))))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct LinkPackagePayload {
+    pub registry: String,
+    pub name: String,
+}
+
+#[instrument(name = "link_package", skip(app_state))]
+pub async fn link_package(
+    Path(id): Path<Uuid>,
+    State(app_state): State<Arc<AppState>>,
+    Json(payload): Json<LinkPackagePayload>,
+) -> Result<impl IntoResponse, Error> {
+    let mut conn = app_state.db_pool.acquire().await?;
+    if db::get_project(&mut conn, id).await?.is_none() {
+        return Err(Error::NotFound("Project not found".to_string()));
+    }
+    let package = db::link_package(&mut conn, id, &payload.registry, &payload.name)
+        .await?
+        .ok_or_else(|| Error::NotFound("Package not found".to_string()))?;
+
+    Ok(Json(ApiResponse::new(package)))
+}
@@ -0,0 +1,140 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+use axum::{
+    body::Bytes,
+    extract::{Path, Query, State},
+    response::IntoResponse,
+    routing::{get, post},
+    Json, Router,
+};
+use chrono::Utc;
+use http::{header, StatusCode};
+use tracing::instrument;
+use uuid::Uuid;
+
+use crate::{
+    api::types::{ApiResponse, ApiResponseList, AppState, Limit, PaginationQuery},
+    db,
+    error::Error,
+    models::quarantine::QuarantinedMessage,
+    types::JobMessage,
+};
+
+pub fn create_router(app_state: Arc<AppState>) -> Router {
+    Router::new()
+        .route("/quarantine", get(get_quarantined_messages))
+        .route("/quarantine/:id", get(get_quarantined_message))
+        .route("/quarantine/:id/payload", get(get_quarantined_payload))
+        .route(
+            "/quarantine/:id/reinject",
+            post(reinject_quarantined_message),
+        )
+        .with_state(app_state)
+}
+
+#[instrument(name = "get_quarantined_messages", skip(app_state))]
+pub async fn get_quarantined_messages(
+    Query(query): Query<PaginationQuery>,
+    State(app_state): State<Arc<AppState>>,
+) -> Result<impl IntoResponse, Error> {
+    let limit: Limit = query.limit.unwrap_or(100).try_into()?;
+
+    let mut conn = app_state.db_pool.acquire().await?;
+    let messages = db::get_quarantined_messages(
+        &mut conn,
+        limit.as_u64() + 1,
+        query.after,
+        query.order.into(),
+    )
+    .await?;
+
+    Ok(Json(ApiResponseList::new(messages, limit)))
+}
+
+#[instrument(name = "get_quarantined_message", skip(app_state))]
+pub async fn get_quarantined_message(
+    Path(id): Path<Uuid>,
+    State(app_state): State<Arc<AppState>>,
+) -> Result<impl IntoResponse, Error> {
+    let message = find_quarantined_message(&app_state, id).await?;
+
+    Ok(Json(ApiResponse::new(message)))
+}
+
+#[instrument(name = "get_quarantined_payload", skip(app_state))]
+pub async fn get_quarantined_payload(
+    Path(id): Path<Uuid>,
+    State(app_state): State<Arc<AppState>>,
+) -> Result<impl IntoResponse, Error> {
+    let message = find_quarantined_message(&app_state, id).await?;
+    let payload = app_state.quarantine.payload(&message).await?;
+
+    Ok((
+        [(
+            header::CONTENT_TYPE,
+            mime::APPLICATION_OCTET_STREAM.as_ref(),
+        )],
+        payload,
+    ))
+}
+
+// A corrected message can be sent in the body; without one the stored payload is replayed,
+// e.g. once the worker learned to read it. The message is marked before it is published and
+// the mark is rolled back if publishing fails, so it is re-injected at most once.
+#[instrument(name = "reinject_quarantined_message", skip(app_state, body))]
+pub async fn reinject_quarantined_message(
+    Path(id): Path<Uuid>,
+    State(app_state): State<Arc<AppState>>,
+    body: Bytes,
+) -> Result<impl IntoResponse, Error> {
+    let message = find_quarantined_message(&app_state, id).await?;
+    if message.reinjected_at.is_some() {
+        return Err(already_reinjected());
+    }
+    let payload = if body.is_empty() {
+        app_state.quarantine.payload(&message).await?
+    } else {
+        body
+    };
+
+    let job_message = serde_json::from_slice::<JobMessage>(&payload)
+        .map_err(|error| Error::InvalidInput(format!("Invalid job message: {}", error)))?;
+
+    let mut conn = app_state.db_pool.acquire().await?;
+    if db::get_job_by_id(&mut conn, job_message.job_id)
+        .await?
+        .is_none()
+    {
+        return Err(Error::InvalidInput(format!(
+            "Job {} not found",
+            job_message.job_id
+        )));
+    }
+
+    let mut transaction = app_state.db_pool.begin().await?;
+    let message = db::mark_quarantined_message_reinjected(&mut transaction, message.id, Utc::now())
+        .await?
+        .ok_or_else(already_reinjected)?;
+    app_state
+        .dispatcher
+        .publish(&app_state.consumer_queue, &job_message)
+        .await?;
+    transaction.commit().await?;
+
+    Ok((StatusCode::ACCEPTED, Json(ApiResponse::new(message))))
+}
+
+fn already_reinjected() -> Error {
+    Error::Conflict("Quarantined message was already re-injected".to_string())
+}
+
+async fn find_quarantined_message(
+    app_state: &AppState,
+    id: Uuid,
+) -> Result<QuarantinedMessage, Error> {
+    let mut conn = app_state.db_pool.acquire().await?;
+    db::get_quarantined_message(&mut conn, id)
+        .await?
+        .ok_or_else(|| Error::NotFound("Quarantined message not found".to_string()))
+}
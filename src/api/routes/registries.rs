@@ -0,0 +1,48 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, Query, State},
+    response::IntoResponse,
+    routing::get,
+    Json, Router,
+};
+use serde::Deserialize;
+use tracing::instrument;
+
+use crate::{
+    api::types::{ApiResponse, AppState, Limit, RankBy},
+    db,
+    error::Error,
+};
+
+pub fn create_router(app_state: Arc<AppState>) -> Router {
+    Router::new()
+        .route("/registries/:registry/top", get(get_top_packages))
+        .with_state(app_state)
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TopPackagesQuery {
+    pub limit: Option<u64>,
+    #[serde(default)]
+    pub by: RankBy,
+}
+
+#[instrument(name = "get_top_packages", skip(app_state))]
+pub async fn get_top_packages(
+    Path(registry): Path<String>,
+    Query(query): Query<TopPackagesQuery>,
+    State(app_state): State<Arc<AppState>>,
+) -> Result<impl IntoResponse, Error> {
+    if !app_state.integration_queues.contains_key(&registry) {
+        return Err(Error::NotFound("Registry not found".to_string()));
+    }
+
+    let limit: Limit = query.limit.unwrap_or(10).try_into()?;
+
+    let mut conn = app_state.db_pool.acquire().await?;
+    let packages =
+        db::get_top_packages(&mut conn, &registry, query.by.into(), limit.as_u64()).await?;
+
+    Ok(Json(ApiResponse::new(packages)))
+}
@@ -0,0 +1,243 @@
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, Query, State},
+    response::IntoResponse,
+    routing::{delete, get, post},
+    Json, Router,
+};
+use chrono::Utc;
+use http::{header::ETAG, StatusCode};
+use serde::Deserialize;
+use tracing::instrument;
+use uuid::Uuid;
+
+use crate::{
+    api::{
+        middlewares::{revision_etag, IfMatch},
+        types::{ApiResponse, AppState, Limit},
+    },
+    db,
+    error::Error,
+    models::watchlist::{
+        NotificationChannel, WatchedPackage, Watchlist, WatchlistDetails, WatchlistTarget,
+    },
+};
+
+pub fn create_router(app_state: Arc<AppState>) -> Router {
+    Router::new()
+        .route("/watchlists", post(create_watchlist))
+        .route(
+            "/watchlists/:id",
+            get(get_watchlist).delete(delete_watchlist),
+        )
+        .route("/watchlists/:id/packages", post(add_packages))
+        .route(
+            "/watchlists/:id/packages/:registry/:name",
+            delete(remove_package),
+        )
+        .route(
+            "/watchlists/:id/notifications",
+            get(get_watchlist_notifications),
+        )
+        .with_state(app_state)
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateWatchlistPayload {
+    pub name: String,
+    #[serde(default)]
+    pub packages: Vec<WatchedPackage>,
+    #[serde(default)]
+    pub targets: Vec<TargetPayload>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TargetPayload {
+    pub channel: NotificationChannel,
+    pub target: String,
+}
+
+impl TryFrom<TargetPayload> for WatchlistTarget {
+    type Error = Error;
+
+    fn try_from(payload: TargetPayload) -> Result<Self, Self::Error> {
+        let target = payload.target.trim().to_string();
+        let valid = match payload.channel {
+            NotificationChannel::Webhook => {
+                url::Url::parse(&target).is_ok_and(|url| matches!(url.scheme(), "http" | "https"))
+            }
+            NotificationChannel::Email => target.parse::<lettre::Address>().is_ok(),
+        };
+        if !valid {
+            return Err(Error::InvalidInput(format!(
+                "Invalid {} target: {}",
+                payload.channel, target
+            )));
+        }
+
+        Ok(WatchlistTarget {
+            id: Uuid::now_v7(),
+            channel: payload.channel,
+            target,
+        })
+    }
+}
+
+fn validate_packages(packages: &[WatchedPackage]) -> Result<(), Error> {
+    if packages
+        .iter()
+        .any(|package| package.registry.trim().is_empty() || package.name.trim().is_empty())
+    {
+        return Err(Error::InvalidInput(
+            "Package registry and name must not be empty".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+#[instrument(name = "create_watchlist", skip(app_state))]
+pub async fn create_watchlist(
+    State(app_state): State<Arc<AppState>>,
+    Json(payload): Json<CreateWatchlistPayload>,
+) -> Result<impl IntoResponse, Error> {
+    if payload.name.trim().is_empty() {
+        return Err(Error::InvalidInput("Name must not be empty".to_string()));
+    }
+    validate_packages(&payload.packages)?;
+    let targets = payload
+        .targets
+        .into_iter()
+        .map(WatchlistTarget::try_from)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut transaction = app_state.db_pool.begin().await?;
+    let watchlist = Watchlist {
+        id: Uuid::now_v7(),
+        name: payload.name,
+        created_at: Utc::now(),
+        revision: 1,
+    };
+    let watchlist = db::insert_watchlist(&mut transaction, watchlist).await?;
+    db::insert_watched_packages(&mut transaction, watchlist.id, &payload.packages, None).await?;
+    db::insert_watchlist_targets(&mut transaction, watchlist.id, &targets).await?;
+    let packages = db::get_watched_packages(&mut transaction, watchlist.id).await?;
+    let targets = db::get_watchlist_targets(&mut transaction, watchlist.id).await?;
+    transaction.commit().await?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(ApiResponse::new(WatchlistDetails {
+            watchlist,
+            packages,
+            targets,
+        })),
+    ))
+}
+
+#[instrument(name = "get_watchlist", skip(app_state))]
+pub async fn get_watchlist(
+    Path(id): Path<Uuid>,
+    State(app_state): State<Arc<AppState>>,
+) -> Result<impl IntoResponse, Error> {
+    let mut conn = app_state.db_pool.acquire().await?;
+    let Some(watchlist) = db::get_watchlist(&mut conn, id).await? else {
+        return Err(Error::NotFound("Not found".to_string()));
+    };
+    let packages = db::get_watched_packages(&mut conn, watchlist.id).await?;
+    let targets = db::get_watchlist_targets(&mut conn, watchlist.id).await?;
+
+    Ok((
+        [(ETAG, revision_etag(watchlist.revision))],
+        Json(ApiResponse::new(WatchlistDetails {
+            watchlist,
+            packages,
+            targets,
+        })),
+    ))
+}
+
+#[instrument(name = "delete_watchlist", skip(app_state))]
+pub async fn delete_watchlist(
+    Path(id): Path<Uuid>,
+    if_match: IfMatch,
+    State(app_state): State<Arc<AppState>>,
+) -> Result<impl IntoResponse, Error> {
+    let mut conn = app_state.db_pool.acquire().await?;
+    if !db::delete_watchlist(&mut conn, id, if_match.revisions()).await? {
+        return Err(match db::get_watchlist(&mut conn, id).await? {
+            Some(watchlist) => Error::PreconditionFailed(revision_etag(watchlist.revision)),
+            None => Error::NotFound("Not found".to_string()),
+        });
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AddPackagesPayload {
+    pub packages: Vec<WatchedPackage>,
+}
+
+#[instrument(name = "add_watchlist_packages", skip(app_state))]
+pub async fn add_packages(
+    Path(id): Path<Uuid>,
+    if_match: IfMatch,
+    State(app_state): State<Arc<AppState>>,
+    Json(payload): Json<AddPackagesPayload>,
+) -> Result<impl IntoResponse, Error> {
+    validate_packages(&payload.packages)?;
+
+    let mut conn = app_state.db_pool.acquire().await?;
+    if !db::insert_watched_packages(&mut conn, id, &payload.packages, if_match.revisions()).await? {
+        return Err(match db::get_watchlist(&mut conn, id).await? {
+            Some(watchlist) => Error::PreconditionFailed(revision_etag(watchlist.revision)),
+            None => Error::NotFound("Watchlist not found".to_string()),
+        });
+    }
+    let packages = db::get_watched_packages(&mut conn, id).await?;
+
+    Ok(Json(ApiResponse::new(packages)))
+}
+
+#[instrument(name = "remove_watchlist_package", skip(app_state))]
+pub async fn remove_package(
+    Path((id, registry, name)): Path<(Uuid, String, String)>,
+    if_match: IfMatch,
+    State(app_state): State<Arc<AppState>>,
+) -> Result<impl IntoResponse, Error> {
+    let mut conn = app_state.db_pool.acquire().await?;
+    if !db::delete_watched_package(&mut conn, id, &registry, &name, if_match.revisions()).await? {
+        return Err(match db::get_watchlist(&mut conn, id).await? {
+            Some(watchlist) if !if_match.allows(watchlist.revision) => {
+                Error::PreconditionFailed(revision_etag(watchlist.revision))
+            }
+            _ => Error::NotFound("Not found".to_string()),
+        });
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[derive(Debug, Deserialize)]
+pub struct NotificationsQuery {
+    pub limit: Option<u64>,
+}
+
+#[instrument(name = "get_watchlist_notifications", skip(app_state))]
+pub async fn get_watchlist_notifications(
+    Path(id): Path<Uuid>,
+    Query(query): Query<NotificationsQuery>,
+    State(app_state): State<Arc<AppState>>,
+) -> Result<impl IntoResponse, Error> {
+    let limit: Limit = query.limit.unwrap_or(100).try_into()?;
+
+    let mut conn = app_state.db_pool.acquire().await?;
+    if db::get_watchlist(&mut conn, id).await?.is_none() {
+        return Err(Error::NotFound("Watchlist not found".to_string()));
+    }
+    let notifications = db::get_watchlist_notifications(&mut conn, id, limit.as_u64()).await?;
+
+    Ok(Json(ApiResponse::new(notifications)))
+}
@@ -1,11 +1,10 @@
-use std::{collections::HashMap, sync::Arc};
+use std::collections::HashMap;
 
-use lapin::Connection;
 use serde::{Deserialize, Serialize};
 use sqlx::{Pool, Postgres};
 use uuid::Uuid;
 
-use crate::{db, types::Cursor};
+use crate::{db, dispatcher::Dispatcher, quarantine::Quarantine, types::Cursor};
 
 #[derive(Debug, Serialize)]
 pub struct ApiResponseList<T> {
@@ -15,9 +14,18 @@ pub struct ApiResponseList<T> {
 
 impl<T> ApiResponseList<T>
 where
-    T: Serialize + Cursor,
+    T: Serialize + Cursor<Sort = ()>,
 {
     pub fn new(items: Vec<T>, limit: Limit) -> Self {
+        Self::sorted(items, limit, ())
+    }
+}
+
+impl<T> ApiResponseList<T>
+where
+    T: Serialize + Cursor,
+{
+    pub fn sorted(items: Vec<T>, limit: Limit, sort: T::Sort) -> Self {
         let mut data = items;
         let limit: u64 = limit.as_u64();
         let has_more = data.len() > limit as usize;
@@ -25,7 +33,7 @@ where
         data.truncate(limit as usize);
 
         let next_cursor = if has_more {
-            data.last().map(|item| item.cursor())
+            data.last().map(|item| item.cursor(sort))
         } else {
             None
         };
@@ -79,6 +87,109 @@ impl From<Order> for db::Order {
     }
 }
 
+#[derive(Debug, Default, Deserialize, PartialEq, Copy, Clone)]
+pub enum PackageSort {
+    #[serde(rename = "id")]
+    #[default]
+    Id,
+    #[serde(rename = "downloads")]
+    Downloads,
+    #[serde(rename = "name")]
+    Name,
+    #[serde(rename = "updated_at")]
+    UpdatedAt,
+}
+
+impl From<PackageSort> for db::PackageSort {
+    fn from(sort: PackageSort) -> Self {
+        match sort {
+            PackageSort::Id => db::PackageSort::Id,
+            PackageSort::Downloads => db::PackageSort::Downloads,
+            PackageSort::Name => db::PackageSort::Name,
+            PackageSort::UpdatedAt => db::PackageSort::UpdatedAt,
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize, PartialEq, Copy, Clone)]
+pub enum DataFormat {
+    #[serde(rename = "csv")]
+    Csv,
+    #[serde(rename = "ndjson")]
+    #[default]
+    Ndjson,
+}
+
+impl DataFormat {
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            Self::Csv => "text/csv",
+            Self::Ndjson => "application/x-ndjson",
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Csv => "csv",
+            Self::Ndjson => "ndjson",
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize, PartialEq, Copy, Clone)]
+pub enum RankBy {
+    #[serde(rename = "downloads")]
+    #[default]
+    Downloads,
+    #[serde(rename = "growth")]
+    Growth,
+}
+
+impl From<RankBy> for db::RankBy {
+    fn from(by: RankBy) -> Self {
+        match by {
+            RankBy::Downloads => db::RankBy::Downloads,
+            RankBy::Growth => db::RankBy::Growth,
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize, PartialEq, Copy, Clone)]
+pub enum TrendingWindow {
+    #[serde(rename = "7d")]
+    #[default]
+    Week,
+    #[serde(rename = "30d")]
+    Month,
+}
+
+impl From<TrendingWindow> for db::TrendingWindow {
+    fn from(window: TrendingWindow) -> Self {
+        match window {
+            TrendingWindow::Week => db::TrendingWindow::Week,
+            TrendingWindow::Month => db::TrendingWindow::Month,
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize, PartialEq, Copy, Clone)]
+pub enum GrowthBy {
+    #[serde(rename = "relative")]
+    #[default]
+    Relative,
+    #[serde(rename = "absolute")]
+    Absolute,
+}
+
+impl From<GrowthBy> for db::GrowthBy {
+    fn from(by: GrowthBy) -> Self {
+        match by {
+            GrowthBy::Relative => db::GrowthBy::Relative,
+            GrowthBy::Absolute => db::GrowthBy::Absolute,
+        }
+    }
+}
+
 pub struct Limit(u64);
 
 impl Limit {
@@ -109,9 +220,10 @@ impl TryFrom<u64> for Limit {
 
 pub struct AppState {
     pub db_pool: Pool<Postgres>,
-    pub rabbitmq_connection: Arc<Connection>,
+    pub dispatcher: Dispatcher,
     pub integration_queues: HashMap<String, String>,
-    pub exchange_name: String,
+    pub quarantine: Quarantine,
+    pub consumer_queue: String,
 }
 
 #[cfg(test)]
@@ -120,7 +232,7 @@ mod tests {
     use uuid::Uuid;
 
     use crate::{
-        api::types::{ApiResponse, ApiResponseList, Limit, Order},
+        api::types::{ApiResponse, ApiResponseList, Limit, Order, PackageSort, RankBy},
         db,
         types::Cursor,
     };
@@ -130,7 +242,9 @@ mod tests {
         id: String,
     }
     impl Cursor for Item {
-        fn cursor(&self) -> String {
+        type Sort = ();
+
+        fn cursor(&self, _sort: ()) -> String {
             self.id.to_string()
         }
     }
@@ -217,4 +331,32 @@ mod tests {
         let db_order: db::Order = api_order.into();
         assert_eq!(db_order, db::Order::Desc);
     }
+
+    #[test]
+    fn test_package_sort_is_default_to_id() {
+        assert_eq!(PackageSort::default(), PackageSort::Id);
+    }
+
+    #[test]
+    fn test_package_sort_converts_to_db_package_sort() {
+        for (api_sort, db_sort) in [
+            (PackageSort::Id, db::PackageSort::Id),
+            (PackageSort::Downloads, db::PackageSort::Downloads),
+            (PackageSort::Name, db::PackageSort::Name),
+            (PackageSort::UpdatedAt, db::PackageSort::UpdatedAt),
+        ] {
+            assert_eq!(db::PackageSort::from(api_sort), db_sort);
+        }
+    }
+
+    #[test]
+    fn test_rank_by_is_default_to_downloads() {
+        assert_eq!(RankBy::default(), RankBy::Downloads);
+    }
+
+    #[test]
+    fn test_rank_by_converts_to_db_rank_by() {
+        assert_eq!(db::RankBy::from(RankBy::Downloads), db::RankBy::Downloads);
+        assert_eq!(db::RankBy::from(RankBy::Growth), db::RankBy::Growth);
+    }
 }
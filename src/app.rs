@@ -1,53 +1,66 @@
-use std::{collections::HashMap, sync::Arc};
+use std::{collections::HashMap, future::Future, sync::Arc, time::Duration};
 
 use anyhow::Result;
 use sqlx::{postgres::PgPoolOptions, Pool, Postgres};
-use tokio::try_join;
+use tokio::{signal, try_join};
+use tokio_util::sync::CancellationToken;
 
 use crate::{
     api::Api,
+    broker::Broker,
     config::{Config, DatabaseConfig},
-    services::{minio, rabbitmq},
+    dispatcher::Dispatcher,
+    job_stats::JobStatsCollector,
+    notifier::Notifier,
+    outputs::OutputStore,
+    quarantine::Quarantine,
+    rankings::RankingsRefresher,
+    refresher::PackageRefresher,
+    retry,
+    services::minio,
     telemetry::Metrics,
     worker::Worker,
 };
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Role {
+    Api,
+    Worker,
+    All,
+}
+
+impl Role {
+    fn runs_api(self) -> bool {
+        matches!(self, Role::Api | Role::All)
+    }
+
+    fn runs_worker(self) -> bool {
+        matches!(self, Role::Worker | Role::All)
+    }
+}
+
 pub struct Application {
+    pub broker: Broker,
     pub api: Api,
-    pub worker: Worker,
+    pub worker: Option<Worker>,
+    pub rankings_refresher: Option<RankingsRefresher>,
+    pub package_refresher: Option<PackageRefresher>,
+    pub notifier: Option<Notifier>,
+    pub job_stats: Option<JobStatsCollector>,
 }
 
 impl Application {
-    pub async fn build(configuration: Config, metrics: Metrics) -> Result<Self> {
-        let db_pool = get_db_pool(&configuration.database);
-
-        let rabbitmq_connection = Arc::new(rabbitmq::connect(&configuration.rabbitmq).await?);
-        let channel = rabbitmq_connection.create_channel().await?;
+    pub async fn build(configuration: Config, metrics: Metrics, role: Role) -> Result<Self> {
+        let startup_timeout =
+            Duration::from_secs(configuration.application.startup_timeout_seconds);
 
-        let all_queues: Vec<&str> = configuration
-            .rabbitmq
-            .queues
-            .iter()
-            .map(|s| s.as_str())
-            .chain(std::iter::once(
-                configuration.rabbitmq.queue_consumer.as_str(),
-            ))
-            .collect();
+        let db_pool = wait_for_db_pool(&configuration.database, startup_timeout).await?;
 
-        rabbitmq::declare_exchange(&channel, &configuration.rabbitmq.exchange_name).await?;
-        rabbitmq::declare_and_bind_queues(
-            &channel,
-            &all_queues,
-            &configuration.rabbitmq.exchange_name,
-        )
+        let broker = retry::wait_for("RabbitMQ", startup_timeout, || {
+            Broker::connect(&configuration.rabbitmq)
+        })
         .await?;
 
-        let queue_consumer = configuration.rabbitmq.queue_consumer.clone();
-
-        let minio_client = minio::create_client(&configuration.minio).await?;
-
-        minio::ensure_bucket(&minio_client, &configuration.minio.bucket_name).await?;
-
         let integration_queues: HashMap<String, String> = configuration
             .rabbitmq
             .registry_queues
@@ -55,39 +68,187 @@ impl Application {
             .cloned()
             .collect();
 
-        let worker = Worker::build(
-            rabbitmq_connection.clone(),
-            queue_consumer.clone(),
-            minio_client.clone(),
-            configuration.minio.bucket_name.clone(),
+        let output_keys = configuration.minio.output_keys()?;
+
+        let metrics = Arc::new(metrics);
+
+        let dispatcher = Dispatcher::new(
             db_pool.clone(),
-        )
+            broker.clone(),
+            configuration.rabbitmq.exchange_name.clone(),
+            integration_queues.clone(),
+            output_keys.clone(),
+        );
+
+        let api = if role.runs_api() {
+            Api::build(
+                &configuration,
+                db_pool.clone(),
+                dispatcher.clone(),
+                integration_queues,
+                Quarantine::new(configuration.minio.clone(), db_pool.clone()),
+                metrics.clone(),
+            )
+            .await?
+        } else {
+            Api::build_operational(&configuration, metrics.clone()).await?
+        };
+
+        if !role.runs_worker() {
+            return Ok(Self {
+                broker,
+                api,
+                worker: None,
+                rankings_refresher: None,
+                package_refresher: None,
+                notifier: None,
+                job_stats: None,
+            });
+        }
+
+        let minio_client = minio::create_client(&configuration.minio).await?;
+        retry::wait_for("MinIO", startup_timeout, || {
+            minio::ensure_bucket(&minio_client, &configuration.minio.bucket_name)
+        })
         .await?;
 
-        let metrics = Arc::new(metrics);
+        let rankings_refresher = RankingsRefresher::build(
+            db_pool.clone(),
+            Duration::from_secs(configuration.worker.rankings_refresh_delay_seconds),
+        );
 
-        let api = Api::build(
-            &configuration,
-            db_pool,
-            rabbitmq_connection.clone(),
-            integration_queues,
-            metrics,
+        let worker = Worker::build(
+            broker.clone(),
+            configuration.rabbitmq.queue_consumer.clone(),
+            OutputStore::new(
+                minio_client.clone(),
+                configuration.minio.bucket_name.clone(),
+                output_keys,
+                configuration.minio.max_output_bytes,
+                configuration.minio.max_bulk_output_bytes,
+                metrics.clone(),
+            ),
+            Quarantine::with_client(minio_client, configuration.minio.clone(), db_pool.clone()),
+            db_pool.clone(),
+            rankings_refresher.notifier(),
+            configuration.worker.clone(),
+            metrics.clone(),
         )
         .await?;
 
-        Ok(Self { api, worker })
+        let job_stats = JobStatsCollector::build(
+            db_pool.clone(),
+            Duration::from_secs(configuration.worker.job_stats_interval_seconds),
+            metrics,
+        );
+
+        let package_refresher =
+            PackageRefresher::build(db_pool.clone(), dispatcher, configuration.refresher.clone());
+
+        let notifier = Notifier::build(db_pool, configuration.notifications.clone())?;
+
+        Ok(Self {
+            broker,
+            api,
+            worker: Some(worker),
+            rankings_refresher: Some(rankings_refresher),
+            package_refresher: Some(package_refresher),
+            notifier: Some(notifier),
+            job_stats: Some(job_stats),
+        })
     }
 
     pub async fn run_until_stopped(self) -> Result<()> {
+        self.run_until(shutdown_signal()).await
+    }
+
+    pub async fn run_until(self, signal: impl Future<Output = ()>) -> Result<()> {
+        let shutdown = CancellationToken::new();
+        let trigger = shutdown.clone();
+        let signal = async move {
+            signal.await;
+            tracing::info!("Shutdown requested, draining in-flight work");
+            trigger.cancel();
+            Ok(())
+        };
+
         try_join!(
-            self.worker.run_until_stopped(),
-            self.api.run_until_stopped()
+            signal,
+            self.broker.run_until_stopped(shutdown.clone()),
+            self.api.run_until_stopped(shutdown.clone()),
+            run_if_enabled(
+                self.worker
+                    .map(|worker| worker.run_until_stopped(shutdown.clone()))
+            ),
+            run_if_enabled(
+                self.rankings_refresher
+                    .map(|refresher| refresher.run_until_stopped(shutdown.clone()))
+            ),
+            run_if_enabled(
+                self.package_refresher
+                    .map(|refresher| refresher.run_until_stopped(shutdown.clone()))
+            ),
+            run_if_enabled(
+                self.notifier
+                    .map(|notifier| notifier.run_until_stopped(shutdown.clone()))
+            ),
+            run_if_enabled(
+                self.job_stats
+                    .map(|job_stats| job_stats.run_until_stopped(shutdown))
+            )
         )?;
 
         Ok(())
     }
 }
 
+pub async fn shutdown_signal() {
+    let ctrl_c = async {
+        signal::ctrl_c()
+            .await
+            .expect("Failed to install Ctrl+C handler");
+    };
+    let terminate = async {
+        signal::unix::signal(signal::unix::SignalKind::terminate())
+            .expect("Failed to install SIGTERM handler")
+            .recv()
+            .await;
+    };
+
+    tokio::select! {
+        _ = ctrl_c => {}
+        _ = terminate => {}
+    }
+}
+
+async fn run_if_enabled(component: Option<impl Future<Output = Result<()>>>) -> Result<()> {
+    match component {
+        Some(component) => component.await,
+        None => Ok(()),
+    }
+}
+
+pub async fn migrate(configuration: &Config) -> Result<()> {
+    let startup_timeout = Duration::from_secs(configuration.application.startup_timeout_seconds);
+    let db_pool = wait_for_db_pool(&configuration.database, startup_timeout).await?;
+
+    sqlx::migrate!("./migrations").run(&db_pool).await?;
+    tracing::info!("Database migrations applied");
+
+    Ok(())
+}
+
+async fn wait_for_db_pool(settings: &DatabaseConfig, timeout: Duration) -> Result<Pool<Postgres>> {
+    let db_pool = get_db_pool(settings);
+    retry::wait_for("Postgres", timeout, || async {
+        db_pool.acquire().await?;
+        Ok(())
+    })
+    .await?;
+
+    Ok(db_pool)
+}
+
 pub fn get_db_pool(settings: &DatabaseConfig) -> Pool<Postgres> {
     PgPoolOptions::new().connect_lazy_with(settings.connect_options())
 }
@@ -0,0 +1,127 @@
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use lapin::{Channel, Connection};
+use tokio::sync::watch;
+use tokio_util::sync::CancellationToken;
+use tracing::instrument;
+
+use crate::{config::RabbitMQConfig, retry::Backoff, services::rabbitmq};
+
+#[derive(Clone)]
+pub struct Link {
+    pub connection: Arc<Connection>,
+    pub lost: CancellationToken,
+}
+
+impl Link {
+    pub fn is_alive(&self) -> bool {
+        !self.lost.is_cancelled() && self.connection.status().connected()
+    }
+}
+
+#[derive(Clone)]
+pub struct Broker {
+    settings: Arc<RabbitMQConfig>,
+    link: Arc<watch::Sender<Option<Link>>>,
+}
+
+impl Broker {
+    pub async fn connect(settings: &RabbitMQConfig) -> Result<Self> {
+        let broker = Self {
+            settings: Arc::new(settings.clone()),
+            link: Arc::new(watch::Sender::new(None)),
+        };
+        let link = broker.open().await?;
+        broker.link.send_replace(Some(link));
+
+        Ok(broker)
+    }
+
+    pub async fn create_channel(&self) -> Result<Channel> {
+        let link = self
+            .current()
+            .context("RabbitMQ connection is unavailable")?;
+
+        Ok(link.connection.create_channel().await?)
+    }
+
+    pub async fn connected(&self) -> Link {
+        let mut receiver = self.link.subscribe();
+        let link = receiver
+            .wait_for(|link| link.as_ref().is_some_and(Link::is_alive))
+            .await
+            .expect("Broker link sender dropped");
+
+        link.clone().expect("Link checked above")
+    }
+
+    pub async fn run_until_stopped(self, shutdown: CancellationToken) -> Result<()> {
+        let mut backoff = Backoff::default();
+        loop {
+            if let Some(link) = self.current() {
+                tokio::select! {
+                    _ = link.lost.cancelled() => {
+                        tracing::warn!("Lost RabbitMQ connection, reconnecting");
+                        self.link.send_replace(None);
+                    }
+                    _ = shutdown.cancelled() => return Ok(()),
+                }
+            }
+
+            match self.open().await {
+                Ok(link) => {
+                    tracing::info!("Connected to RabbitMQ");
+                    backoff.reset();
+                    self.link.send_replace(Some(link));
+                }
+                Err(error) => {
+                    let delay = backoff.next_delay();
+                    tracing::warn!(
+                        error = ?error,
+                        retry_in_ms = delay.as_millis() as u64,
+                        "Failed to connect to RabbitMQ"
+                    );
+                    tokio::select! {
+                        _ = tokio::time::sleep(delay) => {}
+                        _ = shutdown.cancelled() => return Ok(()),
+                    }
+                }
+            }
+        }
+    }
+
+    fn current(&self) -> Option<Link> {
+        self.link.borrow().clone().filter(Link::is_alive)
+    }
+
+    #[instrument(name = "broker_open", skip(self))]
+    async fn open(&self) -> Result<Link> {
+        let connection = rabbitmq::connect(&self.settings).await?;
+
+        let lost = CancellationToken::new();
+        let on_error = lost.clone();
+        connection.on_error(move |error| {
+            tracing::error!(error = ?error, "RabbitMQ connection error");
+            on_error.cancel();
+        });
+
+        let channel = connection.create_channel().await?;
+        let queues: Vec<&str> = self
+            .settings
+            .queues
+            .iter()
+            .map(|s| s.as_str())
+            .chain(std::iter::once(self.settings.queue_consumer.as_str()))
+            .collect();
+
+        rabbitmq::declare_exchange(&channel, &self.settings.exchange_name).await?;
+        rabbitmq::declare_and_bind_queues(&channel, &queues, &self.settings.exchange_name).await?;
+        channel.close(200, "Topology declared").await?;
+
+        Ok(Link {
+            connection: Arc::new(connection),
+            lost,
+        })
+    }
+}
@@ -0,0 +1,55 @@
+use clap::{Parser, Subcommand};
+
+use crate::app::Role;
+
+#[derive(Debug, Parser)]
+#[command(version, about)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Subcommand)]
+pub enum Command {
+    /// Serve the HTTP API
+    Serve,
+    /// Consume integration outputs and run the background refreshers
+    Worker,
+    /// Run the HTTP API and the worker in one process
+    All,
+    /// Apply pending database migrations and exit
+    Migrate,
+}
+
+impl Command {
+    pub fn role(self) -> Option<Role> {
+        match self {
+            Command::Serve => Some(Role::Api),
+            Command::Worker => Some(Role::Worker),
+            Command::All => Some(Role::All),
+            Command::Migrate => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cli_defaults_to_no_subcommand() {
+        let cli = Cli::try_parse_from(["integrations_api"]).unwrap();
+
+        assert_eq!(cli.command, None);
+    }
+
+    #[test]
+    fn test_cli_parses_subcommands() {
+        let cli = Cli::try_parse_from(["integrations_api", "worker"]).unwrap();
+
+        assert_eq!(cli.command, Some(Command::Worker));
+        assert_eq!(Command::Serve.role(), Some(Role::Api));
+        assert_eq!(Command::Migrate.role(), None);
+        assert!(Cli::try_parse_from(["integrations_api", "unknown"]).is_err());
+    }
+}
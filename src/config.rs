@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use anyhow::{Context, Result};
 use aws_sdk_s3::config::Credentials;
 use secrecy::{ExposeSecret, SecretString};
@@ -5,12 +7,17 @@ use serde::Deserialize;
 use serde_aux::prelude::*;
 use sqlx::postgres::{PgConnectOptions, PgSslMode};
 
+use crate::outputs::OutputKeys;
+
 #[derive(Deserialize)]
 pub struct Config {
     pub application: ApplicationConfig,
     pub database: DatabaseConfig,
     pub rabbitmq: RabbitMQConfig,
     pub minio: MinioConfig,
+    pub worker: WorkerConfig,
+    pub refresher: RefresherConfig,
+    pub notifications: NotificationsConfig,
 }
 
 #[derive(Deserialize)]
@@ -19,6 +26,7 @@ pub struct ApplicationConfig {
     pub version: String,
     pub host: String,
     pub port: u16,
+    pub startup_timeout_seconds: u64,
 }
 
 #[derive(Deserialize)]
@@ -32,7 +40,7 @@ pub struct DatabaseConfig {
     pub require_ssl: bool,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, Clone)]
 pub struct RabbitMQConfig {
     pub url: String,
     pub exchange_name: String,
@@ -41,12 +49,67 @@ pub struct RabbitMQConfig {
     pub queue_consumer: String,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, Clone)]
 pub struct MinioConfig {
     pub url: String,
     pub username: SecretString,
     pub password: SecretString,
     pub bucket_name: String,
+    pub output_key_template: String,
+    pub legacy_output_keys: bool,
+    pub max_output_bytes: u64,
+    pub max_bulk_output_bytes: u64,
+}
+
+#[derive(Deserialize, Clone)]
+pub struct WorkerConfig {
+    pub rankings_refresh_delay_seconds: u64,
+    pub consumers: usize,
+    pub prefetch_count: u16,
+    pub max_concurrency: usize,
+    pub shutdown_timeout_seconds: u64,
+    pub job_stats_interval_seconds: u64,
+}
+
+#[derive(Deserialize, Clone)]
+pub struct RefresherConfig {
+    pub enabled: bool,
+    pub interval_seconds: u64,
+    pub max_age_seconds: u64,
+    pub registry_max_age_seconds: HashMap<String, u64>,
+    pub max_pending_per_registry: u64,
+    pub pending_timeout_seconds: u64,
+}
+
+#[derive(Deserialize, Clone)]
+pub struct NotificationsConfig {
+    pub enabled: bool,
+    pub interval_seconds: u64,
+    pub batch_size: u64,
+    pub max_attempts: i32,
+    pub retry_delay_seconds: u64,
+    pub webhook_timeout_seconds: u64,
+    pub lease_seconds: u64,
+    pub smtp: SmtpConfig,
+}
+
+#[derive(Deserialize, Clone)]
+pub struct SmtpConfig {
+    pub host: String,
+    pub port: u16,
+    pub tls: bool,
+    pub from: String,
+    pub username: Option<SecretString>,
+    pub password: Option<SecretString>,
+}
+
+impl RefresherConfig {
+    pub fn max_age_seconds(&self, registry: &str) -> u64 {
+        self.registry_max_age_seconds
+            .get(registry)
+            .copied()
+            .unwrap_or(self.max_age_seconds)
+    }
 }
 
 impl MinioConfig {
@@ -59,6 +122,10 @@ impl MinioConfig {
             "minio0",
         )
     }
+
+    pub fn output_keys(&self) -> Result<OutputKeys> {
+        OutputKeys::new(&self.output_key_template, self.legacy_output_keys)
+    }
 }
 
 impl DatabaseConfig {
@@ -0,0 +1,193 @@
+use std::collections::{hash_map::Entry, HashMap};
+
+use anyhow::Result;
+use sqlx::PgConnection;
+use tracing::{instrument, Instrument};
+use uuid::Uuid;
+
+use crate::{
+    models::dependency::{Dependency, DependencyEdge},
+    telemetry::{instrument_query, Operation},
+};
+
+#[instrument(name = "replace_package_dependencies", skip(conn, dependencies))]
+pub async fn replace_package_dependencies(
+    conn: &mut PgConnection,
+    package_id: Uuid,
+    dependencies: &[Dependency],
+) -> Result<()> {
+    sqlx::query!(
+        "DELETE FROM package_dependencies WHERE package_id = $1;",
+        package_id,
+    )
+    .execute(&mut *conn)
+    .instrument(instrument_query(Operation::Delete, "package_dependencies"))
+    .await?;
+
+    let dependencies = unique_dependencies(dependencies);
+    let names: Vec<&str> = dependencies.iter().map(|d| d.name.as_str()).collect();
+    let requirements: Vec<&str> = dependencies
+        .iter()
+        .map(|d| d.requirement.as_str())
+        .collect();
+    let kinds: Vec<&str> = dependencies.iter().map(|d| d.kind.as_str()).collect();
+
+    sqlx::query!(
+        r#"INSERT INTO package_dependencies (package_id, name, requirement, kind)
+        SELECT $1, * FROM UNNEST($2::text[], $3::text[], $4::text[])
+        ON CONFLICT (package_id, name, kind) DO UPDATE SET requirement = EXCLUDED.requirement;"#,
+        package_id,
+        &names as &[&str],
+        &requirements as &[&str],
+        &kinds as &[&str],
+    )
+    .execute(&mut *conn)
+    .instrument(instrument_query(Operation::Insert, "package_dependencies"))
+    .await?;
+
+    Ok(())
+}
+
+// Manifests can list a dependency more than once under the same kind, e.g. in Cargo
+// target-specific tables, which a single upsert cannot insert twice. The last one wins.
+fn unique_dependencies(dependencies: &[Dependency]) -> Vec<&Dependency> {
+    let mut unique: Vec<&Dependency> = Vec::with_capacity(dependencies.len());
+    let mut positions: HashMap<(&str, &str), usize> = HashMap::new();
+    for dependency in dependencies {
+        match positions.entry((dependency.name.as_str(), dependency.kind.as_str())) {
+            Entry::Occupied(position) => unique[*position.get()] = dependency,
+            Entry::Vacant(position) => {
+                position.insert(unique.len());
+                unique.push(dependency);
+            }
+        }
+    }
+
+    unique
+}
+
+// Both traversals track each package once per depth instead of every path to it, so
+// diamond-shaped graphs stay linear in size. Edges are reported at the shallowest depth.
+#[instrument(name = "get_dependencies", skip(conn))]
+pub async fn get_dependencies(
+    conn: &mut PgConnection,
+    registry: &str,
+    name: &str,
+    depth: i32,
+) -> Result<Vec<DependencyEdge>> {
+    let edges = sqlx::query_as!(
+        DependencyEdge,
+        r#"WITH RECURSIVE reachable AS (
+            SELECT $2::text AS name, 1 AS depth
+            UNION
+            SELECT d.name, r.depth + 1
+            FROM reachable r
+            JOIN packages p ON p.registry = $1 AND p.name = r.name
+            JOIN package_dependencies d ON d.package_id = p.id
+            WHERE r.depth < $3
+        ),
+        sources AS (
+            SELECT name, MIN(depth) AS depth FROM reachable GROUP BY name
+        )
+        SELECT
+            p.registry AS "registry!",
+            p.name AS "package_name!",
+            d.name AS "dependency_name!",
+            d.requirement AS "requirement!",
+            d.kind AS "kind!",
+            s.depth AS "depth!"
+        FROM sources s
+        JOIN packages p ON p.registry = $1 AND p.name = s.name
+        JOIN package_dependencies d ON d.package_id = p.id
+        ORDER BY s.depth, p.name, d.name, d.kind;"#,
+        registry,
+        name,
+        depth,
+    )
+    .fetch_all(&mut *conn)
+    .instrument(instrument_query(Operation::Select, "package_dependencies"))
+    .await?;
+
+    Ok(edges)
+}
+
+#[instrument(name = "get_dependents", skip(conn))]
+pub async fn get_dependents(
+    conn: &mut PgConnection,
+    registry: &str,
+    name: &str,
+    depth: i32,
+) -> Result<Vec<DependencyEdge>> {
+    let edges = sqlx::query_as!(
+        DependencyEdge,
+        r#"WITH RECURSIVE reachable AS (
+            SELECT $2::text AS name, 0 AS depth
+            UNION
+            SELECT p.name, r.depth + 1
+            FROM reachable r
+            JOIN package_dependencies d ON d.name = r.name
+            JOIN packages p ON p.id = d.package_id AND p.registry = $1
+            WHERE r.depth < $3 - 1
+        ),
+        targets AS (
+            SELECT name, MIN(depth) AS depth FROM reachable GROUP BY name
+        )
+        SELECT
+            p.registry AS "registry!",
+            p.name AS "package_name!",
+            d.name AS "dependency_name!",
+            d.requirement AS "requirement!",
+            d.kind AS "kind!",
+            t.depth + 1 AS "depth!"
+        FROM targets t
+        JOIN package_dependencies d ON d.name = t.name
+        JOIN packages p ON p.id = d.package_id AND p.registry = $1
+        ORDER BY t.depth, p.name, d.name, d.kind;"#,
+        registry,
+        name,
+        depth,
+    )
+    .fetch_all(&mut *conn)
+    .instrument(instrument_query(Operation::Select, "package_dependencies"))
+    .await?;
+
+    Ok(edges)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dependency(name: &str, requirement: &str, kind: &str) -> Dependency {
+        Dependency {
+            name: name.to_string(),
+            requirement: requirement.to_string(),
+            kind: kind.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_unique_dependencies_keeps_last_duplicate() {
+        let dependencies = vec![
+            dependency("libc", "^0.2.100", "normal"),
+            dependency("serde", "^1.0", "normal"),
+            dependency("libc", "^0.2.150", "normal"),
+            dependency("libc", "^0.2", "dev"),
+        ];
+
+        let unique = unique_dependencies(&dependencies);
+
+        let unique: Vec<(&str, &str, &str)> = unique
+            .iter()
+            .map(|d| (d.name.as_str(), d.requirement.as_str(), d.kind.as_str()))
+            .collect();
+        assert_eq!(
+            unique,
+            vec![
+                ("libc", "^0.2.150", "normal"),
+                ("serde", "^1.0", "normal"),
+                ("libc", "^0.2", "dev"),
+            ]
+        );
+    }
+}
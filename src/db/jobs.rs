@@ -1,10 +1,12 @@
 use anyhow::Result;
+use chrono::{DateTime, Utc};
+use futures::{stream::BoxStream, StreamExt, TryStreamExt};
 use sqlx::PgConnection;
 use tracing::{instrument, Instrument};
 use uuid::Uuid;
 
 use crate::{
-    models::job::Job,
+    models::job::{Job, JobSource, JobStatusCount},
     telemetry::{instrument_query, Operation},
 };
 
@@ -14,13 +16,16 @@ use super::types::Order;
 pub async fn insert_job(conn: &mut PgConnection, job: Job) -> Result<Job> {
     let result = sqlx::query_as!(
         Job,
-        "INSERT INTO jobs (id, registry, package_name, status, trace_id, created_at) VALUES ($1, $2, $3, $4, $5, $6) RETURNING *;",
+        "INSERT INTO jobs (id, registry, package_name, status, trace_id, created_at, force, source, output_key) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9) RETURNING *;",
         job.id,
         job.registry,
         job.package_name,
         job.status.to_string(),
         job.trace_id,
         job.created_at,
+        job.force,
+        job.source.to_string(),
+        job.output_key,
     )
     .fetch_one(&mut *conn)
     .instrument(instrument_query(Operation::Insert, "jobs"))
@@ -30,11 +35,50 @@ pub async fn insert_job(conn: &mut PgConnection, job: Job) -> Result<Job> {
 }
 
 #[instrument(name = "complete_job", skip(conn))]
-pub async fn complete_job(conn: &mut PgConnection, id: Uuid) -> Result<Job> {
+pub async fn complete_job(
+    conn: &mut PgConnection,
+    id: Uuid,
+    skipped_version: Option<&str>,
+) -> Result<Job> {
     let job = sqlx::query_as!(
         Job,
-        "UPDATE jobs SET status = 'completed' WHERE id = $1 RETURNING *;",
+        "UPDATE jobs SET status = 'completed', skipped_version = $2 WHERE id = $1 RETURNING *;",
         id,
+        skipped_version,
+    )
+    .fetch_one(&mut *conn)
+    .instrument(instrument_query(Operation::Update, "jobs"))
+    .await?;
+
+    Ok(job)
+}
+
+#[instrument(name = "fail_job", skip(conn))]
+pub async fn fail_job(conn: &mut PgConnection, id: Uuid, errors: &[String]) -> Result<Job> {
+    let job = sqlx::query_as!(
+        Job,
+        "UPDATE jobs SET status = 'failed', errors = $2 WHERE id = $1 RETURNING *;",
+        id,
+        errors,
+    )
+    .fetch_one(&mut *conn)
+    .instrument(instrument_query(Operation::Update, "jobs"))
+    .await?;
+
+    Ok(job)
+}
+
+#[instrument(name = "record_job_errors", skip(conn))]
+pub async fn record_job_errors(
+    conn: &mut PgConnection,
+    id: Uuid,
+    errors: &[String],
+) -> Result<Job> {
+    let job = sqlx::query_as!(
+        Job,
+        "UPDATE jobs SET errors = $2 WHERE id = $1 RETURNING *;",
+        id,
+        errors,
     )
     .fetch_one(&mut *conn)
     .instrument(instrument_query(Operation::Update, "jobs"))
@@ -56,6 +100,19 @@ pub async fn get_jobs(
     }
 }
 
+pub fn stream_jobs(conn: &mut PgConnection, order: Order) -> BoxStream<'_, Result<Job>> {
+    match order {
+        Order::Asc => sqlx::query_as!(Job, "SELECT * FROM jobs ORDER BY id ASC;")
+            .fetch(conn)
+            .map_err(Into::into)
+            .boxed(),
+        Order::Desc => sqlx::query_as!(Job, "SELECT * FROM jobs ORDER BY id DESC;")
+            .fetch(conn)
+            .map_err(Into::into)
+            .boxed(),
+    }
+}
+
 async fn get_jobs_with_limit(
     conn: &mut PgConnection,
     limit: u64,
@@ -137,6 +194,58 @@ async fn get_jobs_with_limit_after_desc(
     Ok(jobs)
 }
 
+#[instrument(name = "count_jobs_by_status", skip(conn))]
+pub async fn count_jobs_by_status(conn: &mut PgConnection) -> Result<Vec<JobStatusCount>> {
+    let counts = sqlx::query_as!(
+        JobStatusCount,
+        r#"SELECT status, COUNT(*) AS "count!", MIN(created_at) AS oldest_created_at FROM jobs GROUP BY status;"#,
+    )
+    .fetch_all(&mut *conn)
+    .instrument(instrument_query(Operation::Select, "jobs"))
+    .await?;
+
+    Ok(counts)
+}
+
+#[instrument(name = "count_pending_jobs", skip(conn))]
+pub async fn count_pending_jobs(
+    conn: &mut PgConnection,
+    registry: &str,
+    source: JobSource,
+    since: DateTime<Utc>,
+) -> Result<i64> {
+    let count = sqlx::query_scalar!(
+        r#"SELECT COUNT(*) AS "count!" FROM jobs WHERE registry = $1 AND source = $2 AND status = 'processing' AND created_at >= $3;"#,
+        registry,
+        source.to_string(),
+        since,
+    )
+    .fetch_one(&mut *conn)
+    .instrument(instrument_query(Operation::Select, "jobs"))
+    .await?;
+
+    Ok(count)
+}
+
+// Every worker process runs a refresher, and they share each registry's pending-job budget,
+// so only one of them may dispatch refreshes for a registry at a time. The lock is held until
+// the transaction on `conn` ends.
+#[instrument(name = "try_lock_refresh", skip(conn))]
+pub async fn try_lock_refresh(conn: &mut PgConnection, registry: &str) -> Result<bool> {
+    let locked = sqlx::query_scalar!(
+        r#"SELECT pg_try_advisory_xact_lock($1, hashtext($2)) AS "locked!";"#,
+        REFRESH_LOCK_NAMESPACE,
+        registry,
+    )
+    .fetch_one(&mut *conn)
+    .instrument(instrument_query(Operation::Select, "jobs"))
+    .await?;
+
+    Ok(locked)
+}
+
+const REFRESH_LOCK_NAMESPACE: i32 = 0x7265_6672;
+
 #[instrument(name = "get_one", skip(conn))]
 pub async fn get_job_by_id(conn: &mut PgConnection, id: Uuid) -> Result<Option<Job>> {
     let job = sqlx::query_as!(Job, "SELECT * FROM jobs WHERE id = $1;", id)
@@ -1,7 +1,19 @@
+mod dependencies;
 mod jobs;
+mod notifications;
 mod packages;
+mod projects;
+mod quarantine;
+mod rankings;
 mod types;
+mod watchlists;
 
+pub use dependencies::*;
 pub use jobs::*;
+pub use notifications::*;
 pub use packages::*;
+pub use projects::*;
+pub use quarantine::*;
+pub use rankings::*;
 pub use types::*;
+pub use watchlists::*;
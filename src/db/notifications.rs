@@ -0,0 +1,169 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use sqlx::PgConnection;
+use tracing::{instrument, Instrument};
+use uuid::Uuid;
+
+use crate::{
+    models::notification::{Notification, PendingNotification, VersionChangedEvent},
+    telemetry::{instrument_query, Operation},
+};
+
+#[instrument(name = "enqueue_version_notifications", skip(conn))]
+pub async fn enqueue_version_notifications(
+    conn: &mut PgConnection,
+    registry: &str,
+    package_name: &str,
+    old_version: &str,
+    new_version: &str,
+    created_at: DateTime<Utc>,
+) -> Result<u64> {
+    // Ids are generated here rather than in Postgres to keep them time-ordered like every other id.
+    let target_ids = sqlx::query_scalar!(
+        r#"SELECT DISTINCT t.id FROM watchlist_targets t
+        JOIN watchlist_packages p ON p.watchlist_id = t.watchlist_id
+        WHERE p.registry = $1 AND p.name = $2;"#,
+        registry,
+        package_name,
+    )
+    .fetch_all(&mut *conn)
+    .instrument(instrument_query(Operation::Select, "watchlist_targets"))
+    .await?;
+    if target_ids.is_empty() {
+        return Ok(0);
+    }
+
+    let ids: Vec<Uuid> = target_ids.iter().map(|_| Uuid::now_v7()).collect();
+    let result = sqlx::query!(
+        r#"INSERT INTO notifications (id, target_id, registry, package_name, old_version, new_version, created_at, next_attempt_at)
+        SELECT id, target_id, $3, $4, $5, $6, $7, $7 FROM UNNEST($1::uuid[], $2::uuid[]) AS t (id, target_id);"#,
+        &ids,
+        &target_ids,
+        registry,
+        package_name,
+        old_version,
+        new_version,
+        created_at,
+    )
+    .execute(&mut *conn)
+    .instrument(instrument_query(Operation::Insert, "notifications"))
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
+// Leases the claimed rows by pushing their next attempt past `lease_until`, so concurrent
+// notifiers skip them while they are delivered, and a crashed notifier's batch is retried.
+#[instrument(name = "claim_pending_notifications", skip(conn))]
+pub async fn claim_pending_notifications(
+    conn: &mut PgConnection,
+    now: DateTime<Utc>,
+    lease_until: DateTime<Utc>,
+    limit: u64,
+) -> Result<Vec<PendingNotification>> {
+    let rows = sqlx::query!(
+        r#"UPDATE notifications n SET next_attempt_at = $2
+        FROM watchlist_targets t
+        WHERE t.id = n.target_id AND n.id IN (
+            SELECT id FROM notifications
+            WHERE status = 'pending' AND next_attempt_at <= $1
+            ORDER BY next_attempt_at ASC
+            LIMIT $3
+            FOR UPDATE SKIP LOCKED
+        )
+        RETURNING n.id, n.registry, n.package_name, n.old_version, n.new_version, n.attempts, n.created_at, t.channel, t.target;"#,
+        now,
+        lease_until,
+        limit as i64,
+    )
+    .fetch_all(&mut *conn)
+    .instrument(instrument_query(Operation::Update, "notifications"))
+    .await?;
+
+    let notifications = rows
+        .into_iter()
+        .map(|row| PendingNotification {
+            id: row.id,
+            channel: row.channel.into(),
+            target: row.target,
+            attempts: row.attempts,
+            event: VersionChangedEvent {
+                event: VersionChangedEvent::NAME.to_string(),
+                registry: row.registry,
+                package_name: row.package_name,
+                old_version: row.old_version,
+                new_version: row.new_version,
+                detected_at: row.created_at,
+            },
+        })
+        .collect();
+
+    Ok(notifications)
+}
+
+#[instrument(name = "mark_notification_delivered", skip(conn))]
+pub async fn mark_notification_delivered(
+    conn: &mut PgConnection,
+    id: Uuid,
+    delivered_at: DateTime<Utc>,
+) -> Result<()> {
+    sqlx::query!(
+        "UPDATE notifications SET status = 'delivered', attempts = attempts + 1, last_error = NULL, delivered_at = $2 WHERE id = $1;",
+        id,
+        delivered_at,
+    )
+    .execute(&mut *conn)
+    .instrument(instrument_query(Operation::Update, "notifications"))
+    .await?;
+
+    Ok(())
+}
+
+// Without a next attempt the notification is given up on.
+#[instrument(name = "mark_notification_failed", skip(conn))]
+pub async fn mark_notification_failed(
+    conn: &mut PgConnection,
+    id: Uuid,
+    error: &str,
+    next_attempt_at: Option<DateTime<Utc>>,
+) -> Result<()> {
+    sqlx::query!(
+        r#"UPDATE notifications SET
+            status = CASE WHEN $3::timestamptz IS NULL THEN 'failed' ELSE 'pending' END,
+            attempts = attempts + 1,
+            last_error = $2,
+            next_attempt_at = COALESCE($3, next_attempt_at)
+        WHERE id = $1;"#,
+        id,
+        error,
+        next_attempt_at,
+    )
+    .execute(&mut *conn)
+    .instrument(instrument_query(Operation::Update, "notifications"))
+    .await?;
+
+    Ok(())
+}
+
+#[instrument(name = "get_watchlist_notifications", skip(conn))]
+pub async fn get_watchlist_notifications(
+    conn: &mut PgConnection,
+    watchlist_id: Uuid,
+    limit: u64,
+) -> Result<Vec<Notification>> {
+    let notifications = sqlx::query_as!(
+        Notification,
+        r#"SELECT n.* FROM notifications n
+        JOIN watchlist_targets t ON t.id = n.target_id
+        WHERE t.watchlist_id = $1
+        ORDER BY n.id DESC
+        LIMIT $2;"#,
+        watchlist_id,
+        limit as i64,
+    )
+    .fetch_all(&mut *conn)
+    .instrument(instrument_query(Operation::Select, "notifications"))
+    .await?;
+
+    Ok(notifications)
+}
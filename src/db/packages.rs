@@ -1,4 +1,6 @@
 use anyhow::Result;
+use chrono::{DateTime, Utc};
+use futures::{stream::BoxStream, StreamExt, TryStreamExt};
 use sqlx::PgConnection;
 use tracing::{instrument, Instrument};
 use uuid::Uuid;
@@ -8,18 +10,32 @@ use crate::{
     telemetry::{instrument_query, Operation},
 };
 
-use super::Order;
+use super::{Order, PackageCursor, PackageFilter, PackageSort};
 
 #[instrument(name = "insert_package", skip(conn))]
 pub async fn insert_package(conn: &mut PgConnection, package: Package) -> Result<Package> {
     let package = sqlx::query_as!(
         Package,
-        r#"INSERT INTO packages (id, registry, name, version, downloads) VALUES ($1, $2, $3, $4, $5) RETURNING *;"#,
+        r#"INSERT INTO packages (id, registry, name, version, downloads, updated_at, description, license, repository_url, homepage, keywords, published_at, deprecated, created_at, last_refreshed_at, last_job_id, deleted_at, deletion_reason, project_id) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19) RETURNING *;"#,
         package.id,
         package.registry,
         package.name,
         package.version,
         package.downloads,
+        package.updated_at,
+        package.description,
+        package.license,
+        package.repository_url,
+        package.homepage,
+        &package.keywords,
+        package.published_at,
+        package.deprecated,
+        package.created_at,
+        package.last_refreshed_at,
+        package.last_job_id,
+        package.deleted_at,
+        package.deletion_reason,
+        package.project_id,
     )
     .fetch_one(&mut *conn)
     .instrument(instrument_query(Operation::Insert, "packages"))
@@ -32,11 +48,24 @@ pub async fn insert_package(conn: &mut PgConnection, package: Package) -> Result
 pub async fn update_package(conn: &mut PgConnection, package: Package) -> Result<Package> {
     let package = sqlx::query_as!(
         Package,
-        r#"UPDATE packages SET registry = $1, name = $2, version = $3, downloads = $4 WHERE id = $5 RETURNING *;"#,
+        r#"UPDATE packages SET registry = $1, name = $2, version = $3, downloads = $4, updated_at = $5, description = $6, license = $7, repository_url = $8, homepage = $9, keywords = $10, published_at = $11, deprecated = $12, last_refreshed_at = $13, last_job_id = $14, deleted_at = $15, deletion_reason = $16, project_id = $17 WHERE id = $18 RETURNING *;"#,
         package.registry,
         package.name,
         package.version,
         package.downloads,
+        package.updated_at,
+        package.description,
+        package.license,
+        package.repository_url,
+        package.homepage,
+        &package.keywords,
+        package.published_at,
+        package.deprecated,
+        package.last_refreshed_at,
+        package.last_job_id,
+        package.deleted_at,
+        package.deletion_reason,
+        package.project_id,
         package.id,
     )
     .fetch_one(&mut *conn)
@@ -50,12 +79,26 @@ pub async fn update_package(conn: &mut PgConnection, package: Package) -> Result
 pub async fn upsert_package(conn: &mut PgConnection, package: Package) -> Result<Package> {
     let package = sqlx::query_as!(
         Package,
-        r#"INSERT INTO packages (id, registry, name, version, downloads) VALUES ($1, $2, $3, $4, $5) ON CONFLICT (registry, name) DO UPDATE SET version = $4, downloads = $5 RETURNING *;"#,
+        r#"INSERT INTO packages (id, registry, name, version, downloads, updated_at, description, license, repository_url, homepage, keywords, published_at, deprecated, created_at, last_refreshed_at, last_job_id, deleted_at, deletion_reason, project_id) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19) ON CONFLICT (registry, name) DO UPDATE SET version = $4, downloads = $5, updated_at = $6, description = $7, license = $8, repository_url = $9, homepage = $10, keywords = $11, published_at = $12, deprecated = $13, last_refreshed_at = $15, last_job_id = $16 RETURNING *;"#,
         package.id,
         package.registry,
         package.name,
         package.version,
         package.downloads,
+        package.updated_at,
+        package.description,
+        package.license,
+        package.repository_url,
+        package.homepage,
+        &package.keywords,
+        package.published_at,
+        package.deprecated,
+        package.created_at,
+        package.last_refreshed_at,
+        package.last_job_id,
+        package.deleted_at,
+        package.deletion_reason,
+        package.project_id,
     )
     .fetch_one(&mut *conn)
     .instrument(instrument_query(Operation::Insert, "packages"))
@@ -64,35 +107,412 @@ pub async fn upsert_package(conn: &mut PgConnection, package: Package) -> Result
     Ok(package)
 }
 
+// Expects to run inside a transaction, the staging table is dropped on commit.
+#[instrument(name = "import_packages", skip(conn, packages), fields(count = packages.len()))]
+pub async fn import_packages(conn: &mut PgConnection, packages: &[Package]) -> Result<u64> {
+    sqlx::query(
+        r#"CREATE TEMP TABLE package_imports (
+            position INT NOT NULL,
+            id UUID NOT NULL,
+            registry TEXT NOT NULL,
+            name TEXT NOT NULL,
+            version TEXT NOT NULL,
+            downloads BIGINT NOT NULL,
+            updated_at TIMESTAMPTZ NOT NULL,
+            description TEXT,
+            license TEXT,
+            repository_url TEXT,
+            homepage TEXT,
+            keywords TEXT[] NOT NULL,
+            published_at TIMESTAMPTZ,
+            deprecated BOOLEAN NOT NULL,
+            created_at TIMESTAMPTZ NOT NULL
+        ) ON COMMIT DROP;"#,
+    )
+    .execute(&mut *conn)
+    .instrument(instrument_query(Operation::Insert, "package_imports"))
+    .await?;
+
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    for (position, package) in packages.iter().enumerate() {
+        writer.write_record([
+            position.to_string(),
+            package.id.to_string(),
+            package.registry.clone(),
+            package.name.clone(),
+            package.version.clone(),
+            package.downloads.to_string(),
+            package.updated_at.to_rfc3339(),
+            package.description.clone().unwrap_or_default(),
+            package.license.clone().unwrap_or_default(),
+            package.repository_url.clone().unwrap_or_default(),
+            package.homepage.clone().unwrap_or_default(),
+            text_array(&package.keywords),
+            package
+                .published_at
+                .map(|published_at| published_at.to_rfc3339())
+                .unwrap_or_default(),
+            package.deprecated.to_string(),
+            package.created_at.to_rfc3339(),
+        ])?;
+    }
+    let data = writer.into_inner().map_err(|error| error.into_error())?;
+
+    let mut copy = conn
+        .copy_in_raw("COPY package_imports FROM STDIN WITH (FORMAT csv);")
+        .instrument(instrument_query(Operation::Insert, "package_imports"))
+        .await?;
+    copy.send(data).await?;
+    copy.finish().await?;
+
+    // The last row wins when a batch contains the same package twice.
+    let result = sqlx::query(
+        r#"INSERT INTO packages (id, registry, name, version, downloads, updated_at, description, license, repository_url, homepage, keywords, published_at, deprecated, created_at, last_refreshed_at)
+        SELECT DISTINCT ON (registry, name)
+            id, registry, name, version, downloads, updated_at, description, license, repository_url, homepage, keywords, published_at, deprecated, created_at, updated_at
+        FROM package_imports
+        ORDER BY registry, name, position DESC
+        ON CONFLICT (registry, name) DO UPDATE SET version = EXCLUDED.version, downloads = EXCLUDED.downloads, updated_at = EXCLUDED.updated_at, description = EXCLUDED.description, license = EXCLUDED.license, repository_url = EXCLUDED.repository_url, homepage = EXCLUDED.homepage, keywords = EXCLUDED.keywords, published_at = EXCLUDED.published_at, deprecated = EXCLUDED.deprecated;"#,
+    )
+    .execute(&mut *conn)
+    .instrument(instrument_query(Operation::Insert, "packages"))
+    .await?;
+
+    sqlx::query("DROP TABLE package_imports;")
+        .execute(&mut *conn)
+        .instrument(instrument_query(Operation::Delete, "package_imports"))
+        .await?;
+
+    Ok(result.rows_affected())
+}
+
+fn text_array(values: &[String]) -> String {
+    let elements: Vec<String> = values
+        .iter()
+        .map(|value| format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\"")))
+        .collect();
+
+    format!("{{{}}}", elements.join(","))
+}
+
+#[instrument(name = "get_package", skip(conn))]
+pub async fn get_package(
+    conn: &mut PgConnection,
+    registry: &str,
+    name: &str,
+) -> Result<Option<Package>> {
+    let package = sqlx::query_as!(
+        Package,
+        "SELECT * FROM packages WHERE registry = $1 AND name = $2 AND deleted_at IS NULL;",
+        registry,
+        name,
+    )
+    .fetch_optional(&mut *conn)
+    .instrument(instrument_query(Operation::Select, "packages"))
+    .await?;
+
+    Ok(package)
+}
+
+#[instrument(name = "get_package_for_update", skip(conn))]
+pub async fn get_package_for_update(
+    conn: &mut PgConnection,
+    registry: &str,
+    name: &str,
+) -> Result<Option<Package>> {
+    let package = sqlx::query_as!(
+        Package,
+        "SELECT * FROM packages WHERE registry = $1 AND name = $2 FOR UPDATE;",
+        registry,
+        name,
+    )
+    .fetch_optional(&mut *conn)
+    .instrument(instrument_query(Operation::Select, "packages"))
+    .await?;
+
+    Ok(package)
+}
+
+#[instrument(name = "delete_package", skip(conn))]
+pub async fn delete_package(
+    conn: &mut PgConnection,
+    registry: &str,
+    name: &str,
+    reason: &str,
+    deleted_at: DateTime<Utc>,
+    revisions: Option<&[i64]>,
+) -> Result<Option<Package>> {
+    let package = sqlx::query_as!(
+        Package,
+        "UPDATE packages SET deleted_at = $3, deletion_reason = $4 WHERE registry = $1 AND name = $2 AND deleted_at IS NULL AND ($5::bigint[] IS NULL OR revision = ANY($5)) RETURNING *;",
+        registry,
+        name,
+        deleted_at,
+        reason,
+        revisions,
+    )
+    .fetch_optional(&mut *conn)
+    .instrument(instrument_query(Operation::Delete, "packages"))
+    .await?;
+
+    Ok(package)
+}
+
+#[instrument(name = "mark_package_refreshed", skip(conn))]
+pub async fn mark_package_refreshed(
+    conn: &mut PgConnection,
+    id: Uuid,
+    job_id: Uuid,
+    refreshed_at: DateTime<Utc>,
+) -> Result<()> {
+    sqlx::query!(
+        "UPDATE packages SET last_refreshed_at = $2, last_job_id = $3 WHERE id = $1;",
+        id,
+        refreshed_at,
+        job_id,
+    )
+    .execute(&mut *conn)
+    .instrument(instrument_query(Operation::Update, "packages"))
+    .await?;
+
+    Ok(())
+}
+
+// A refresh that failed since `refreshed_before` is not retried until the package is stale
+// again, so that failing packages cannot keep the head of the queue.
+#[instrument(name = "get_stale_packages", skip(conn))]
+pub async fn get_stale_packages(
+    conn: &mut PgConnection,
+    registry: &str,
+    refreshed_before: DateTime<Utc>,
+    pending_since: DateTime<Utc>,
+    limit: u64,
+) -> Result<Vec<Package>> {
+    let packages = sqlx::query_as!(
+        Package,
+        r#"SELECT p.* FROM packages p
+        WHERE p.registry = $1
+        AND p.deleted_at IS NULL
+        AND p.last_refreshed_at < $2
+        AND NOT EXISTS (
+            SELECT 1 FROM jobs j
+            WHERE j.registry = p.registry
+            AND j.package_name = p.name
+            AND (
+                (j.status = 'processing' AND j.created_at >= $3)
+                OR (j.status = 'failed' AND j.source = 'refresher' AND j.created_at >= $2)
+            )
+        )
+        ORDER BY p.last_refreshed_at ASC
+        LIMIT $4;"#,
+        registry,
+        refreshed_before,
+        pending_since,
+        limit as i64,
+    )
+    .fetch_all(&mut *conn)
+    .instrument(instrument_query(Operation::Select, "packages"))
+    .await?;
+
+    Ok(packages)
+}
+
+#[instrument(name = "insert_package_downloads", skip(conn))]
+pub async fn insert_package_downloads(
+    conn: &mut PgConnection,
+    package_id: Uuid,
+    downloads: i64,
+    recorded_at: DateTime<Utc>,
+) -> Result<()> {
+    sqlx::query!(
+        "INSERT INTO package_downloads (package_id, downloads, recorded_at) VALUES ($1, $2, $3) ON CONFLICT (package_id, recorded_at) DO UPDATE SET downloads = $2;",
+        package_id,
+        downloads,
+        recorded_at,
+    )
+    .execute(&mut *conn)
+    .instrument(instrument_query(Operation::Insert, "package_downloads"))
+    .await?;
+
+    Ok(())
+}
+
 #[instrument(name = "get_packages", skip(conn))]
 pub async fn get_packages(
     conn: &mut PgConnection,
+    filter: &PackageFilter,
+    limit: u64,
+    sort: PackageSort,
+    after: Option<PackageCursor>,
+    order: Order,
+) -> Result<Vec<Package>> {
+    match sort {
+        PackageSort::Id => {
+            let after = after.map(|after| after.id());
+            get_packages_by_id(conn, filter, limit, after, order).await
+        }
+        PackageSort::Downloads => {
+            let after = after.as_ref().and_then(PackageCursor::downloads);
+            get_packages_by_downloads(conn, filter, limit, after, order).await
+        }
+        PackageSort::Name => {
+            let after = after.as_ref().and_then(PackageCursor::name);
+            get_packages_by_name(conn, filter, limit, after, order).await
+        }
+        PackageSort::UpdatedAt => {
+            let after = after.as_ref().and_then(PackageCursor::updated_at);
+            get_packages_by_updated_at(conn, filter, limit, after, order).await
+        }
+    }
+}
+
+pub fn stream_packages<'a>(
+    conn: &'a mut PgConnection,
+    filter: &'a PackageFilter,
+    order: Order,
+) -> BoxStream<'a, Result<Package>> {
+    match order {
+        Order::Asc => sqlx::query_as!(
+            Package,
+            r#"SELECT * FROM packages
+            WHERE ($1::text IS NULL OR registry = $1)
+            AND ($2::bigint IS NULL OR downloads >= $2)
+            AND ($3::bigint IS NULL OR downloads <= $3)
+            AND ($4::boolean OR deleted_at IS NULL)
+            ORDER BY id ASC;"#,
+            filter.registry,
+            filter.min_downloads,
+            filter.max_downloads,
+            filter.include_deleted,
+        )
+        .fetch(conn)
+        .map_err(Into::into)
+        .boxed(),
+        Order::Desc => sqlx::query_as!(
+            Package,
+            r#"SELECT * FROM packages
+            WHERE ($1::text IS NULL OR registry = $1)
+            AND ($2::bigint IS NULL OR downloads >= $2)
+            AND ($3::bigint IS NULL OR downloads <= $3)
+            AND ($4::boolean OR deleted_at IS NULL)
+            ORDER BY id DESC;"#,
+            filter.registry,
+            filter.min_downloads,
+            filter.max_downloads,
+            filter.include_deleted,
+        )
+        .fetch(conn)
+        .map_err(Into::into)
+        .boxed(),
+    }
+}
+
+async fn get_packages_by_id(
+    conn: &mut PgConnection,
+    filter: &PackageFilter,
     limit: u64,
     after: Option<Uuid>,
     order: Order,
 ) -> Result<Vec<Package>> {
-    match after {
-        Some(after) => get_packages_with_limit_after(conn, limit, after, order).await,
-        None => get_packages_with_limit(conn, limit, order).await,
+    match order {
+        Order::Asc => get_packages_by_id_asc(conn, filter, limit, after).await,
+        Order::Desc => get_packages_by_id_desc(conn, filter, limit, after).await,
     }
 }
 
-async fn get_packages_with_limit(
+async fn get_packages_by_id_asc(
+    conn: &mut PgConnection,
+    filter: &PackageFilter,
+    limit: u64,
+    after: Option<Uuid>,
+) -> Result<Vec<Package>> {
+    let packages = sqlx::query_as!(
+        Package,
+        r#"SELECT * FROM packages
+        WHERE ($1::text IS NULL OR registry = $1)
+        AND ($2::bigint IS NULL OR downloads >= $2)
+        AND ($3::bigint IS NULL OR downloads <= $3)
+        AND ($4::uuid IS NULL OR id > $4)
+        AND ($5::boolean OR deleted_at IS NULL)
+        ORDER BY id ASC LIMIT $6;"#,
+        filter.registry,
+        filter.min_downloads,
+        filter.max_downloads,
+        after,
+        filter.include_deleted,
+        limit as i64,
+    )
+    .fetch_all(&mut *conn)
+    .instrument(instrument_query(Operation::Select, "packages"))
+    .await?;
+
+    Ok(packages)
+}
+
+async fn get_packages_by_id_desc(
+    conn: &mut PgConnection,
+    filter: &PackageFilter,
+    limit: u64,
+    after: Option<Uuid>,
+) -> Result<Vec<Package>> {
+    let packages = sqlx::query_as!(
+        Package,
+        r#"SELECT * FROM packages
+        WHERE ($1::text IS NULL OR registry = $1)
+        AND ($2::bigint IS NULL OR downloads >= $2)
+        AND ($3::bigint IS NULL OR downloads <= $3)
+        AND ($4::uuid IS NULL OR id < $4)
+        AND ($5::boolean OR deleted_at IS NULL)
+        ORDER BY id DESC LIMIT $6;"#,
+        filter.registry,
+        filter.min_downloads,
+        filter.max_downloads,
+        after,
+        filter.include_deleted,
+        limit as i64,
+    )
+    .fetch_all(&mut *conn)
+    .instrument(instrument_query(Operation::Select, "packages"))
+    .await?;
+
+    Ok(packages)
+}
+
+async fn get_packages_by_downloads(
     conn: &mut PgConnection,
+    filter: &PackageFilter,
     limit: u64,
+    after: Option<(i64, Uuid)>,
     order: Order,
 ) -> Result<Vec<Package>> {
     match order {
-        Order::Asc => get_packages_with_limit_asc(conn, limit).await,
-        Order::Desc => get_packages_with_limit_desc(conn, limit).await,
+        Order::Asc => get_packages_by_downloads_asc(conn, filter, limit, after).await,
+        Order::Desc => get_packages_by_downloads_desc(conn, filter, limit, after).await,
     }
 }
 
-async fn get_packages_with_limit_asc(conn: &mut PgConnection, limit: u64) -> Result<Vec<Package>> {
+async fn get_packages_by_downloads_asc(
+    conn: &mut PgConnection,
+    filter: &PackageFilter,
+    limit: u64,
+    after: Option<(i64, Uuid)>,
+) -> Result<Vec<Package>> {
     let packages = sqlx::query_as!(
         Package,
-        "SELECT * FROM packages ORDER BY id ASC LIMIT $1;",
-        limit as i64
+        r#"SELECT * FROM packages
+        WHERE ($1::text IS NULL OR registry = $1)
+        AND ($2::bigint IS NULL OR downloads >= $2)
+        AND ($3::bigint IS NULL OR downloads <= $3)
+        AND ($4::bigint IS NULL OR (downloads, id) > ($4, $5::uuid))
+        AND ($6::boolean OR deleted_at IS NULL)
+        ORDER BY downloads ASC, id ASC LIMIT $7;"#,
+        filter.registry,
+        filter.min_downloads,
+        filter.max_downloads,
+        after.map(|(value, _)| value),
+        after.map(|(_, id)| id),
+        filter.include_deleted,
+        limit as i64,
     )
     .fetch_all(&mut *conn)
     .instrument(instrument_query(Operation::Select, "packages"))
@@ -101,11 +521,28 @@ async fn get_packages_with_limit_asc(conn: &mut PgConnection, limit: u64) -> Res
     Ok(packages)
 }
 
-async fn get_packages_with_limit_desc(conn: &mut PgConnection, limit: u64) -> Result<Vec<Package>> {
+async fn get_packages_by_downloads_desc(
+    conn: &mut PgConnection,
+    filter: &PackageFilter,
+    limit: u64,
+    after: Option<(i64, Uuid)>,
+) -> Result<Vec<Package>> {
     let packages = sqlx::query_as!(
         Package,
-        "SELECT * FROM packages ORDER BY id DESC LIMIT $1;",
-        limit as i64
+        r#"SELECT * FROM packages
+        WHERE ($1::text IS NULL OR registry = $1)
+        AND ($2::bigint IS NULL OR downloads >= $2)
+        AND ($3::bigint IS NULL OR downloads <= $3)
+        AND ($4::bigint IS NULL OR (downloads, id) < ($4, $5::uuid))
+        AND ($6::boolean OR deleted_at IS NULL)
+        ORDER BY downloads DESC, id DESC LIMIT $7;"#,
+        filter.registry,
+        filter.min_downloads,
+        filter.max_downloads,
+        after.map(|(value, _)| value),
+        after.map(|(_, id)| id),
+        filter.include_deleted,
+        limit as i64,
     )
     .fetch_all(&mut *conn)
     .instrument(instrument_query(Operation::Select, "packages"))
@@ -114,28 +551,41 @@ async fn get_packages_with_limit_desc(conn: &mut PgConnection, limit: u64) -> Re
     Ok(packages)
 }
 
-async fn get_packages_with_limit_after(
+async fn get_packages_by_name(
     conn: &mut PgConnection,
+    filter: &PackageFilter,
     limit: u64,
-    after: Uuid,
+    after: Option<(&str, Uuid)>,
     order: Order,
 ) -> Result<Vec<Package>> {
     match order {
-        Order::Asc => get_packages_with_limit_after_asc(conn, limit, after).await,
-        Order::Desc => get_packages_with_limit_after_desc(conn, limit, after).await,
+        Order::Asc => get_packages_by_name_asc(conn, filter, limit, after).await,
+        Order::Desc => get_packages_by_name_desc(conn, filter, limit, after).await,
     }
 }
 
-async fn get_packages_with_limit_after_asc(
+async fn get_packages_by_name_asc(
     conn: &mut PgConnection,
+    filter: &PackageFilter,
     limit: u64,
-    after: Uuid,
+    after: Option<(&str, Uuid)>,
 ) -> Result<Vec<Package>> {
     let packages = sqlx::query_as!(
         Package,
-        "SELECT * FROM packages WHERE id > $1 ORDER BY id ASC LIMIT $2;",
-        after,
-        limit as i64
+        r#"SELECT * FROM packages
+        WHERE ($1::text IS NULL OR registry = $1)
+        AND ($2::bigint IS NULL OR downloads >= $2)
+        AND ($3::bigint IS NULL OR downloads <= $3)
+        AND ($4::text IS NULL OR (name, id) > ($4, $5::uuid))
+        AND ($6::boolean OR deleted_at IS NULL)
+        ORDER BY name ASC, id ASC LIMIT $7;"#,
+        filter.registry,
+        filter.min_downloads,
+        filter.max_downloads,
+        after.map(|(value, _)| value),
+        after.map(|(_, id)| id),
+        filter.include_deleted,
+        limit as i64,
     )
     .fetch_all(&mut *conn)
     .instrument(instrument_query(Operation::Select, "packages"))
@@ -144,16 +594,101 @@ async fn get_packages_with_limit_after_asc(
     Ok(packages)
 }
 
-async fn get_packages_with_limit_after_desc(
+async fn get_packages_by_name_desc(
     conn: &mut PgConnection,
+    filter: &PackageFilter,
     limit: u64,
-    after: Uuid,
+    after: Option<(&str, Uuid)>,
 ) -> Result<Vec<Package>> {
     let packages = sqlx::query_as!(
         Package,
-        "SELECT * FROM packages WHERE id < $1 ORDER BY id DESC LIMIT $2;",
-        after,
-        limit as i64
+        r#"SELECT * FROM packages
+        WHERE ($1::text IS NULL OR registry = $1)
+        AND ($2::bigint IS NULL OR downloads >= $2)
+        AND ($3::bigint IS NULL OR downloads <= $3)
+        AND ($4::text IS NULL OR (name, id) < ($4, $5::uuid))
+        AND ($6::boolean OR deleted_at IS NULL)
+        ORDER BY name DESC, id DESC LIMIT $7;"#,
+        filter.registry,
+        filter.min_downloads,
+        filter.max_downloads,
+        after.map(|(value, _)| value),
+        after.map(|(_, id)| id),
+        filter.include_deleted,
+        limit as i64,
+    )
+    .fetch_all(&mut *conn)
+    .instrument(instrument_query(Operation::Select, "packages"))
+    .await?;
+
+    Ok(packages)
+}
+
+async fn get_packages_by_updated_at(
+    conn: &mut PgConnection,
+    filter: &PackageFilter,
+    limit: u64,
+    after: Option<(DateTime<Utc>, Uuid)>,
+    order: Order,
+) -> Result<Vec<Package>> {
+    match order {
+        Order::Asc => get_packages_by_updated_at_asc(conn, filter, limit, after).await,
+        Order::Desc => get_packages_by_updated_at_desc(conn, filter, limit, after).await,
+    }
+}
+
+async fn get_packages_by_updated_at_asc(
+    conn: &mut PgConnection,
+    filter: &PackageFilter,
+    limit: u64,
+    after: Option<(DateTime<Utc>, Uuid)>,
+) -> Result<Vec<Package>> {
+    let packages = sqlx::query_as!(
+        Package,
+        r#"SELECT * FROM packages
+        WHERE ($1::text IS NULL OR registry = $1)
+        AND ($2::bigint IS NULL OR downloads >= $2)
+        AND ($3::bigint IS NULL OR downloads <= $3)
+        AND ($4::timestamptz IS NULL OR (updated_at, id) > ($4, $5::uuid))
+        AND ($6::boolean OR deleted_at IS NULL)
+        ORDER BY updated_at ASC, id ASC LIMIT $7;"#,
+        filter.registry,
+        filter.min_downloads,
+        filter.max_downloads,
+        after.map(|(value, _)| value),
+        after.map(|(_, id)| id),
+        filter.include_deleted,
+        limit as i64,
+    )
+    .fetch_all(&mut *conn)
+    .instrument(instrument_query(Operation::Select, "packages"))
+    .await?;
+
+    Ok(packages)
+}
+
+async fn get_packages_by_updated_at_desc(
+    conn: &mut PgConnection,
+    filter: &PackageFilter,
+    limit: u64,
+    after: Option<(DateTime<Utc>, Uuid)>,
+) -> Result<Vec<Package>> {
+    let packages = sqlx::query_as!(
+        Package,
+        r#"SELECT * FROM packages
+        WHERE ($1::text IS NULL OR registry = $1)
+        AND ($2::bigint IS NULL OR downloads >= $2)
+        AND ($3::bigint IS NULL OR downloads <= $3)
+        AND ($4::timestamptz IS NULL OR (updated_at, id) < ($4, $5::uuid))
+        AND ($6::boolean OR deleted_at IS NULL)
+        ORDER BY updated_at DESC, id DESC LIMIT $7;"#,
+        filter.registry,
+        filter.min_downloads,
+        filter.max_downloads,
+        after.map(|(value, _)| value),
+        after.map(|(_, id)| id),
+        filter.include_deleted,
+        limit as i64,
     )
     .fetch_all(&mut *conn)
     .instrument(instrument_query(Operation::Select, "packages"))
@@ -161,3 +696,24 @@ async fn get_packages_with_limit_after_desc(
 
     Ok(packages)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_text_array_escapes_elements() {
+        let values = vec![
+            "async".to_string(),
+            "a,b".to_string(),
+            "say \"hi\"".to_string(),
+            "back\\slash".to_string(),
+        ];
+
+        assert_eq!(
+            text_array(&values),
+            r#"{"async","a,b","say \"hi\"","back\\slash"}"#
+        );
+        assert_eq!(text_array(&[]), "{}");
+    }
+}
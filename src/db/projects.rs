@@ -0,0 +1,146 @@
+use anyhow::Result;
+use sqlx::PgConnection;
+use tracing::{instrument, Instrument};
+use uuid::Uuid;
+
+use crate::{
+    models::{package::Package, project::Project},
+    telemetry::{instrument_query, Operation},
+};
+
+#[instrument(name = "insert_project", skip(conn))]
+pub async fn insert_project(conn: &mut PgConnection, project: Project) -> Result<Project> {
+    let project = sqlx::query_as!(
+        Project,
+        "INSERT INTO projects (id, name, repository_url, created_at) VALUES ($1, $2, normalize_repository_url($3), $4) RETURNING *;",
+        project.id,
+        project.name,
+        project.repository_url,
+        project.created_at,
+    )
+    .fetch_one(&mut *conn)
+    .instrument(instrument_query(Operation::Insert, "projects"))
+    .await?;
+
+    Ok(project)
+}
+
+#[instrument(name = "upsert_project", skip(conn))]
+pub async fn upsert_project(conn: &mut PgConnection, project: Project) -> Result<Project> {
+    let project = sqlx::query_as!(
+        Project,
+        "INSERT INTO projects (id, name, repository_url, created_at) VALUES ($1, $2, normalize_repository_url($3), $4) ON CONFLICT (repository_url) DO UPDATE SET repository_url = EXCLUDED.repository_url RETURNING *;",
+        project.id,
+        project.name,
+        project.repository_url,
+        project.created_at,
+    )
+    .fetch_one(&mut *conn)
+    .instrument(instrument_query(Operation::Insert, "projects"))
+    .await?;
+
+    Ok(project)
+}
+
+#[instrument(name = "get_project", skip(conn))]
+pub async fn get_project(conn: &mut PgConnection, id: Uuid) -> Result<Option<Project>> {
+    let project = sqlx::query_as!(Project, "SELECT * FROM projects WHERE id = $1;", id)
+        .fetch_optional(&mut *conn)
+        .instrument(instrument_query(Operation::Select, "projects"))
+        .await?;
+
+    Ok(project)
+}
+
+#[instrument(name = "get_project_by_repository_url", skip(conn))]
+pub async fn get_project_by_repository_url(
+    conn: &mut PgConnection,
+    repository_url: &str,
+) -> Result<Option<Project>> {
+    let project = sqlx::query_as!(
+        Project,
+        "SELECT * FROM projects WHERE repository_url = normalize_repository_url($1);",
+        repository_url,
+    )
+    .fetch_optional(&mut *conn)
+    .instrument(instrument_query(Operation::Select, "projects"))
+    .await?;
+
+    Ok(project)
+}
+
+#[instrument(name = "has_cross_registry_match", skip(conn))]
+pub async fn has_cross_registry_match(conn: &mut PgConnection, package_id: Uuid) -> Result<bool> {
+    let matched = sqlx::query_scalar!(
+        r#"SELECT EXISTS (
+            SELECT 1 FROM packages p
+            JOIN packages o
+                ON normalize_repository_url(o.repository_url) = normalize_repository_url(p.repository_url)
+            WHERE p.id = $1
+            AND o.registry <> p.registry
+            AND o.deleted_at IS NULL
+        ) AS "matched!";"#,
+        package_id,
+    )
+    .fetch_one(&mut *conn)
+    .instrument(instrument_query(Operation::Select, "packages"))
+    .await?;
+
+    Ok(matched)
+}
+
+#[instrument(name = "link_packages_by_repository", skip(conn))]
+pub async fn link_packages_by_repository(
+    conn: &mut PgConnection,
+    project_id: Uuid,
+    repository_url: &str,
+) -> Result<u64> {
+    let result = sqlx::query!(
+        "UPDATE packages SET project_id = $1 WHERE project_id IS NULL AND normalize_repository_url(repository_url) = normalize_repository_url($2);",
+        project_id,
+        repository_url,
+    )
+    .execute(&mut *conn)
+    .instrument(instrument_query(Operation::Update, "packages"))
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
+#[instrument(name = "link_package", skip(conn))]
+pub async fn link_package(
+    conn: &mut PgConnection,
+    project_id: Uuid,
+    registry: &str,
+    name: &str,
+) -> Result<Option<Package>> {
+    let package = sqlx::query_as!(
+        Package,
+        "UPDATE packages SET project_id = $1 WHERE registry = $2 AND name = $3 AND deleted_at IS NULL RETURNING *;",
+        project_id,
+        registry,
+        name,
+    )
+    .fetch_optional(&mut *conn)
+    .instrument(instrument_query(Operation::Update, "packages"))
+    .await?;
+
+    Ok(package)
+}
+
+#[instrument(name = "get_project_packages", skip(conn))]
+pub async fn get_project_packages(
+    conn: &mut PgConnection,
+    project_id: Uuid,
+) -> Result<Vec<Package>> {
+    let packages = sqlx::query_as!(
+        Package,
+        "SELECT * FROM packages WHERE project_id = $1 AND deleted_at IS NULL ORDER BY registry ASC, name ASC;",
+        project_id,
+    )
+    .fetch_all(&mut *conn)
+    .instrument(instrument_query(Operation::Select, "packages"))
+    .await?;
+
+    Ok(packages)
+}
@@ -0,0 +1,107 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use sqlx::PgConnection;
+use tracing::{instrument, Instrument};
+use uuid::Uuid;
+
+use crate::{
+    models::quarantine::QuarantinedMessage,
+    telemetry::{instrument_query, Operation},
+};
+
+use super::types::Order;
+
+#[instrument(name = "insert_quarantined_message", skip(conn, message), fields(id = %message.id))]
+pub async fn insert_quarantined_message(
+    conn: &mut PgConnection,
+    message: QuarantinedMessage,
+) -> Result<QuarantinedMessage> {
+    let message = sqlx::query_as!(
+        QuarantinedMessage,
+        "INSERT INTO quarantined_messages (id, routing_key, headers, error, object_key, size, created_at) VALUES ($1, $2, $3, $4, $5, $6, $7) RETURNING *;",
+        message.id,
+        message.routing_key,
+        message.headers,
+        message.error,
+        message.object_key,
+        message.size,
+        message.created_at,
+    )
+    .fetch_one(&mut *conn)
+    .instrument(instrument_query(Operation::Insert, "quarantined_messages"))
+    .await?;
+
+    Ok(message)
+}
+
+#[instrument(name = "get_quarantined_message", skip(conn))]
+pub async fn get_quarantined_message(
+    conn: &mut PgConnection,
+    id: Uuid,
+) -> Result<Option<QuarantinedMessage>> {
+    let message = sqlx::query_as!(
+        QuarantinedMessage,
+        "SELECT * FROM quarantined_messages WHERE id = $1;",
+        id,
+    )
+    .fetch_optional(&mut *conn)
+    .instrument(instrument_query(Operation::Select, "quarantined_messages"))
+    .await?;
+
+    Ok(message)
+}
+
+#[instrument(name = "get_quarantined_messages", skip(conn))]
+pub async fn get_quarantined_messages(
+    conn: &mut PgConnection,
+    limit: u64,
+    after: Option<Uuid>,
+    order: Order,
+) -> Result<Vec<QuarantinedMessage>> {
+    let messages = match order {
+        Order::Asc => {
+            sqlx::query_as!(
+                QuarantinedMessage,
+                "SELECT * FROM quarantined_messages WHERE $1::uuid IS NULL OR id > $1 ORDER BY id ASC LIMIT $2;",
+                after,
+                limit as i64,
+            )
+            .fetch_all(&mut *conn)
+            .instrument(instrument_query(Operation::Select, "quarantined_messages"))
+            .await?
+        }
+        Order::Desc => {
+            sqlx::query_as!(
+                QuarantinedMessage,
+                "SELECT * FROM quarantined_messages WHERE $1::uuid IS NULL OR id < $1 ORDER BY id DESC LIMIT $2;",
+                after,
+                limit as i64,
+            )
+            .fetch_all(&mut *conn)
+            .instrument(instrument_query(Operation::Select, "quarantined_messages"))
+            .await?
+        }
+    };
+
+    Ok(messages)
+}
+
+// Only the first re-injection marks a message, so a message is never published twice.
+#[instrument(name = "mark_quarantined_message_reinjected", skip(conn))]
+pub async fn mark_quarantined_message_reinjected(
+    conn: &mut PgConnection,
+    id: Uuid,
+    reinjected_at: DateTime<Utc>,
+) -> Result<Option<QuarantinedMessage>> {
+    let message = sqlx::query_as!(
+        QuarantinedMessage,
+        "UPDATE quarantined_messages SET reinjected_at = $2 WHERE id = $1 AND reinjected_at IS NULL RETURNING *;",
+        id,
+        reinjected_at,
+    )
+    .fetch_optional(&mut *conn)
+    .instrument(instrument_query(Operation::Update, "quarantined_messages"))
+    .await?;
+
+    Ok(message)
+}
@@ -0,0 +1,285 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use sqlx::PgConnection;
+use tracing::{instrument, Instrument};
+use uuid::Uuid;
+
+use crate::{
+    models::{
+        package::Package,
+        ranking::{RankedPackage, TrendingPackage},
+    },
+    telemetry::{instrument_query, Operation},
+};
+
+use super::{GrowthBy, RankBy, TrendingWindow};
+
+#[instrument(name = "refresh_package_rankings", skip(conn))]
+pub async fn refresh_package_rankings(conn: &mut PgConnection) -> Result<()> {
+    sqlx::query!("REFRESH MATERIALIZED VIEW CONCURRENTLY package_rankings;")
+        .execute(&mut *conn)
+        .instrument(instrument_query(Operation::Refresh, "package_rankings"))
+        .await?;
+
+    Ok(())
+}
+
+#[instrument(name = "refresh_package_growth", skip(conn))]
+pub async fn refresh_package_growth(conn: &mut PgConnection) -> Result<()> {
+    sqlx::query!("REFRESH MATERIALIZED VIEW CONCURRENTLY package_growth;")
+        .execute(&mut *conn)
+        .instrument(instrument_query(Operation::Refresh, "package_growth"))
+        .await?;
+
+    Ok(())
+}
+
+#[instrument(name = "get_top_packages", skip(conn))]
+pub async fn get_top_packages(
+    conn: &mut PgConnection,
+    registry: &str,
+    by: RankBy,
+    limit: u64,
+) -> Result<Vec<RankedPackage>> {
+    match by {
+        RankBy::Downloads => get_top_packages_by_downloads(conn, registry, limit).await,
+        RankBy::Growth => get_top_packages_by_growth(conn, registry, limit).await,
+    }
+}
+
+async fn get_top_packages_by_downloads(
+    conn: &mut PgConnection,
+    registry: &str,
+    limit: u64,
+) -> Result<Vec<RankedPackage>> {
+    let packages = sqlx::query_as!(
+        RankedPackageRow,
+        r#"SELECT
+            r.downloads_rank AS "rank!",
+            r.downloads_percentile AS "percentile!",
+            r.downloads_growth AS "downloads_growth!",
+            p.id, p.registry, p.name, p.version, p.downloads, p.updated_at,
+            p.description, p.license, p.repository_url, p.homepage, p.keywords,
+            p.published_at, p.deprecated, p.created_at, p.last_refreshed_at, p.last_job_id,
+            p.deleted_at, p.deletion_reason, p.project_id, p.revision
+        FROM package_rankings r
+        JOIN packages p ON p.id = r.package_id
+        WHERE r.registry = $1 AND p.deleted_at IS NULL
+        ORDER BY r.downloads_rank ASC, p.id ASC
+        LIMIT $2;"#,
+        registry,
+        limit as i64,
+    )
+    .fetch_all(&mut *conn)
+    .instrument(instrument_query(Operation::Select, "package_rankings"))
+    .await?;
+
+    Ok(packages.into_iter().map(Into::into).collect())
+}
+
+async fn get_top_packages_by_growth(
+    conn: &mut PgConnection,
+    registry: &str,
+    limit: u64,
+) -> Result<Vec<RankedPackage>> {
+    let packages = sqlx::query_as!(
+        RankedPackageRow,
+        r#"SELECT
+            r.growth_rank AS "rank!",
+            r.growth_percentile AS "percentile!",
+            r.downloads_growth AS "downloads_growth!",
+            p.id, p.registry, p.name, p.version, p.downloads, p.updated_at,
+            p.description, p.license, p.repository_url, p.homepage, p.keywords,
+            p.published_at, p.deprecated, p.created_at, p.last_refreshed_at, p.last_job_id,
+            p.deleted_at, p.deletion_reason, p.project_id, p.revision
+        FROM package_rankings r
+        JOIN packages p ON p.id = r.package_id
+        WHERE r.registry = $1 AND p.deleted_at IS NULL
+        ORDER BY r.growth_rank ASC, p.id ASC
+        LIMIT $2;"#,
+        registry,
+        limit as i64,
+    )
+    .fetch_all(&mut *conn)
+    .instrument(instrument_query(Operation::Select, "package_rankings"))
+    .await?;
+
+    Ok(packages.into_iter().map(Into::into).collect())
+}
+
+// Packages below the minimum downloads at the start of the window are left out so
+// small absolute changes don't dominate relative growth. The window's columns are picked
+// once, and only the key selected by `by` is non-null in the ordering.
+#[instrument(name = "get_trending_packages", skip(conn))]
+pub async fn get_trending_packages(
+    conn: &mut PgConnection,
+    registry: Option<&str>,
+    window: TrendingWindow,
+    by: GrowthBy,
+    min_downloads: i64,
+    limit: u64,
+) -> Result<Vec<TrendingPackage>> {
+    let packages = sqlx::query_as!(
+        TrendingPackageRow,
+        r#"SELECT
+            RANK() OVER (
+                ORDER BY
+                    CASE WHEN $3 THEN w.relative_growth END DESC NULLS LAST,
+                    CASE WHEN NOT $3 THEN w.growth END DESC NULLS LAST
+            ) AS "rank!",
+            w.previous_downloads AS "previous_downloads!",
+            w.growth AS "downloads_growth!",
+            w.relative_growth AS "relative_growth!",
+            p.id, p.registry, p.name, p.version, p.downloads, p.updated_at,
+            p.description, p.license, p.repository_url, p.homepage, p.keywords,
+            p.published_at, p.deprecated, p.created_at, p.last_refreshed_at, p.last_job_id,
+            p.deleted_at, p.deletion_reason, p.project_id, p.revision
+        FROM package_growth g
+        CROSS JOIN LATERAL (
+            SELECT
+                CASE WHEN $2 THEN g.downloads_7d_ago ELSE g.downloads_30d_ago END AS previous_downloads,
+                CASE WHEN $2 THEN g.growth_7d ELSE g.growth_30d END AS growth,
+                CASE WHEN $2 THEN g.relative_growth_7d ELSE g.relative_growth_30d END AS relative_growth
+        ) w
+        JOIN packages p ON p.id = g.package_id
+        WHERE ($1::text IS NULL OR g.registry = $1)
+            AND w.previous_downloads >= $4
+            AND w.relative_growth IS NOT NULL
+            AND w.growth > 0
+            AND p.deleted_at IS NULL
+        ORDER BY
+            CASE WHEN $3 THEN w.relative_growth END DESC NULLS LAST,
+            CASE WHEN NOT $3 THEN w.growth END DESC NULLS LAST,
+            p.id ASC
+        LIMIT $5;"#,
+        registry,
+        window == TrendingWindow::Week,
+        by == GrowthBy::Relative,
+        min_downloads,
+        limit as i64,
+    )
+    .fetch_all(&mut *conn)
+    .instrument(instrument_query(Operation::Select, "package_growth"))
+    .await?;
+
+    Ok(packages.into_iter().map(Into::into).collect())
+}
+
+// `query_as!` cannot fill the nested `Package`, so rows are read flat and nested here.
+struct RankedPackageRow {
+    rank: i64,
+    percentile: f64,
+    downloads_growth: i64,
+    id: Uuid,
+    registry: String,
+    name: String,
+    version: String,
+    downloads: i64,
+    updated_at: DateTime<Utc>,
+    description: Option<String>,
+    license: Option<String>,
+    repository_url: Option<String>,
+    homepage: Option<String>,
+    keywords: Vec<String>,
+    published_at: Option<DateTime<Utc>>,
+    deprecated: bool,
+    created_at: DateTime<Utc>,
+    last_refreshed_at: DateTime<Utc>,
+    last_job_id: Option<Uuid>,
+    deleted_at: Option<DateTime<Utc>>,
+    deletion_reason: Option<String>,
+    project_id: Option<Uuid>,
+    revision: i64,
+}
+
+impl From<RankedPackageRow> for RankedPackage {
+    fn from(row: RankedPackageRow) -> Self {
+        Self {
+            rank: row.rank,
+            percentile: row.percentile,
+            downloads_growth: row.downloads_growth,
+            package: Package {
+                id: row.id,
+                registry: row.registry,
+                name: row.name,
+                version: row.version,
+                downloads: row.downloads,
+                updated_at: row.updated_at,
+                description: row.description,
+                license: row.license,
+                repository_url: row.repository_url,
+                homepage: row.homepage,
+                keywords: row.keywords,
+                published_at: row.published_at,
+                deprecated: row.deprecated,
+                created_at: row.created_at,
+                last_refreshed_at: row.last_refreshed_at,
+                last_job_id: row.last_job_id,
+                deleted_at: row.deleted_at,
+                deletion_reason: row.deletion_reason,
+                project_id: row.project_id,
+                revision: row.revision,
+            },
+        }
+    }
+}
+
+struct TrendingPackageRow {
+    rank: i64,
+    previous_downloads: i64,
+    downloads_growth: i64,
+    relative_growth: f64,
+    id: Uuid,
+    registry: String,
+    name: String,
+    version: String,
+    downloads: i64,
+    updated_at: DateTime<Utc>,
+    description: Option<String>,
+    license: Option<String>,
+    repository_url: Option<String>,
+    homepage: Option<String>,
+    keywords: Vec<String>,
+    published_at: Option<DateTime<Utc>>,
+    deprecated: bool,
+    created_at: DateTime<Utc>,
+    last_refreshed_at: DateTime<Utc>,
+    last_job_id: Option<Uuid>,
+    deleted_at: Option<DateTime<Utc>>,
+    deletion_reason: Option<String>,
+    project_id: Option<Uuid>,
+    revision: i64,
+}
+
+impl From<TrendingPackageRow> for TrendingPackage {
+    fn from(row: TrendingPackageRow) -> Self {
+        Self {
+            rank: row.rank,
+            previous_downloads: row.previous_downloads,
+            downloads_growth: row.downloads_growth,
+            relative_growth: row.relative_growth,
+            package: Package {
+                id: row.id,
+                registry: row.registry,
+                name: row.name,
+                version: row.version,
+                downloads: row.downloads,
+                updated_at: row.updated_at,
+                description: row.description,
+                license: row.license,
+                repository_url: row.repository_url,
+                homepage: row.homepage,
+                keywords: row.keywords,
+                published_at: row.published_at,
+                deprecated: row.deprecated,
+                created_at: row.created_at,
+                last_refreshed_at: row.last_refreshed_at,
+                last_job_id: row.last_job_id,
+                deleted_at: row.deleted_at,
+                deletion_reason: row.deletion_reason,
+                project_id: row.project_id,
+                revision: row.revision,
+            },
+        }
+    }
+}
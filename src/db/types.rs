@@ -1,6 +1,10 @@
 use std::fmt::Display;
 
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::types::{decode_cursor, encode_cursor};
 
 #[derive(Debug, Deserialize, Serialize, PartialEq, Copy, Clone)]
 pub enum Order {
@@ -23,6 +27,101 @@ impl Default for Order {
     }
 }
 
+#[derive(Debug, Default, PartialEq, Copy, Clone)]
+pub enum PackageSort {
+    #[default]
+    Id,
+    Downloads,
+    Name,
+    UpdatedAt,
+}
+
+#[derive(Debug, Default, PartialEq, Copy, Clone)]
+pub enum RankBy {
+    #[default]
+    Downloads,
+    Growth,
+}
+
+#[derive(Debug, Default, PartialEq, Copy, Clone)]
+pub enum TrendingWindow {
+    #[default]
+    Week,
+    Month,
+}
+
+#[derive(Debug, Default, PartialEq, Copy, Clone)]
+pub enum GrowthBy {
+    #[default]
+    Relative,
+    Absolute,
+}
+
+#[derive(Debug, Default)]
+pub struct PackageFilter {
+    pub registry: Option<String>,
+    pub min_downloads: Option<i64>,
+    pub max_downloads: Option<i64>,
+    pub include_deleted: bool,
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Clone)]
+#[serde(tag = "sort", content = "key", rename_all = "snake_case")]
+pub enum PackageCursor {
+    Id(Uuid),
+    Downloads(i64, Uuid),
+    Name(String, Uuid),
+    UpdatedAt(DateTime<Utc>, Uuid),
+}
+
+impl PackageCursor {
+    pub fn encode(&self) -> String {
+        encode_cursor(self)
+    }
+
+    pub fn decode(cursor: &str) -> Option<Self> {
+        decode_cursor(cursor)
+    }
+
+    pub fn sort(&self) -> PackageSort {
+        match self {
+            Self::Id(_) => PackageSort::Id,
+            Self::Downloads(..) => PackageSort::Downloads,
+            Self::Name(..) => PackageSort::Name,
+            Self::UpdatedAt(..) => PackageSort::UpdatedAt,
+        }
+    }
+
+    pub fn id(&self) -> Uuid {
+        match self {
+            Self::Id(id) | Self::Downloads(_, id) | Self::Name(_, id) | Self::UpdatedAt(_, id) => {
+                *id
+            }
+        }
+    }
+
+    pub fn downloads(&self) -> Option<(i64, Uuid)> {
+        match self {
+            Self::Downloads(downloads, id) => Some((*downloads, *id)),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> Option<(&str, Uuid)> {
+        match self {
+            Self::Name(name, id) => Some((name.as_str(), *id)),
+            _ => None,
+        }
+    }
+
+    pub fn updated_at(&self) -> Option<(DateTime<Utc>, Uuid)> {
+        match self {
+            Self::UpdatedAt(updated_at, id) => Some((*updated_at, *id)),
+            _ => None,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -39,4 +138,34 @@ mod tests {
         assert_eq!(format!("{}", Order::Asc), "ASC");
         assert_eq!(format!("{}", Order::Desc), "DESC");
     }
+
+    #[test]
+    fn test_package_sort_default() {
+        assert_eq!(PackageSort::default(), PackageSort::Id);
+    }
+
+    #[test]
+    fn test_package_cursor_round_trips() {
+        let id = Uuid::from_u64_pair(1, 2);
+        for cursor in [
+            PackageCursor::Id(id),
+            PackageCursor::Downloads(10, id),
+            PackageCursor::Name("serde".to_string(), id),
+            PackageCursor::UpdatedAt(Utc::now(), id),
+        ] {
+            assert_eq!(PackageCursor::decode(&cursor.encode()), Some(cursor));
+        }
+    }
+
+    #[test]
+    fn test_package_cursor_exposes_only_its_own_sort_key() {
+        let id = Uuid::from_u64_pair(1, 2);
+        let cursor = PackageCursor::Downloads(10, id);
+
+        assert_eq!(cursor.sort(), PackageSort::Downloads);
+        assert_eq!(cursor.id(), id);
+        assert_eq!(cursor.downloads(), Some((10, id)));
+        assert_eq!(cursor.name(), None);
+        assert_eq!(cursor.updated_at(), None);
+    }
 }
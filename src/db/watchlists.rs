@@ -0,0 +1,173 @@
+use anyhow::Result;
+use sqlx::PgConnection;
+use tracing::{instrument, Instrument};
+use uuid::Uuid;
+
+use crate::{
+    models::watchlist::{WatchedPackage, Watchlist, WatchlistTarget},
+    telemetry::{instrument_query, Operation},
+};
+
+#[instrument(name = "insert_watchlist", skip(conn))]
+pub async fn insert_watchlist(conn: &mut PgConnection, watchlist: Watchlist) -> Result<Watchlist> {
+    let watchlist = sqlx::query_as!(
+        Watchlist,
+        "INSERT INTO watchlists (id, name, created_at) VALUES ($1, $2, $3) RETURNING *;",
+        watchlist.id,
+        watchlist.name,
+        watchlist.created_at,
+    )
+    .fetch_one(&mut *conn)
+    .instrument(instrument_query(Operation::Insert, "watchlists"))
+    .await?;
+
+    Ok(watchlist)
+}
+
+#[instrument(name = "get_watchlist", skip(conn))]
+pub async fn get_watchlist(conn: &mut PgConnection, id: Uuid) -> Result<Option<Watchlist>> {
+    let watchlist = sqlx::query_as!(Watchlist, "SELECT * FROM watchlists WHERE id = $1;", id)
+        .fetch_optional(&mut *conn)
+        .instrument(instrument_query(Operation::Select, "watchlists"))
+        .await?;
+
+    Ok(watchlist)
+}
+
+#[instrument(name = "delete_watchlist", skip(conn))]
+pub async fn delete_watchlist(
+    conn: &mut PgConnection,
+    id: Uuid,
+    revisions: Option<&[i64]>,
+) -> Result<bool> {
+    let result = sqlx::query!(
+        "DELETE FROM watchlists WHERE id = $1 AND ($2::bigint[] IS NULL OR revision = ANY($2));",
+        id,
+        revisions,
+    )
+    .execute(&mut *conn)
+    .instrument(instrument_query(Operation::Delete, "watchlists"))
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+// The watchlist row is locked before its revision is checked, so a concurrent edit holding
+// the same ETag sees the bumped revision once it gets the lock.
+#[instrument(name = "insert_watched_packages", skip(conn, packages))]
+pub async fn insert_watched_packages(
+    conn: &mut PgConnection,
+    watchlist_id: Uuid,
+    packages: &[WatchedPackage],
+    revisions: Option<&[i64]>,
+) -> Result<bool> {
+    let registries: Vec<&str> = packages.iter().map(|p| p.registry.as_str()).collect();
+    let names: Vec<&str> = packages.iter().map(|p| p.name.as_str()).collect();
+
+    let matched = sqlx::query_scalar!(
+        r#"WITH watchlist AS (
+            SELECT id FROM watchlists WHERE id = $1 AND ($4::bigint[] IS NULL OR revision = ANY($4)) FOR UPDATE
+        ), inserted AS (
+            INSERT INTO watchlist_packages (watchlist_id, registry, name)
+            SELECT watchlist.id, packages.registry, packages.name
+            FROM watchlist, UNNEST($2::text[], $3::text[]) AS packages (registry, name)
+            ON CONFLICT DO NOTHING
+        )
+        SELECT EXISTS (SELECT 1 FROM watchlist) AS "matched!";"#,
+        watchlist_id,
+        &registries as &[&str],
+        &names as &[&str],
+        revisions,
+    )
+    .fetch_one(&mut *conn)
+    .instrument(instrument_query(Operation::Insert, "watchlist_packages"))
+    .await?;
+
+    Ok(matched)
+}
+
+#[instrument(name = "delete_watched_package", skip(conn))]
+pub async fn delete_watched_package(
+    conn: &mut PgConnection,
+    watchlist_id: Uuid,
+    registry: &str,
+    name: &str,
+    revisions: Option<&[i64]>,
+) -> Result<bool> {
+    let result = sqlx::query!(
+        r#"WITH watchlist AS (
+            SELECT id FROM watchlists WHERE id = $1 AND ($4::bigint[] IS NULL OR revision = ANY($4)) FOR UPDATE
+        )
+        DELETE FROM watchlist_packages
+        WHERE watchlist_id IN (SELECT id FROM watchlist) AND registry = $2 AND name = $3;"#,
+        watchlist_id,
+        registry,
+        name,
+        revisions,
+    )
+    .execute(&mut *conn)
+    .instrument(instrument_query(Operation::Delete, "watchlist_packages"))
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+#[instrument(name = "get_watched_packages", skip(conn))]
+pub async fn get_watched_packages(
+    conn: &mut PgConnection,
+    watchlist_id: Uuid,
+) -> Result<Vec<WatchedPackage>> {
+    let packages = sqlx::query_as!(
+        WatchedPackage,
+        "SELECT registry, name FROM watchlist_packages WHERE watchlist_id = $1 ORDER BY registry ASC, name ASC;",
+        watchlist_id,
+    )
+    .fetch_all(&mut *conn)
+    .instrument(instrument_query(Operation::Select, "watchlist_packages"))
+    .await?;
+
+    Ok(packages)
+}
+
+#[instrument(name = "insert_watchlist_targets", skip(conn, targets))]
+pub async fn insert_watchlist_targets(
+    conn: &mut PgConnection,
+    watchlist_id: Uuid,
+    targets: &[WatchlistTarget],
+) -> Result<()> {
+    let ids: Vec<Uuid> = targets.iter().map(|t| t.id).collect();
+    let channels: Vec<String> = targets.iter().map(|t| t.channel.to_string()).collect();
+    let values: Vec<&str> = targets.iter().map(|t| t.target.as_str()).collect();
+
+    sqlx::query!(
+        r#"INSERT INTO watchlist_targets (id, watchlist_id, channel, target)
+        SELECT id, $1, channel, target FROM UNNEST($2::uuid[], $3::text[], $4::text[]) AS t (id, channel, target)
+        ON CONFLICT DO NOTHING;"#,
+        watchlist_id,
+        &ids,
+        &channels,
+        &values as &[&str],
+    )
+    .execute(&mut *conn)
+    .instrument(instrument_query(Operation::Insert, "watchlist_targets"))
+    .await?;
+
+    Ok(())
+}
+
+#[instrument(name = "get_watchlist_targets", skip(conn))]
+pub async fn get_watchlist_targets(
+    conn: &mut PgConnection,
+    watchlist_id: Uuid,
+) -> Result<Vec<WatchlistTarget>> {
+    let targets = sqlx::query_as!(
+        WatchlistTarget,
+        "SELECT id, channel, target FROM watchlist_targets WHERE watchlist_id = $1 ORDER BY id ASC;",
+        watchlist_id,
+    )
+    .fetch_all(&mut *conn)
+    .instrument(instrument_query(Operation::Select, "watchlist_targets"))
+    .await?;
+
+    Ok(targets)
+}
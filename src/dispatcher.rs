@@ -0,0 +1,111 @@
+use std::{collections::HashMap, sync::Arc};
+
+use anyhow::Context;
+use axum_tracing_opentelemetry::tracing_opentelemetry_instrumentation_sdk::find_current_trace_id;
+use chrono::Utc;
+use sqlx::{Pool, Postgres};
+use tracing::instrument;
+use uuid::Uuid;
+
+use crate::{
+    broker::Broker,
+    db,
+    error::Error,
+    models::job::{Job, JobSource, JobStatus},
+    outputs::OutputKeys,
+    services::rabbitmq,
+    types::JobMessage,
+};
+
+#[derive(Clone)]
+pub struct Dispatcher {
+    db_pool: Pool<Postgres>,
+    broker: Broker,
+    exchange_name: String,
+    integration_queues: Arc<HashMap<String, String>>,
+    output_keys: OutputKeys,
+}
+
+impl Dispatcher {
+    pub fn new(
+        db_pool: Pool<Postgres>,
+        broker: Broker,
+        exchange_name: String,
+        integration_queues: HashMap<String, String>,
+        output_keys: OutputKeys,
+    ) -> Self {
+        Self {
+            db_pool,
+            broker,
+            exchange_name,
+            integration_queues: Arc::new(integration_queues),
+            output_keys,
+        }
+    }
+
+    pub fn registries(&self) -> impl Iterator<Item = &str> {
+        self.integration_queues
+            .keys()
+            .map(|registry| registry.as_str())
+    }
+
+    #[instrument(name = "dispatch_job", skip(self))]
+    pub async fn dispatch(
+        &self,
+        registry: String,
+        package_name: String,
+        force: bool,
+        source: JobSource,
+    ) -> Result<Job, Error> {
+        let id = Uuid::now_v7();
+        let trace_id = find_current_trace_id();
+
+        let mut transaction = self.db_pool.begin().await?;
+
+        let routing_key = self
+            .integration_queues
+            .get(&registry)
+            .context("Registry not found")?
+            .clone();
+
+        let job = db::insert_job(
+            &mut transaction,
+            Job {
+                id,
+                output_key: Some(self.output_keys.key(&registry, &package_name, id)),
+                registry,
+                package_name,
+                status: JobStatus::Processing,
+                trace_id: trace_id.clone(),
+                created_at: Utc::now(),
+                force,
+                skipped_version: None,
+                source,
+                errors: None,
+            },
+        )
+        .await?;
+
+        transaction.commit().await?;
+
+        let message = JobMessage {
+            job_id: job.id,
+            registry: job.registry.clone(),
+            package_name: job.package_name.clone(),
+            force: job.force,
+            output_key: job.output_key.clone(),
+        };
+        self.publish(&routing_key, &message).await?;
+
+        Ok(job)
+    }
+
+    #[instrument(name = "publish_job_message", skip(self, message), fields(job_id = %message.job_id))]
+    pub async fn publish(&self, routing_key: &str, message: &JobMessage) -> Result<(), Error> {
+        let channel = self.broker.create_channel().await?;
+
+        rabbitmq::publish_message(&channel, &self.exchange_name, routing_key, message).await?;
+
+        Ok(())
+    }
+}
@@ -1,5 +1,5 @@
 use axum::{
-    http::StatusCode,
+    http::{header::ETAG, HeaderValue, StatusCode},
     response::{IntoResponse, Response},
     Json,
 };
@@ -11,6 +11,11 @@ pub enum Error {
     InvalidInput(String),
     #[error("{0}")]
     NotFound(String),
+    #[error("{0}")]
+    Conflict(String),
+    // Carries the ETag of the current representation.
+    #[error("Precondition failed")]
+    PreconditionFailed(HeaderValue),
     #[error(transparent)]
     Io(#[from] std::io::Error),
     #[error(transparent)]
@@ -35,6 +40,17 @@ impl IntoResponse for Error {
             Error::NotFound(message) => {
                 (StatusCode::NOT_FOUND, Json(ErrorResponse { message })).into_response()
             }
+            Error::Conflict(message) => {
+                (StatusCode::CONFLICT, Json(ErrorResponse { message })).into_response()
+            }
+            Error::PreconditionFailed(etag) => (
+                StatusCode::PRECONDITION_FAILED,
+                [(ETAG, etag)],
+                Json(ErrorResponse {
+                    message: "Precondition failed".to_string(),
+                }),
+            )
+                .into_response(),
             Error::Io(_) | Error::Unknown(_) | Error::Sqlx(_) | Error::RabbitMQ(_) => {
                 tracing::error!(
                     error = ?self,
@@ -0,0 +1,73 @@
+use std::{sync::Arc, time::Duration};
+
+use anyhow::Result;
+use chrono::Utc;
+use sqlx::{Pool, Postgres};
+use tokio_util::sync::CancellationToken;
+
+use crate::{db, models::job::JobStatus, telemetry::Metrics};
+
+// Publishes job counts from the database, which the worker's own counters cannot see across
+// restarts or other instances.
+pub struct JobStatsCollector {
+    db_pool: Pool<Postgres>,
+    interval: Duration,
+    metrics: Arc<Metrics>,
+}
+
+impl JobStatsCollector {
+    pub fn build(db_pool: Pool<Postgres>, interval: Duration, metrics: Arc<Metrics>) -> Self {
+        Self {
+            db_pool,
+            interval,
+            metrics,
+        }
+    }
+
+    pub async fn run_until_stopped(self, shutdown: CancellationToken) -> Result<()> {
+        let mut interval = tokio::time::interval(self.interval);
+        loop {
+            tokio::select! {
+                _ = interval.tick() => {}
+                _ = shutdown.cancelled() => return Ok(()),
+            }
+
+            if let Err(error) = self.collect().await {
+                tracing::error!(error = ?error, "Failed to collect job stats");
+            }
+        }
+    }
+
+    pub async fn collect(&self) -> Result<()> {
+        let mut conn = self.db_pool.acquire().await?;
+        let counts = db::count_jobs_by_status(&mut conn).await?;
+
+        // Statuses without jobs are missing from the query, but should read as zero.
+        let mut processing = 0;
+        let mut completed = 0;
+        let mut failed = 0;
+        let mut oldest_processing = None;
+        for count in counts {
+            match count.status {
+                JobStatus::Processing => {
+                    processing = count.count;
+                    oldest_processing = count.oldest_created_at;
+                }
+                JobStatus::Completed => completed = count.count,
+                JobStatus::Failed => failed = count.count,
+            }
+        }
+
+        let oldest_age = oldest_processing.map_or(0.0, |oldest| {
+            ((Utc::now() - oldest).num_milliseconds() as f64 / 1000.0).max(0.0)
+        });
+        self.metrics.jobs("processing").set(processing as f64);
+        self.metrics.jobs("completed").set(completed as f64);
+        self.metrics.jobs("failed").set(failed as f64);
+        self.metrics
+            .jobs_oldest_processing_age_seconds()
+            .set(oldest_age);
+
+        Ok(())
+    }
+}
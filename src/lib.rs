@@ -1,10 +1,23 @@
 pub mod api;
 pub mod app;
+pub mod broker;
+pub mod cli;
 pub mod config;
 pub mod db;
+pub mod dispatcher;
 pub mod error;
+pub mod job_stats;
 pub mod models;
+pub mod notifier;
+pub mod outputs;
+pub mod parsers;
+pub mod quarantine;
+pub mod rankings;
+pub mod refresher;
+pub mod retry;
+pub mod schemas;
 pub mod services;
 pub mod telemetry;
 pub mod types;
+pub mod versions;
 pub mod worker;
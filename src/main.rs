@@ -1,6 +1,8 @@
 use anyhow::Result;
+use clap::Parser;
 use integrations_api::{
-    app::Application,
+    app::{self, Application},
+    cli::{Cli, Command},
     config::Config,
     telemetry::{init_subscribers, Metrics},
 };
@@ -9,13 +11,21 @@ use integrations_api::{
 async fn main() -> Result<()> {
     dotenvy::dotenv().ok();
 
-    let _guard = init_subscribers()?;
+    let cli = Cli::parse();
+
+    let guard = init_subscribers()?;
 
     let metrics = Metrics::build()?;
     let configuration = Config::build()?;
-    let application = Application::build(configuration, metrics).await?;
 
-    application.run_until_stopped().await?;
+    let result = match cli.command.unwrap_or(Command::All).role() {
+        Some(role) => match Application::build(configuration, metrics, role).await {
+            Ok(application) => application.run_until_stopped().await,
+            Err(error) => Err(error),
+        },
+        None => app::migrate(&configuration).await,
+    };
+    guard.shutdown().await;
 
-    Ok(())
+    result
 }
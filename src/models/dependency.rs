@@ -0,0 +1,18 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Dependency {
+    pub name: String,
+    pub requirement: String,
+    pub kind: String,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct DependencyEdge {
+    pub registry: String,
+    pub package_name: String,
+    pub dependency_name: String,
+    pub requirement: String,
+    pub kind: String,
+    pub depth: i32,
+}
@@ -4,7 +4,7 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-use crate::types::Cursor;
+use crate::types::{CsvRecord, Cursor};
 
 #[derive(Debug, Deserialize, Serialize)]
 pub enum JobStatus {
@@ -12,6 +12,8 @@ pub enum JobStatus {
     Processing,
     #[serde(rename = "completed")]
     Completed,
+    #[serde(rename = "failed")]
+    Failed,
 }
 
 impl From<String> for JobStatus {
@@ -19,6 +21,7 @@ impl From<String> for JobStatus {
         match s.as_str() {
             "processing" => JobStatus::Processing,
             "completed" => JobStatus::Completed,
+            "failed" => JobStatus::Failed,
             _ => {
                 tracing::warn!(status = s, "Invalid job status");
                 JobStatus::Processing
@@ -32,6 +35,37 @@ impl Display for JobStatus {
         match self {
             JobStatus::Processing => write!(f, "processing"),
             JobStatus::Completed => write!(f, "completed"),
+            JobStatus::Failed => write!(f, "failed"),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub enum JobSource {
+    #[serde(rename = "api")]
+    Api,
+    #[serde(rename = "refresher")]
+    Refresher,
+}
+
+impl From<String> for JobSource {
+    fn from(s: String) -> Self {
+        match s.as_str() {
+            "api" => JobSource::Api,
+            "refresher" => JobSource::Refresher,
+            _ => {
+                tracing::warn!(source = s, "Invalid job source");
+                JobSource::Api
+            }
+        }
+    }
+}
+
+impl Display for JobSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            JobSource::Api => write!(f, "api"),
+            JobSource::Refresher => write!(f, "refresher"),
         }
     }
 }
@@ -44,10 +78,56 @@ pub struct Job {
     pub status: JobStatus,
     pub trace_id: Option<String>,
     pub created_at: DateTime<Utc>,
+    pub force: bool,
+    pub skipped_version: Option<String>,
+    pub source: JobSource,
+    pub errors: Option<Vec<String>>,
+    pub output_key: Option<String>,
+}
+
+#[derive(Debug)]
+pub struct JobStatusCount {
+    pub status: JobStatus,
+    pub count: i64,
+    pub oldest_created_at: Option<DateTime<Utc>>,
 }
 
 impl Cursor for Job {
-    fn cursor(&self) -> String {
+    type Sort = ();
+
+    fn cursor(&self, _sort: ()) -> String {
         self.id.to_string()
     }
 }
+
+impl CsvRecord for Job {
+    const HEADER: &'static [&'static str] = &[
+        "id",
+        "registry",
+        "package_name",
+        "status",
+        "trace_id",
+        "created_at",
+        "force",
+        "skipped_version",
+        "source",
+        "errors",
+        "output_key",
+    ];
+
+    fn record(&self) -> Vec<String> {
+        vec![
+            self.id.to_string(),
+            self.registry.clone(),
+            self.package_name.clone(),
+            self.status.to_string(),
+            self.trace_id.clone().unwrap_or_default(),
+            self.created_at.to_rfc3339(),
+            self.force.to_string(),
+            self.skipped_version.clone().unwrap_or_default(),
+            self.source.to_string(),
+            self.errors.as_deref().unwrap_or_default().join("; "),
+            self.output_key.clone().unwrap_or_default(),
+        ]
+    }
+}
@@ -1,2 +1,8 @@
+pub mod dependency;
 pub mod job;
+pub mod notification;
 pub mod package;
+pub mod project;
+pub mod quarantine;
+pub mod ranking;
+pub mod watchlist;
@@ -0,0 +1,80 @@
+use std::fmt::Display;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::watchlist::NotificationChannel;
+
+#[derive(Debug, Deserialize, Serialize, PartialEq)]
+pub enum NotificationStatus {
+    #[serde(rename = "pending")]
+    Pending,
+    #[serde(rename = "delivered")]
+    Delivered,
+    #[serde(rename = "failed")]
+    Failed,
+}
+
+impl From<String> for NotificationStatus {
+    fn from(s: String) -> Self {
+        match s.as_str() {
+            "pending" => NotificationStatus::Pending,
+            "delivered" => NotificationStatus::Delivered,
+            "failed" => NotificationStatus::Failed,
+            _ => {
+                tracing::warn!(status = s, "Invalid notification status");
+                NotificationStatus::Pending
+            }
+        }
+    }
+}
+
+impl Display for NotificationStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NotificationStatus::Pending => write!(f, "pending"),
+            NotificationStatus::Delivered => write!(f, "delivered"),
+            NotificationStatus::Failed => write!(f, "failed"),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Notification {
+    pub id: Uuid,
+    pub target_id: Uuid,
+    pub registry: String,
+    pub package_name: String,
+    pub old_version: String,
+    pub new_version: String,
+    pub status: NotificationStatus,
+    pub attempts: i32,
+    pub last_error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub next_attempt_at: DateTime<Utc>,
+    pub delivered_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug)]
+pub struct PendingNotification {
+    pub id: Uuid,
+    pub channel: NotificationChannel,
+    pub target: String,
+    pub attempts: i32,
+    pub event: VersionChangedEvent,
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq)]
+pub struct VersionChangedEvent {
+    pub event: String,
+    pub registry: String,
+    pub package_name: String,
+    pub old_version: String,
+    pub new_version: String,
+    pub detected_at: DateTime<Utc>,
+}
+
+impl VersionChangedEvent {
+    pub const NAME: &'static str = "package.version_changed";
+}
@@ -1,7 +1,11 @@
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-use crate::types::Cursor;
+use crate::{
+    db::{PackageCursor, PackageSort},
+    types::{CsvRecord, Cursor},
+};
 
 #[derive(Debug, Deserialize, Serialize)]
 pub struct Package {
@@ -10,10 +14,89 @@ pub struct Package {
     pub name: String,
     pub version: String,
     pub downloads: i64,
+    pub updated_at: DateTime<Utc>,
+    pub description: Option<String>,
+    pub license: Option<String>,
+    pub repository_url: Option<String>,
+    pub homepage: Option<String>,
+    pub keywords: Vec<String>,
+    pub published_at: Option<DateTime<Utc>>,
+    pub deprecated: bool,
+    pub created_at: DateTime<Utc>,
+    pub last_refreshed_at: DateTime<Utc>,
+    pub last_job_id: Option<Uuid>,
+    pub deleted_at: Option<DateTime<Utc>>,
+    pub deletion_reason: Option<String>,
+    pub project_id: Option<Uuid>,
+    #[serde(skip)]
+    pub revision: i64,
 }
 
 impl Cursor for Package {
-    fn cursor(&self) -> String {
-        self.id.to_string()
+    type Sort = PackageSort;
+
+    fn cursor(&self, sort: PackageSort) -> String {
+        let cursor = match sort {
+            PackageSort::Id => PackageCursor::Id(self.id),
+            PackageSort::Downloads => PackageCursor::Downloads(self.downloads, self.id),
+            PackageSort::Name => PackageCursor::Name(self.name.clone(), self.id),
+            PackageSort::UpdatedAt => PackageCursor::UpdatedAt(self.updated_at, self.id),
+        };
+
+        cursor.encode()
+    }
+}
+
+impl CsvRecord for Package {
+    const HEADER: &'static [&'static str] = &[
+        "id",
+        "registry",
+        "name",
+        "version",
+        "downloads",
+        "updated_at",
+        "description",
+        "license",
+        "repository_url",
+        "homepage",
+        "keywords",
+        "published_at",
+        "deprecated",
+        "created_at",
+        "last_refreshed_at",
+        "last_job_id",
+        "deleted_at",
+        "deletion_reason",
+        "project_id",
+    ];
+
+    fn record(&self) -> Vec<String> {
+        vec![
+            self.id.to_string(),
+            self.registry.clone(),
+            self.name.clone(),
+            self.version.clone(),
+            self.downloads.to_string(),
+            self.updated_at.to_rfc3339(),
+            self.description.clone().unwrap_or_default(),
+            self.license.clone().unwrap_or_default(),
+            self.repository_url.clone().unwrap_or_default(),
+            self.homepage.clone().unwrap_or_default(),
+            self.keywords.join(","),
+            self.published_at
+                .map(|published_at| published_at.to_rfc3339())
+                .unwrap_or_default(),
+            self.deprecated.to_string(),
+            self.created_at.to_rfc3339(),
+            self.last_refreshed_at.to_rfc3339(),
+            self.last_job_id
+                .map(|id| id.to_string())
+                .unwrap_or_default(),
+            self.deleted_at
+                .map(|deleted_at| deleted_at.to_rfc3339())
+                .unwrap_or_default(),
+            self.deletion_reason.clone().unwrap_or_default(),
+            self.project_id.map(|id| id.to_string()).unwrap_or_default(),
+        ]
     }
 }
@@ -0,0 +1,33 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::package::Package;
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Project {
+    pub id: Uuid,
+    pub name: String,
+    pub repository_url: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ProjectSummary {
+    #[serde(flatten)]
+    pub project: Project,
+    pub downloads: i64,
+    pub packages: Vec<Package>,
+}
+
+impl ProjectSummary {
+    pub fn new(project: Project, packages: Vec<Package>) -> Self {
+        let downloads = packages.iter().map(|package| package.downloads).sum();
+
+        Self {
+            project,
+            downloads,
+            packages,
+        }
+    }
+}
@@ -0,0 +1,26 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use uuid::Uuid;
+
+use crate::types::Cursor;
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct QuarantinedMessage {
+    pub id: Uuid,
+    pub routing_key: String,
+    pub headers: Value,
+    pub error: String,
+    pub object_key: String,
+    pub size: i64,
+    pub created_at: DateTime<Utc>,
+    pub reinjected_at: Option<DateTime<Utc>>,
+}
+
+impl Cursor for QuarantinedMessage {
+    type Sort = ();
+
+    fn cursor(&self, _sort: ()) -> String {
+        self.id.to_string()
+    }
+}
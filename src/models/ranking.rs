@@ -0,0 +1,22 @@
+use serde::{Deserialize, Serialize};
+
+use super::package::Package;
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct RankedPackage {
+    pub rank: i64,
+    pub percentile: f64,
+    pub downloads_growth: i64,
+    #[serde(flatten)]
+    pub package: Package,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct TrendingPackage {
+    pub rank: i64,
+    pub previous_downloads: i64,
+    pub downloads_growth: i64,
+    pub relative_growth: f64,
+    #[serde(flatten)]
+    pub package: Package,
+}
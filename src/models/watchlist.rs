@@ -0,0 +1,65 @@
+use std::fmt::Display;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Copy, Clone)]
+pub enum NotificationChannel {
+    #[serde(rename = "webhook")]
+    Webhook,
+    #[serde(rename = "email")]
+    Email,
+}
+
+impl From<String> for NotificationChannel {
+    fn from(s: String) -> Self {
+        match s.as_str() {
+            "webhook" => NotificationChannel::Webhook,
+            "email" => NotificationChannel::Email,
+            _ => {
+                tracing::warn!(channel = s, "Invalid notification channel");
+                NotificationChannel::Webhook
+            }
+        }
+    }
+}
+
+impl Display for NotificationChannel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NotificationChannel::Webhook => write!(f, "webhook"),
+            NotificationChannel::Email => write!(f, "email"),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Watchlist {
+    pub id: Uuid,
+    pub name: String,
+    pub created_at: DateTime<Utc>,
+    #[serde(skip)]
+    pub revision: i64,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct WatchedPackage {
+    pub registry: String,
+    pub name: String,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct WatchlistTarget {
+    pub id: Uuid,
+    pub channel: NotificationChannel,
+    pub target: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct WatchlistDetails {
+    #[serde(flatten)]
+    pub watchlist: Watchlist,
+    pub packages: Vec<WatchedPackage>,
+    pub targets: Vec<WatchlistTarget>,
+}
@@ -0,0 +1,173 @@
+use std::time::Duration;
+
+use anyhow::Result;
+use chrono::{DateTime, TimeDelta, Utc};
+use reqwest::Client;
+use sqlx::{Pool, Postgres};
+use tokio_util::sync::CancellationToken;
+
+use crate::{
+    config::NotificationsConfig,
+    db,
+    models::{notification::PendingNotification, watchlist::NotificationChannel},
+    services::{
+        smtp::{self, Mailer},
+        webhook,
+    },
+};
+
+pub struct Notifier {
+    db_pool: Pool<Postgres>,
+    client: Client,
+    mailer: Mailer,
+    settings: NotificationsConfig,
+}
+
+impl Notifier {
+    pub fn build(db_pool: Pool<Postgres>, settings: NotificationsConfig) -> Result<Self> {
+        let client = webhook::create_client(Duration::from_secs(settings.webhook_timeout_seconds))?;
+        let mailer = smtp::create_transport(&settings.smtp)?;
+
+        Ok(Self {
+            db_pool,
+            client,
+            mailer,
+            settings,
+        })
+    }
+
+    pub async fn run_until_stopped(self, shutdown: CancellationToken) -> Result<()> {
+        if !self.settings.enabled {
+            return Ok(());
+        }
+
+        let mut interval =
+            tokio::time::interval(Duration::from_secs(self.settings.interval_seconds));
+        loop {
+            tokio::select! {
+                _ = interval.tick() => {}
+                _ = shutdown.cancelled() => return Ok(()),
+            }
+
+            if let Err(error) = self.deliver_once().await {
+                tracing::error!(error = ?error, "Failed to deliver notifications");
+            }
+        }
+    }
+
+    // Deliveries run outside of any transaction, each outcome is recorded on its own so that
+    // one failed write cannot undo the others.
+    pub async fn deliver_once(&self) -> Result<u64> {
+        let now = Utc::now();
+        let lease_until = now + TimeDelta::seconds(self.settings.lease_seconds as i64);
+        let notifications = {
+            let mut conn = self.db_pool.acquire().await?;
+            db::claim_pending_notifications(&mut conn, now, lease_until, self.settings.batch_size)
+                .await?
+        };
+
+        let mut delivered = 0;
+        for notification in notifications {
+            let result = self.deliver(&notification).await;
+            if result.is_ok() {
+                delivered += 1;
+            }
+            if let Err(error) = self.record(&notification, result).await {
+                tracing::error!(
+                    error = ?error,
+                    notification_id = %notification.id,
+                    "Failed to record notification delivery"
+                );
+            }
+        }
+
+        if delivered > 0 {
+            tracing::info!(delivered, "Delivered notifications");
+        }
+
+        Ok(delivered)
+    }
+
+    async fn record(&self, notification: &PendingNotification, result: Result<()>) -> Result<()> {
+        let mut conn = self.db_pool.acquire().await?;
+        match result {
+            Ok(()) => {
+                db::mark_notification_delivered(&mut conn, notification.id, Utc::now()).await?;
+            }
+            Err(error) => {
+                tracing::warn!(
+                    error = ?error,
+                    notification_id = %notification.id,
+                    channel = %notification.channel,
+                    "Failed to deliver notification"
+                );
+                let next_attempt_at = next_attempt_at(
+                    Utc::now(),
+                    notification.attempts,
+                    self.settings.max_attempts,
+                    self.settings.retry_delay_seconds,
+                );
+                db::mark_notification_failed(
+                    &mut conn,
+                    notification.id,
+                    &error.to_string(),
+                    next_attempt_at,
+                )
+                .await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn deliver(&self, notification: &PendingNotification) -> Result<()> {
+        match notification.channel {
+            NotificationChannel::Webhook => {
+                webhook::send(&self.client, &notification.target, &notification.event).await
+            }
+            NotificationChannel::Email => {
+                let message = smtp::version_changed_message(
+                    &self.settings.smtp.from,
+                    &notification.target,
+                    &notification.event,
+                )?;
+                smtp::send(&self.mailer, message).await
+            }
+        }
+    }
+}
+
+// Backs off exponentially and gives up once the attempt being recorded is the last allowed one.
+fn next_attempt_at(
+    now: DateTime<Utc>,
+    attempts: i32,
+    max_attempts: i32,
+    retry_delay_seconds: u64,
+) -> Option<DateTime<Utc>> {
+    if attempts + 1 >= max_attempts {
+        return None;
+    }
+
+    let delay = retry_delay_seconds.saturating_mul(1 << attempts.clamp(0, 16));
+    Some(now + TimeDelta::seconds(delay as i64))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_next_attempt_at_backs_off_exponentially() {
+        let now = Utc::now();
+
+        assert_eq!(
+            next_attempt_at(now, 0, 5, 30),
+            Some(now + TimeDelta::seconds(30))
+        );
+        assert_eq!(
+            next_attempt_at(now, 2, 5, 30),
+            Some(now + TimeDelta::seconds(120))
+        );
+        assert_eq!(next_attempt_at(now, 4, 5, 30), None);
+    }
+}
@@ -0,0 +1,546 @@
+use std::sync::Arc;
+
+use anyhow::{bail, Result};
+use async_compression::tokio::bufread::{GzipDecoder, ZstdDecoder};
+use aws_sdk_s3::{error::SdkError, operation::get_object::GetObjectError, Client};
+use bytes::Bytes;
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncRead, AsyncReadExt, BufReader, Take};
+use tracing::instrument;
+use uuid::Uuid;
+
+use crate::{telemetry::Metrics, types::JobMessage};
+
+const PLACEHOLDERS: &[&str] = &["registry", "job_id", "package_name"];
+
+#[derive(Debug, Clone)]
+pub struct OutputKeys {
+    template: String,
+    legacy_fallback: bool,
+}
+
+impl OutputKeys {
+    // Every job writes its own object so concurrent jobs don't race and a job's input
+    // can be looked up later, which is why `{job_id}` is required.
+    pub fn new(template: &str, legacy_fallback: bool) -> Result<Self> {
+        let mut rest = template;
+        let mut has_job_id = false;
+        while let Some(start) = rest.find('{') {
+            let Some(end) = rest[start..].find('}') else {
+                bail!("Unclosed placeholder in output key template: {}", template);
+            };
+            let placeholder = &rest[start + 1..start + end];
+            if !PLACEHOLDERS.contains(&placeholder) {
+                bail!(
+                    "Unknown placeholder {{{}}} in output key template",
+                    placeholder
+                );
+            }
+            has_job_id |= placeholder == "job_id";
+            rest = &rest[start + end + 1..];
+        }
+        if !has_job_id {
+            bail!("Output key template must contain {{job_id}}: {}", template);
+        }
+
+        Ok(Self {
+            template: template.to_string(),
+            legacy_fallback,
+        })
+    }
+
+    pub fn key(&self, registry: &str, package_name: &str, job_id: Uuid) -> String {
+        self.template
+            .replace("{registry}", registry)
+            .replace("{job_id}", &job_id.to_string())
+            .replace("{package_name}", package_name)
+    }
+
+    // Outputs written before keys were scoped per job, kept readable while integrations migrate.
+    pub fn legacy_key(&self, package_name: &str) -> Option<String> {
+        self.legacy_fallback
+            .then(|| format!("outputs/{}.json", package_name))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContentEncoding {
+    Identity,
+    Gzip,
+    Zstd,
+}
+
+impl ContentEncoding {
+    // The `Content-Encoding` set on upload wins over the key suffix.
+    pub fn detect(content_encoding: Option<&str>, key: &str) -> Self {
+        match content_encoding.map(|encoding| encoding.trim().to_ascii_lowercase()) {
+            Some(encoding) if encoding == "gzip" || encoding == "x-gzip" => return Self::Gzip,
+            Some(encoding) if encoding == "zstd" => return Self::Zstd,
+            Some(encoding) if encoding == "identity" => return Self::Identity,
+            _ => {}
+        }
+
+        if key.ends_with(".gz") {
+            Self::Gzip
+        } else if key.ends_with(".zst") || key.ends_with(".zstd") {
+            Self::Zstd
+        } else {
+            Self::Identity
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    Json,
+    Ndjson,
+}
+
+impl OutputFormat {
+    pub fn detect(content_type: Option<&str>, key: &str) -> Self {
+        let content_type = content_type
+            .and_then(|content_type| content_type.split(';').next())
+            .map(|content_type| content_type.trim().to_ascii_lowercase());
+        if let Some("application/x-ndjson" | "application/ndjson" | "application/jsonl") =
+            content_type.as_deref()
+        {
+            return Self::Ndjson;
+        }
+
+        let key = [".gz", ".zst", ".zstd"]
+            .iter()
+            .find_map(|suffix| key.strip_suffix(suffix))
+            .unwrap_or(key);
+        if key.ends_with(".ndjson") || key.ends_with(".jsonl") {
+            Self::Ndjson
+        } else {
+            Self::Json
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum OutputError {
+    #[error("Output exceeds {0} bytes once decompressed")]
+    TooLarge(u64),
+    #[error("Failed to decompress output: {0}")]
+    Decompression(#[source] std::io::Error),
+}
+
+pub struct OutputStore {
+    client: Client,
+    bucket_name: String,
+    keys: OutputKeys,
+    max_bytes: u64,
+    max_bulk_bytes: u64,
+    metrics: Arc<Metrics>,
+}
+
+impl OutputStore {
+    pub fn new(
+        client: Client,
+        bucket_name: String,
+        keys: OutputKeys,
+        max_bytes: u64,
+        max_bulk_bytes: u64,
+        metrics: Arc<Metrics>,
+    ) -> Self {
+        Self {
+            client,
+            bucket_name,
+            keys,
+            max_bytes,
+            max_bulk_bytes,
+            metrics,
+        }
+    }
+
+    // Jobs dispatched before keys were configurable carry no output key, and integrations
+    // that have not migrated yet still write to the legacy key.
+    #[instrument(name = "open_output", skip_all, fields(job_id = %message.job_id))]
+    pub async fn open(&self, message: &JobMessage) -> Result<Output> {
+        let mut key = message.output_key.clone().unwrap_or_else(|| {
+            self.keys
+                .key(&message.registry, &message.package_name, message.job_id)
+        });
+
+        let response = match self
+            .client
+            .get_object()
+            .bucket(&self.bucket_name)
+            .key(&key)
+            .send()
+            .await
+        {
+            Err(SdkError::ServiceError(error))
+                if matches!(error.err(), GetObjectError::NoSuchKey(_)) =>
+            {
+                let Some(legacy_key) = self.keys.legacy_key(&message.package_name) else {
+                    return Err(SdkError::ServiceError(error).into());
+                };
+                tracing::warn!(key, legacy_key, "Output not found, reading legacy key");
+                let response = self
+                    .client
+                    .get_object()
+                    .bucket(&self.bucket_name)
+                    .key(&legacy_key)
+                    .send()
+                    .await?;
+                key = legacy_key;
+                response
+            }
+            response => response?,
+        };
+
+        if let Some(size) = response.content_length() {
+            self.metrics
+                .worker_output_bytes(&message.registry)
+                .observe(size as f64);
+        }
+
+        let encoding = ContentEncoding::detect(response.content_encoding(), &key);
+        let format = OutputFormat::detect(response.content_type(), &key);
+        let body = BufReader::new(response.body.into_async_read());
+
+        Ok(Output {
+            format,
+            reader: decompress(body, encoding),
+            max_bytes: self.max_bytes,
+            max_bulk_bytes: self.max_bulk_bytes,
+        })
+    }
+}
+
+pub struct Output {
+    pub format: OutputFormat,
+    reader: Box<dyn AsyncBufRead + Unpin + Send>,
+    max_bytes: u64,
+    max_bulk_bytes: u64,
+}
+
+impl Output {
+    pub async fn into_bytes(self) -> Result<Bytes, OutputError> {
+        read_limited(self.reader, self.max_bytes).await
+    }
+
+    // Bulk outputs hold many records, so they get a larger total limit of their own while
+    // each record keeps the single-output limit.
+    pub fn records(self) -> Records<Box<dyn AsyncBufRead + Unpin + Send>> {
+        Records::new(self.reader, self.max_bytes, self.max_bulk_bytes)
+    }
+}
+
+pub struct Records<R> {
+    reader: Take<R>,
+    max_bytes: u64,
+    max_total_bytes: u64,
+    line: u64,
+}
+
+impl<R: AsyncBufRead + Unpin> Records<R> {
+    pub fn new(reader: R, max_bytes: u64, max_total_bytes: u64) -> Self {
+        Self {
+            reader: reader.take(max_total_bytes + 1),
+            max_bytes,
+            max_total_bytes,
+            line: 0,
+        }
+    }
+
+    pub fn line(&self) -> u64 {
+        self.line
+    }
+
+    // Yields each non-blank line with its 1-based line number. A line over `max_bytes` is
+    // skipped and reported on its own, only going over `max_total_bytes` ends the stream.
+    pub async fn next(&mut self) -> Result<Option<(u64, Result<Bytes, OutputError>)>, OutputError> {
+        loop {
+            let mut record = Vec::new();
+            let read = (&mut self.reader)
+                .take(self.max_bytes + 1)
+                .read_until(b'\n', &mut record)
+                .await
+                .map_err(OutputError::Decompression)?;
+            if read == 0 {
+                return Ok(None);
+            }
+            let too_large = record.last() != Some(&b'\n') && record.len() as u64 > self.max_bytes;
+            if too_large {
+                self.skip_line().await?;
+            }
+            if self.reader.limit() == 0 {
+                return Err(OutputError::TooLarge(self.max_total_bytes));
+            }
+            self.line += 1;
+
+            if too_large {
+                return Ok(Some((
+                    self.line,
+                    Err(OutputError::TooLarge(self.max_bytes)),
+                )));
+            }
+            if record.last() == Some(&b'\n') {
+                record.pop();
+            }
+            if record.iter().all(u8::is_ascii_whitespace) {
+                continue;
+            }
+
+            return Ok(Some((self.line, Ok(Bytes::from(record)))));
+        }
+    }
+
+    async fn skip_line(&mut self) -> Result<(), OutputError> {
+        loop {
+            let buffer = self
+                .reader
+                .fill_buf()
+                .await
+                .map_err(OutputError::Decompression)?;
+            if buffer.is_empty() {
+                return Ok(());
+            }
+            match buffer.iter().position(|byte| *byte == b'\n') {
+                Some(end) => {
+                    self.reader.consume(end + 1);
+                    return Ok(());
+                }
+                None => {
+                    let read = buffer.len();
+                    self.reader.consume(read);
+                }
+            }
+        }
+    }
+}
+
+fn decompress<'a, R>(
+    reader: R,
+    encoding: ContentEncoding,
+) -> Box<dyn AsyncBufRead + Unpin + Send + 'a>
+where
+    R: AsyncBufRead + Unpin + Send + 'a,
+{
+    match encoding {
+        ContentEncoding::Identity => Box::new(reader),
+        ContentEncoding::Gzip => Box::new(BufReader::new(GzipDecoder::new(reader))),
+        ContentEncoding::Zstd => Box::new(BufReader::new(ZstdDecoder::new(reader))),
+    }
+}
+
+pub async fn decode<R>(
+    reader: R,
+    encoding: ContentEncoding,
+    max_bytes: u64,
+) -> Result<Bytes, OutputError>
+where
+    R: AsyncBufRead + Unpin + Send,
+{
+    read_limited(decompress(reader, encoding), max_bytes).await
+}
+
+// Reading one byte past the limit tells an output of exactly `max_bytes` apart from a larger
+// one without decompressing the rest of it.
+async fn read_limited<R>(reader: R, max_bytes: u64) -> Result<Bytes, OutputError>
+where
+    R: AsyncRead + Unpin,
+{
+    let mut data = Vec::new();
+    reader
+        .take(max_bytes + 1)
+        .read_to_end(&mut data)
+        .await
+        .map_err(OutputError::Decompression)?;
+    if data.len() as u64 > max_bytes {
+        return Err(OutputError::TooLarge(max_bytes));
+    }
+
+    Ok(Bytes::from(data))
+}
+
+#[cfg(test)]
+mod tests {
+    use async_compression::tokio::bufread::{GzipEncoder, ZstdEncoder};
+
+    use super::*;
+
+    #[test]
+    fn test_key_renders_placeholders() {
+        let keys = OutputKeys::new("outputs/{registry}/{job_id}.json", true).unwrap();
+
+        assert_eq!(
+            keys.key("npmjs.com", "foo", Uuid::from_u64_pair(1, 2)),
+            "outputs/npmjs.com/00000000-0000-0001-0000-000000000002.json"
+        );
+        assert_eq!(keys.legacy_key("foo"), Some("outputs/foo.json".to_string()));
+    }
+
+    #[test]
+    fn test_legacy_key_can_be_disabled() {
+        let keys = OutputKeys::new("{registry}/{package_name}/{job_id}", false).unwrap();
+
+        assert_eq!(keys.legacy_key("foo"), None);
+    }
+
+    #[test]
+    fn test_new_rejects_invalid_templates() {
+        assert!(OutputKeys::new("outputs/{package_name}.json", true).is_err());
+        assert!(OutputKeys::new("outputs/{job_id}/{version}.json", true).is_err());
+        assert!(OutputKeys::new("outputs/{job_id", true).is_err());
+    }
+
+    async fn compress(encoding: ContentEncoding, data: &[u8]) -> Vec<u8> {
+        let mut compressed = Vec::new();
+        match encoding {
+            ContentEncoding::Identity => compressed.extend_from_slice(data),
+            ContentEncoding::Gzip => {
+                GzipEncoder::new(data)
+                    .read_to_end(&mut compressed)
+                    .await
+                    .unwrap();
+            }
+            ContentEncoding::Zstd => {
+                ZstdEncoder::new(data)
+                    .read_to_end(&mut compressed)
+                    .await
+                    .unwrap();
+            }
+        }
+        compressed
+    }
+
+    #[test]
+    fn test_detect_prefers_content_encoding_over_suffix() {
+        assert_eq!(
+            ContentEncoding::detect(Some("gzip"), "outputs/a.json"),
+            ContentEncoding::Gzip
+        );
+        assert_eq!(
+            ContentEncoding::detect(Some("zstd"), "outputs/a.json.gz"),
+            ContentEncoding::Zstd
+        );
+        assert_eq!(
+            ContentEncoding::detect(None, "outputs/a.json.zst"),
+            ContentEncoding::Zstd
+        );
+        assert_eq!(
+            ContentEncoding::detect(Some("identity"), "outputs/a.json.gz"),
+            ContentEncoding::Identity
+        );
+        assert_eq!(
+            ContentEncoding::detect(None, "outputs/a.json"),
+            ContentEncoding::Identity
+        );
+    }
+
+    #[tokio::test]
+    async fn test_decode_decompresses_gzip_and_zstd() {
+        let output = br#"{"name":"serde","version":"1.0.0","downloads":10}"#;
+
+        for encoding in [
+            ContentEncoding::Identity,
+            ContentEncoding::Gzip,
+            ContentEncoding::Zstd,
+        ] {
+            let compressed = compress(encoding, output).await;
+
+            let decoded = decode(compressed.as_slice(), encoding, 1024).await.unwrap();
+
+            assert_eq!(decoded.as_ref(), output, "{:?}", encoding);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_decode_rejects_outputs_over_the_limit() {
+        let bomb = compress(ContentEncoding::Zstd, &vec![b' '; 1 << 20]).await;
+        assert!(bomb.len() < 1024);
+
+        let error = decode(bomb.as_slice(), ContentEncoding::Zstd, 4096)
+            .await
+            .unwrap_err();
+        let exact = decode(&[b' '; 4096][..], ContentEncoding::Identity, 4096).await;
+
+        assert!(matches!(error, OutputError::TooLarge(4096)));
+        assert_eq!(exact.unwrap().len(), 4096);
+    }
+
+    #[tokio::test]
+    async fn test_decode_reports_corrupt_streams() {
+        let error = decode(&b"not gzip"[..], ContentEncoding::Gzip, 1024)
+            .await
+            .unwrap_err();
+
+        assert!(matches!(error, OutputError::Decompression(_)));
+    }
+
+    #[test]
+    fn test_detect_ndjson_by_content_type_or_suffix() {
+        assert_eq!(
+            OutputFormat::detect(Some("application/x-ndjson; charset=utf-8"), "a.json"),
+            OutputFormat::Ndjson
+        );
+        assert_eq!(
+            OutputFormat::detect(None, "outputs/a.ndjson.zst"),
+            OutputFormat::Ndjson
+        );
+        assert_eq!(
+            OutputFormat::detect(Some("application/json"), "outputs/a.json.gz"),
+            OutputFormat::Json
+        );
+    }
+
+    #[tokio::test]
+    async fn test_records_yields_numbered_non_blank_lines() {
+        let data = b"{\"name\":\"a\"}\n\n  \n{\"name\":\"b\"}";
+        let mut records = Records::new(&data[..], 1024, 4096);
+
+        let (first_line, first) = records.next().await.unwrap().unwrap();
+        let (second_line, second) = records.next().await.unwrap().unwrap();
+
+        assert_eq!(first_line, 1);
+        assert_eq!(first.unwrap(), Bytes::from_static(b"{\"name\":\"a\"}"));
+        assert_eq!(second_line, 4);
+        assert_eq!(second.unwrap(), Bytes::from_static(b"{\"name\":\"b\"}"));
+        assert!(records.next().await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_records_skips_records_over_the_limit() {
+        let data = b"1234\n123456789\n5678";
+        let mut records = Records::new(&data[..], 4, 4096);
+
+        let (_, first) = records.next().await.unwrap().unwrap();
+        let (second_line, second) = records.next().await.unwrap().unwrap();
+        let (third_line, third) = records.next().await.unwrap().unwrap();
+
+        assert_eq!(first.unwrap(), Bytes::from_static(b"1234"));
+        assert_eq!(second_line, 2);
+        assert!(matches!(second, Err(OutputError::TooLarge(4))));
+        assert_eq!(third_line, 3);
+        assert_eq!(third.unwrap(), Bytes::from_static(b"5678"));
+        assert!(records.next().await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_records_limits_decompressed_total() {
+        let data = "{\"name\":\"a\"}\n".repeat(1000);
+        let mut compressed = Vec::new();
+        ZstdEncoder::new(data.as_bytes())
+            .read_to_end(&mut compressed)
+            .await
+            .unwrap();
+        let reader = decompress(&compressed[..], ContentEncoding::Zstd);
+        let mut records = Records::new(reader, 1024, 4096);
+
+        let mut ingested = 0;
+        let error = loop {
+            match records.next().await {
+                Ok(Some(_)) => ingested += 1,
+                Ok(None) => panic!("Expected the total limit to be hit"),
+                Err(error) => break error,
+            }
+        };
+
+        assert!(matches!(error, OutputError::TooLarge(4096)));
+        assert!(ingested < 1000);
+    }
+}
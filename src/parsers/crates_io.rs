@@ -0,0 +1,135 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use super::OutputParser;
+
+// The `GET /api/v1/crates/{name}` response.
+pub struct CratesIoParser;
+
+#[derive(Debug, Deserialize)]
+struct CrateResponse {
+    #[serde(rename = "crate")]
+    krate: Crate,
+    #[serde(default)]
+    versions: Vec<CrateVersion>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Crate {
+    name: String,
+    default_version: Option<String>,
+    max_stable_version: Option<String>,
+    newest_version: Option<String>,
+    downloads: u64,
+    description: Option<String>,
+    homepage: Option<String>,
+    repository: Option<String>,
+    keywords: Option<Vec<String>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CrateVersion {
+    num: String,
+    #[serde(default)]
+    yanked: bool,
+    license: Option<String>,
+    created_at: Option<DateTime<Utc>>,
+}
+
+impl OutputParser for CratesIoParser {
+    fn accepts(&self, document: &Value) -> bool {
+        document.get("crate").is_some_and(Value::is_object)
+    }
+
+    fn parse(&self, document: &Value) -> Result<Value> {
+        let response =
+            CrateResponse::deserialize(document).context("Invalid crates.io response")?;
+        let krate = response.krate;
+        let version = krate
+            .default_version
+            .or(krate.max_stable_version)
+            .or(krate.newest_version)
+            .context("crates.io response has no version")?;
+        let release = response
+            .versions
+            .into_iter()
+            .find(|release| release.num == version);
+
+        Ok(json!({
+            "name": krate.name,
+            "version": version,
+            "downloads": krate.downloads,
+            "description": krate.description,
+            "license": release.as_ref().and_then(|release| release.license.clone()),
+            "repository_url": krate.repository,
+            "homepage": krate.homepage,
+            "keywords": krate.keywords.unwrap_or_default(),
+            "published_at": release.as_ref().and_then(|release| release.created_at),
+            "deprecated": release.is_some_and(|release| release.yanked),
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_maps_crate_response() {
+        let document = json!({
+            "crate": {
+                "name": "serde",
+                "default_version": "1.0.219",
+                "max_stable_version": "1.0.219",
+                "newest_version": "1.0.219",
+                "downloads": 500_000_000u64,
+                "description": "A generic serialization/deserialization framework",
+                "homepage": "https://serde.rs",
+                "repository": "https://github.com/serde-rs/serde",
+                "keywords": ["serde", "serialization", "no_std"]
+            },
+            "versions": [
+                { "num": "1.0.219", "yanked": false, "license": "MIT OR Apache-2.0", "created_at": "2025-03-09T19:11:26.527339Z" },
+                { "num": "1.0.218", "yanked": false, "license": "MIT OR Apache-2.0", "created_at": "2025-02-20T04:54:22.000000Z" }
+            ]
+        });
+
+        assert!(CratesIoParser.accepts(&document));
+        let output = CratesIoParser.parse(&document).unwrap();
+
+        assert_eq!(output["name"], "serde");
+        assert_eq!(output["version"], "1.0.219");
+        assert_eq!(output["downloads"], 500_000_000u64);
+        assert_eq!(output["license"], "MIT OR Apache-2.0");
+        assert_eq!(
+            output["repository_url"],
+            "https://github.com/serde-rs/serde"
+        );
+        assert_eq!(output["published_at"], "2025-03-09T19:11:26.527339Z");
+        assert_eq!(output["keywords"].as_array().unwrap().len(), 3);
+        assert_eq!(output["deprecated"], false);
+    }
+
+    #[test]
+    fn test_parse_falls_back_to_newest_version_and_flags_yanked() {
+        let document = json!({
+            "crate": {
+                "name": "left-pad",
+                "max_stable_version": null,
+                "newest_version": "0.1.0-alpha",
+                "downloads": 3,
+                "keywords": null
+            },
+            "versions": [{ "num": "0.1.0-alpha", "yanked": true }]
+        });
+
+        let output = CratesIoParser.parse(&document).unwrap();
+
+        assert_eq!(output["version"], "0.1.0-alpha");
+        assert_eq!(output["deprecated"], true);
+        assert_eq!(output["keywords"], json!([]));
+        assert!(!CratesIoParser.accepts(&json!({ "name": "left-pad", "version": "1.0.0" })));
+    }
+}
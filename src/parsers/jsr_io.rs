@@ -0,0 +1,95 @@
+use std::collections::HashMap;
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use super::{downloads, OutputParser};
+
+// The `https://jsr.io/@{scope}/{name}/meta.json` document.
+pub struct JsrIoParser;
+
+#[derive(Debug, Deserialize)]
+struct PackageMeta {
+    scope: String,
+    name: String,
+    latest: Option<String>,
+    #[serde(default)]
+    versions: HashMap<String, VersionMeta>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct VersionMeta {
+    #[serde(default)]
+    yanked: bool,
+    created_at: Option<DateTime<Utc>>,
+}
+
+impl OutputParser for JsrIoParser {
+    fn accepts(&self, document: &Value) -> bool {
+        document.get("scope").is_some_and(Value::is_string)
+            && document.get("versions").is_some_and(Value::is_object)
+    }
+
+    fn parse(&self, document: &Value) -> Result<Value> {
+        let meta = PackageMeta::deserialize(document).context("Invalid JSR meta.json")?;
+        let name = format!("@{}/{}", meta.scope, meta.name);
+        let version = meta
+            .latest
+            .with_context(|| format!("{} has no latest version", name))?;
+        let release = meta.versions.get(&version);
+
+        Ok(json!({
+            "name": name,
+            "version": version,
+            "downloads": downloads(document)?,
+            "published_at": release.and_then(|release| release.created_at),
+            "deprecated": release.is_some_and(|release| release.yanked),
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_maps_meta_json() {
+        let document = json!({
+            "scope": "std",
+            "name": "path",
+            "latest": "1.0.8",
+            "versions": {
+                "1.0.8": { "createdAt": "2024-11-19T12:00:00Z" },
+                "1.0.7": { "yanked": true, "createdAt": "2024-10-01T12:00:00Z" }
+            },
+            "downloads": 1200
+        });
+
+        assert!(JsrIoParser.accepts(&document));
+        let output = JsrIoParser.parse(&document).unwrap();
+
+        assert_eq!(output["name"], "@std/path");
+        assert_eq!(output["version"], "1.0.8");
+        assert_eq!(output["downloads"], 1200);
+        assert_eq!(output["published_at"], "2024-11-19T12:00:00Z");
+        assert_eq!(output["deprecated"], false);
+    }
+
+    #[test]
+    fn test_parse_rejects_package_without_latest_version() {
+        let document = json!({
+            "scope": "std",
+            "name": "path",
+            "latest": null,
+            "versions": { "0.1.0": { "yanked": true } },
+            "downloads": 0
+        });
+
+        let error = JsrIoParser.parse(&document).unwrap_err();
+
+        assert_eq!(error.to_string(), "@std/path has no latest version");
+    }
+}
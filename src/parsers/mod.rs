@@ -0,0 +1,124 @@
+use std::collections::HashMap;
+
+use anyhow::{bail, Result};
+use bytes::Bytes;
+use serde_json::Value;
+
+mod crates_io;
+mod jsr_io;
+mod npmjs_com;
+
+pub use crates_io::CratesIoParser;
+pub use jsr_io::JsrIoParser;
+pub use npmjs_com::NpmjsComParser;
+
+// Maps a verbatim upstream document to the canonical output shape described by
+// `schemas/outputs`.
+pub trait OutputParser: Send + Sync {
+    fn accepts(&self, document: &Value) -> bool;
+
+    fn parse(&self, document: &Value) -> Result<Value>;
+}
+
+pub struct OutputParsers {
+    parsers: HashMap<&'static str, Box<dyn OutputParser>>,
+}
+
+impl OutputParsers {
+    pub fn new() -> Self {
+        let mut parsers: HashMap<&'static str, Box<dyn OutputParser>> = HashMap::new();
+        parsers.insert("crates.io", Box::new(CratesIoParser));
+        parsers.insert("jsr.io", Box::new(JsrIoParser));
+        parsers.insert("npmjs.com", Box::new(NpmjsComParser));
+
+        Self { parsers }
+    }
+
+    pub fn get(&self, registry: &str) -> Option<&dyn OutputParser> {
+        self.parsers.get(registry).map(|parser| parser.as_ref())
+    }
+
+    // Outputs already in the canonical shape, or that are not JSON at all, are passed through
+    // untouched and left to schema validation.
+    pub fn normalize(&self, registry: &str, data: Bytes) -> Result<Bytes> {
+        let Some(parser) = self.get(registry) else {
+            return Ok(data);
+        };
+        let Ok(document) = serde_json::from_slice::<Value>(&data) else {
+            return Ok(data);
+        };
+        if !parser.accepts(&document) {
+            return Ok(data);
+        }
+
+        let output = parser.parse(&document)?;
+
+        Ok(Bytes::from(serde_json::to_vec(&output)?))
+    }
+}
+
+impl Default for OutputParsers {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// npm and JSR documents carry no download counts, so integrations add them next to the
+// document, either as a number or as the npm downloads API point response.
+fn downloads(document: &Value) -> Result<u64> {
+    let downloads = match document.get("downloads") {
+        Some(Value::Object(point)) => point.get("downloads"),
+        downloads => downloads,
+    };
+    match downloads.and_then(Value::as_u64) {
+        Some(downloads) => Ok(downloads),
+        None => bail!("Missing download count next to the upstream document"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_normalize_passes_canonical_outputs_through() {
+        let parsers = OutputParsers::new();
+        let data = Bytes::from_static(br#"{"name":"serde","version":"1.0.0","downloads":10}"#);
+
+        let normalized = parsers.normalize("crates.io", data.clone()).unwrap();
+
+        assert_eq!(normalized, data);
+    }
+
+    #[test]
+    fn test_normalize_maps_upstream_documents_by_registry() {
+        let parsers = OutputParsers::new();
+        let data = serde_json::to_vec(&json!({
+            "scope": "std",
+            "name": "path",
+            "latest": "1.0.8",
+            "versions": { "1.0.8": {} },
+            "downloads": 42
+        }))
+        .unwrap();
+
+        let normalized = parsers.normalize("jsr.io", Bytes::from(data)).unwrap();
+
+        let output: Value = serde_json::from_slice(&normalized).unwrap();
+        assert_eq!(output["name"], "@std/path");
+        assert_eq!(output["version"], "1.0.8");
+        assert_eq!(output["downloads"], 42);
+    }
+
+    #[test]
+    fn test_downloads_accepts_count_or_point_response() {
+        assert_eq!(downloads(&json!({ "downloads": 7 })).unwrap(), 7);
+        assert_eq!(
+            downloads(&json!({ "downloads": { "downloads": 9, "package": "left-pad" } })).unwrap(),
+            9
+        );
+        assert!(downloads(&json!({})).is_err());
+    }
+}
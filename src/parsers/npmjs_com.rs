@@ -0,0 +1,236 @@
+use std::collections::{BTreeMap, HashMap};
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use super::{downloads, OutputParser};
+
+// The `https://registry.npmjs.org/{name}` packument.
+pub struct NpmjsComParser;
+
+#[derive(Debug, Deserialize)]
+struct Packument {
+    name: String,
+    #[serde(rename = "dist-tags", default)]
+    dist_tags: HashMap<String, String>,
+    // Only the latest manifest is read; older ones are often malformed.
+    #[serde(default)]
+    versions: HashMap<String, Value>,
+    #[serde(default)]
+    time: HashMap<String, Value>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Manifest {
+    description: Option<String>,
+    license: Option<License>,
+    repository: Option<Repository>,
+    homepage: Option<String>,
+    keywords: Option<Keywords>,
+    deprecated: Option<Value>,
+    #[serde(default)]
+    dependencies: BTreeMap<String, String>,
+    #[serde(default)]
+    dev_dependencies: BTreeMap<String, String>,
+    #[serde(default)]
+    peer_dependencies: BTreeMap<String, String>,
+    #[serde(default)]
+    optional_dependencies: BTreeMap<String, String>,
+}
+
+// Older manifests use `{ "type": ... }` for licenses and `{ "url": ... }` for repositories.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum License {
+    Name(String),
+    Object {
+        #[serde(rename = "type")]
+        name: Option<String>,
+    },
+}
+
+impl License {
+    fn into_name(self) -> Option<String> {
+        match self {
+            License::Name(name) => Some(name),
+            License::Object { name } => name,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum Repository {
+    Url(String),
+    Object { url: Option<String> },
+}
+
+impl Repository {
+    fn into_url(self) -> Option<String> {
+        match self {
+            Repository::Url(url) => Some(url),
+            Repository::Object { url } => url,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum Keywords {
+    List(Vec<String>),
+    Joined(String),
+}
+
+impl Keywords {
+    fn into_vec(self) -> Vec<String> {
+        match self {
+            Keywords::List(keywords) => keywords,
+            Keywords::Joined(keywords) => keywords
+                .split([',', ' '])
+                .filter(|keyword| !keyword.is_empty())
+                .map(String::from)
+                .collect(),
+        }
+    }
+}
+
+impl OutputParser for NpmjsComParser {
+    fn accepts(&self, document: &Value) -> bool {
+        document.get("dist-tags").is_some() || document.get("_id").is_some()
+    }
+
+    fn parse(&self, document: &Value) -> Result<Value> {
+        let mut packument =
+            Packument::deserialize(document).context("Invalid npm registry document")?;
+        if packument.time.contains_key("unpublished") {
+            return Ok(json!({ "name": packument.name, "removed": true }));
+        }
+
+        let version = packument
+            .dist_tags
+            .remove("latest")
+            .with_context(|| format!("{} has no latest dist-tag", packument.name))?;
+        let manifest = packument
+            .versions
+            .remove(&version)
+            .with_context(|| format!("{} has no manifest for {}", packument.name, version))?;
+        let manifest = Manifest::deserialize(manifest)
+            .with_context(|| format!("Invalid manifest for {}@{}", packument.name, version))?;
+
+        let dependencies: Vec<Value> = [
+            ("normal", manifest.dependencies),
+            ("dev", manifest.dev_dependencies),
+            ("peer", manifest.peer_dependencies),
+            ("optional", manifest.optional_dependencies),
+        ]
+        .into_iter()
+        .flat_map(|(kind, dependencies)| {
+            dependencies.into_iter().map(move |(name, requirement)| {
+                json!({ "name": name, "requirement": requirement, "kind": kind })
+            })
+        })
+        .collect();
+
+        Ok(json!({
+            "name": packument.name,
+            "version": version,
+            "downloads": downloads(document)?,
+            "description": manifest.description,
+            "license": manifest.license.and_then(License::into_name),
+            "repository_url": manifest.repository.and_then(Repository::into_url),
+            "homepage": manifest.homepage,
+            "keywords": manifest.keywords.map(Keywords::into_vec).unwrap_or_default(),
+            "published_at": packument.time.get(&version),
+            "deprecated": is_deprecated(manifest.deprecated.as_ref()),
+            "dependencies": dependencies,
+        }))
+    }
+}
+
+// An empty message is how npm records an undeprecated version.
+fn is_deprecated(deprecated: Option<&Value>) -> bool {
+    match deprecated {
+        Some(Value::String(message)) => !message.is_empty(),
+        Some(Value::Bool(deprecated)) => *deprecated,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_maps_latest_manifest() {
+        let document = json!({
+            "_id": "left-pad",
+            "name": "left-pad",
+            "dist-tags": { "latest": "1.3.0" },
+            "versions": {
+                "1.3.0": {
+                    "description": "String left pad",
+                    "license": "WTFPL",
+                    "repository": { "type": "git", "url": "git+https://github.com/stevemao/left-pad.git" },
+                    "keywords": ["leftpad", "left", "pad"],
+                    "deprecated": "use String.prototype.padStart()",
+                    "devDependencies": { "tape": "*" }
+                },
+                "1.2.0": {}
+            },
+            "time": { "1.3.0": "2018-04-09T01:33:52.384Z" },
+            "downloads": { "downloads": 2_000_000, "package": "left-pad" }
+        });
+
+        assert!(NpmjsComParser.accepts(&document));
+        let output = NpmjsComParser.parse(&document).unwrap();
+
+        assert_eq!(output["name"], "left-pad");
+        assert_eq!(output["version"], "1.3.0");
+        assert_eq!(output["downloads"], 2_000_000);
+        assert_eq!(output["license"], "WTFPL");
+        assert_eq!(
+            output["repository_url"],
+            "git+https://github.com/stevemao/left-pad.git"
+        );
+        assert_eq!(output["published_at"], "2018-04-09T01:33:52.384Z");
+        assert_eq!(output["deprecated"], true);
+        assert_eq!(
+            output["dependencies"],
+            json!([{ "name": "tape", "requirement": "*", "kind": "dev" }])
+        );
+    }
+
+    #[test]
+    fn test_parse_ignores_malformed_older_manifests() {
+        let document = json!({
+            "_id": "left-pad",
+            "name": "left-pad",
+            "dist-tags": { "latest": "1.3.0" },
+            "versions": {
+                "1.3.0": { "license": "WTFPL" },
+                "0.0.1": { "license": ["MIT"], "dependencies": ["tape"] }
+            },
+            "downloads": { "downloads": 10, "package": "left-pad" }
+        });
+
+        let output = NpmjsComParser.parse(&document).unwrap();
+
+        assert_eq!(output["version"], "1.3.0");
+        assert_eq!(output["license"], "WTFPL");
+    }
+
+    #[test]
+    fn test_parse_reports_unpublished_package_as_removed() {
+        let document = json!({
+            "_id": "gone",
+            "name": "gone",
+            "time": { "unpublished": { "time": "2024-01-01T00:00:00.000Z" } }
+        });
+
+        let output = NpmjsComParser.parse(&document).unwrap();
+
+        assert_eq!(output, json!({ "name": "gone", "removed": true }));
+    }
+}
@@ -0,0 +1,167 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+use aws_sdk_s3::{primitives::ByteStream, Client};
+use bytes::Bytes;
+use chrono::Utc;
+use lapin::types::{AMQPValue, FieldTable};
+use serde_json::{json, Map, Value};
+use sqlx::{Pool, Postgres};
+use tokio::sync::OnceCell;
+use tracing::instrument;
+use uuid::Uuid;
+
+use crate::{config::MinioConfig, db, models::quarantine::QuarantinedMessage, services::minio};
+
+// Deliveries the worker cannot parse are kept verbatim in MinIO and indexed in
+// `quarantined_messages`, so they can be inspected and re-injected once fixed.
+#[derive(Clone)]
+pub struct Quarantine {
+    client: Arc<OnceCell<Client>>,
+    settings: Arc<MinioConfig>,
+    db_pool: Pool<Postgres>,
+}
+
+impl Quarantine {
+    // The API only reaches MinIO to serve and re-inject quarantined payloads, so it creates
+    // its client on first use instead of at startup.
+    pub fn new(settings: MinioConfig, db_pool: Pool<Postgres>) -> Self {
+        Self {
+            client: Arc::new(OnceCell::new()),
+            settings: Arc::new(settings),
+            db_pool,
+        }
+    }
+
+    pub fn with_client(client: Client, settings: MinioConfig, db_pool: Pool<Postgres>) -> Self {
+        Self {
+            client: Arc::new(OnceCell::new_with(Some(client))),
+            settings: Arc::new(settings),
+            db_pool,
+        }
+    }
+
+    async fn client(&self) -> Result<&Client> {
+        self.client
+            .get_or_try_init(|| minio::create_client(&self.settings))
+            .await
+    }
+
+    #[instrument(name = "quarantine_message", skip(self, headers, data))]
+    pub async fn store(
+        &self,
+        routing_key: &str,
+        headers: &Option<FieldTable>,
+        data: &[u8],
+        error: &str,
+    ) -> Result<QuarantinedMessage> {
+        let id = Uuid::now_v7();
+        let object_key = format!("quarantine/{}", id);
+
+        self.client()
+            .await?
+            .put_object()
+            .bucket(&self.settings.bucket_name)
+            .key(&object_key)
+            .body(ByteStream::from(data.to_vec()))
+            .send()
+            .await?;
+
+        let mut conn = self.db_pool.acquire().await?;
+        let message = db::insert_quarantined_message(
+            &mut conn,
+            QuarantinedMessage {
+                id,
+                routing_key: routing_key.to_string(),
+                headers: headers.as_ref().map_or_else(|| json!({}), headers_to_json),
+                error: error.to_string(),
+                object_key,
+                size: data.len() as i64,
+                created_at: Utc::now(),
+                reinjected_at: None,
+            },
+        )
+        .await?;
+
+        Ok(message)
+    }
+
+    #[instrument(name = "quarantined_payload", skip_all, fields(id = %message.id))]
+    pub async fn payload(&self, message: &QuarantinedMessage) -> Result<Bytes> {
+        let response = self
+            .client()
+            .await?
+            .get_object()
+            .bucket(&self.settings.bucket_name)
+            .key(&message.object_key)
+            .send()
+            .await?;
+
+        Ok(response.body.collect().await?.into_bytes())
+    }
+}
+
+fn headers_to_json(headers: &FieldTable) -> Value {
+    let headers: Map<String, Value> = headers
+        .inner()
+        .iter()
+        .map(|(key, value)| (key.to_string(), amqp_to_json(value)))
+        .collect();
+
+    Value::Object(headers)
+}
+
+fn amqp_to_json(value: &AMQPValue) -> Value {
+    match value {
+        AMQPValue::Boolean(value) => json!(value),
+        AMQPValue::ShortShortInt(value) => json!(value),
+        AMQPValue::ShortShortUInt(value) => json!(value),
+        AMQPValue::ShortInt(value) => json!(value),
+        AMQPValue::ShortUInt(value) => json!(value),
+        AMQPValue::LongInt(value) => json!(value),
+        AMQPValue::LongUInt(value) => json!(value),
+        AMQPValue::LongLongInt(value) => json!(value),
+        AMQPValue::Float(value) => json!(value),
+        AMQPValue::Double(value) => json!(value),
+        AMQPValue::DecimalValue(value) => json!({ "scale": value.scale, "value": value.value }),
+        AMQPValue::ShortString(value) => json!(value.as_str()),
+        AMQPValue::LongString(value) => json!(String::from_utf8_lossy(value.as_bytes())),
+        AMQPValue::FieldArray(values) => values.as_slice().iter().map(amqp_to_json).collect(),
+        AMQPValue::Timestamp(value) => json!(value),
+        AMQPValue::FieldTable(table) => headers_to_json(table),
+        AMQPValue::ByteArray(bytes) => json!(bytes.as_slice()),
+        AMQPValue::Void => Value::Null,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_headers_to_json_converts_amqp_values() {
+        let mut nested = FieldTable::default();
+        nested.insert("attempt".into(), AMQPValue::LongInt(2));
+        let mut headers = FieldTable::default();
+        headers.insert(
+            "traceparent".into(),
+            AMQPValue::LongString("00-abc-def-01".into()),
+        );
+        headers.insert("retry".into(), AMQPValue::FieldTable(nested));
+        headers.insert(
+            "tags".into(),
+            AMQPValue::FieldArray(vec![AMQPValue::Boolean(true), AMQPValue::Void].into()),
+        );
+
+        let json = headers_to_json(&headers);
+
+        assert_eq!(
+            json,
+            json!({
+                "traceparent": "00-abc-def-01",
+                "retry": { "attempt": 2 },
+                "tags": [true, null]
+            })
+        );
+    }
+}
@@ -0,0 +1,49 @@
+use std::{sync::Arc, time::Duration};
+
+use anyhow::Result;
+use sqlx::{Pool, Postgres};
+use tokio::sync::Notify;
+use tokio_util::sync::CancellationToken;
+
+use crate::db;
+
+pub struct RankingsRefresher {
+    db_pool: Pool<Postgres>,
+    delay: Duration,
+    notify: Arc<Notify>,
+}
+
+impl RankingsRefresher {
+    pub fn build(db_pool: Pool<Postgres>, delay: Duration) -> Self {
+        Self {
+            db_pool,
+            delay,
+            notify: Arc::new(Notify::new()),
+        }
+    }
+
+    pub fn notifier(&self) -> Arc<Notify> {
+        self.notify.clone()
+    }
+
+    pub async fn run_until_stopped(self, shutdown: CancellationToken) -> Result<()> {
+        loop {
+            tokio::select! {
+                _ = self.notify.notified() => {}
+                _ = shutdown.cancelled() => return Ok(()),
+            }
+            // Let the rest of the ingestion batch land before refreshing once for all of it.
+            tokio::time::sleep(self.delay).await;
+
+            if let Err(error) = self.refresh().await {
+                tracing::error!(error = ?error, "Failed to refresh package rankings");
+            }
+        }
+    }
+
+    async fn refresh(&self) -> Result<()> {
+        let mut conn = self.db_pool.acquire().await?;
+        db::refresh_package_rankings(&mut conn).await?;
+        db::refresh_package_growth(&mut conn).await
+    }
+}
@@ -0,0 +1,125 @@
+use std::time::Duration;
+
+use anyhow::Result;
+use chrono::{TimeDelta, Utc};
+use sqlx::{Pool, Postgres};
+use tokio_util::sync::CancellationToken;
+
+use crate::{config::RefresherConfig, db, dispatcher::Dispatcher, models::job::JobSource};
+
+pub struct PackageRefresher {
+    db_pool: Pool<Postgres>,
+    dispatcher: Dispatcher,
+    settings: RefresherConfig,
+}
+
+impl PackageRefresher {
+    pub fn build(
+        db_pool: Pool<Postgres>,
+        dispatcher: Dispatcher,
+        settings: RefresherConfig,
+    ) -> Self {
+        Self {
+            db_pool,
+            dispatcher,
+            settings,
+        }
+    }
+
+    pub async fn run_until_stopped(self, shutdown: CancellationToken) -> Result<()> {
+        if !self.settings.enabled {
+            return Ok(());
+        }
+
+        let mut interval =
+            tokio::time::interval(Duration::from_secs(self.settings.interval_seconds));
+        loop {
+            tokio::select! {
+                _ = interval.tick() => {}
+                _ = shutdown.cancelled() => return Ok(()),
+            }
+
+            if let Err(error) = self.refresh_once().await {
+                tracing::error!(error = ?error, "Failed to refresh stale packages");
+            }
+        }
+    }
+
+    pub async fn refresh_once(&self) -> Result<u64> {
+        let now = Utc::now();
+        let pending_since = now - TimeDelta::seconds(self.settings.pending_timeout_seconds as i64);
+        let registries: Vec<String> = self.dispatcher.registries().map(String::from).collect();
+
+        let mut dispatched = 0;
+        for registry in registries {
+            let mut transaction = self.db_pool.begin().await?;
+            if !db::try_lock_refresh(&mut transaction, &registry).await? {
+                tracing::debug!(registry, "Another refresher holds the registry, skipping");
+                continue;
+            }
+
+            let pending = db::count_pending_jobs(
+                &mut transaction,
+                &registry,
+                JobSource::Refresher,
+                pending_since,
+            )
+            .await?;
+            let budget = self
+                .settings
+                .max_pending_per_registry
+                .saturating_sub(pending.max(0) as u64);
+            if budget == 0 {
+                continue;
+            }
+
+            let max_age = self.settings.max_age_seconds(&registry);
+            let refreshed_before = now - TimeDelta::seconds(max_age as i64);
+            let packages = db::get_stale_packages(
+                &mut transaction,
+                &registry,
+                refreshed_before,
+                pending_since,
+                budget,
+            )
+            .await?;
+
+            // Jobs are committed as they are dispatched, so the next holder of the lock counts them.
+            for package in packages {
+                self.dispatcher
+                    .dispatch(package.registry, package.name, false, JobSource::Refresher)
+                    .await?;
+                dispatched += 1;
+            }
+            transaction.commit().await?;
+        }
+
+        if dispatched > 0 {
+            tracing::info!(dispatched, "Enqueued refreshes for stale packages");
+        }
+
+        Ok(dispatched)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    #[test]
+    fn test_max_age_falls_back_to_default() {
+        let settings = RefresherConfig {
+            enabled: true,
+            interval_seconds: 60,
+            max_age_seconds: 100,
+            registry_max_age_seconds: HashMap::from([("crates.io".to_string(), 10)]),
+            max_pending_per_registry: 1,
+            pending_timeout_seconds: 60,
+        };
+
+        assert_eq!(settings.max_age_seconds("crates.io"), 10);
+        assert_eq!(settings.max_age_seconds("npmjs.com"), 100);
+    }
+}
@@ -0,0 +1,124 @@
+use std::{future::Future, time::Duration};
+
+use anyhow::{Context, Result};
+use tokio::time::Instant;
+
+const INITIAL_DELAY: Duration = Duration::from_millis(500);
+const MAX_DELAY: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone)]
+pub struct Backoff {
+    initial: Duration,
+    max: Duration,
+    current: Duration,
+}
+
+impl Backoff {
+    pub fn new(initial: Duration, max: Duration) -> Self {
+        Self {
+            initial,
+            max,
+            current: initial,
+        }
+    }
+
+    pub fn next_delay(&mut self) -> Duration {
+        let delay = self.current;
+        self.current = (self.current * 2).min(self.max);
+        delay
+    }
+
+    pub fn reset(&mut self) {
+        self.current = self.initial;
+    }
+}
+
+impl Default for Backoff {
+    fn default() -> Self {
+        Self::new(INITIAL_DELAY, MAX_DELAY)
+    }
+}
+
+pub async fn wait_for<T, F, Fut>(dependency: &str, timeout: Duration, mut attempt: F) -> Result<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T>>,
+{
+    let deadline = Instant::now() + timeout;
+    let mut backoff = Backoff::default();
+    loop {
+        let error = match attempt().await {
+            Ok(value) => return Ok(value),
+            Err(error) => error,
+        };
+
+        let delay = backoff.next_delay();
+        if Instant::now() + delay > deadline {
+            return Err(error).with_context(|| format!("{} is unavailable", dependency));
+        }
+
+        tracing::warn!(
+            dependency,
+            error = ?error,
+            retry_in_ms = delay.as_millis() as u64,
+            "Waiting for dependency"
+        );
+        tokio::time::sleep(delay).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    use anyhow::anyhow;
+    use claims::{assert_err, assert_ok};
+
+    use super::*;
+
+    #[test]
+    fn test_backoff_doubles_up_to_max_and_resets() {
+        // Arrange
+        let mut backoff = Backoff::new(Duration::from_secs(1), Duration::from_secs(5));
+
+        // Act
+        let delays: Vec<u64> = (0..5).map(|_| backoff.next_delay().as_secs()).collect();
+        backoff.reset();
+
+        // Assert
+        assert_eq!(delays, vec![1, 2, 4, 5, 5]);
+        assert_eq!(backoff.next_delay(), Duration::from_secs(1));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_wait_for_retries_until_dependency_is_ready() {
+        // Arrange
+        let attempts = AtomicU32::new(0);
+
+        // Act
+        let result = wait_for("broker", Duration::from_secs(60), || async {
+            match attempts.fetch_add(1, Ordering::SeqCst) {
+                0..=2 => Err(anyhow!("connection refused")),
+                _ => Ok("connected"),
+            }
+        })
+        .await;
+
+        // Assert
+        assert_eq!(assert_ok!(result), "connected");
+        assert_eq!(attempts.load(Ordering::SeqCst), 4);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_wait_for_gives_up_after_timeout() {
+        // Act
+        let result: Result<()> = wait_for("broker", Duration::from_secs(5), || async {
+            Err(anyhow!("connection refused"))
+        })
+        .await;
+
+        // Assert
+        let error = assert_err!(result);
+        assert_eq!(error.to_string(), "broker is unavailable");
+    }
+}
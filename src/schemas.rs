@@ -0,0 +1,158 @@
+use std::collections::HashMap;
+
+use anyhow::{Context, Result};
+use jsonschema::{Resource, Validator};
+use serde_json::Value;
+
+const DEFAULT_SCHEMA_VERSION: u64 = 1;
+
+// The fields every registry shares, which each registry's schema extends with its own rules
+// for package names.
+const BASE_SCHEMAS: &[&str] = &[include_str!("../schemas/outputs/base/v1.json")];
+
+const OUTPUT_SCHEMAS: &[(&str, u64, &str)] = &[
+    (
+        "crates.io",
+        1,
+        include_str!("../schemas/outputs/crates.io/v1.json"),
+    ),
+    (
+        "jsr.io",
+        1,
+        include_str!("../schemas/outputs/jsr.io/v1.json"),
+    ),
+    (
+        "npmjs.com",
+        1,
+        include_str!("../schemas/outputs/npmjs.com/v1.json"),
+    ),
+];
+
+pub struct OutputSchemas {
+    validators: HashMap<(String, u64), Validator>,
+}
+
+impl OutputSchemas {
+    pub fn load() -> Result<Self> {
+        let mut bases = Vec::new();
+        for base in BASE_SCHEMAS {
+            let base: Value = serde_json::from_str(base).context("Invalid base output schema")?;
+            let id = base["$id"]
+                .as_str()
+                .context("Base output schema has no $id")?
+                .to_string();
+            bases.push((id, base));
+        }
+
+        let mut validators = HashMap::new();
+        for (registry, version, schema) in OUTPUT_SCHEMAS {
+            let schema: Value = serde_json::from_str(schema)
+                .with_context(|| format!("Invalid output schema for {} v{}", registry, version))?;
+            let mut options = jsonschema::options().should_validate_formats(true);
+            for (id, base) in &bases {
+                let resource = Resource::from_contents(base.clone())
+                    .with_context(|| format!("Invalid base output schema {}", id))?;
+                options = options.with_resource(id.as_str(), resource);
+            }
+            let validator = options.build(&schema).map_err(|error| {
+                anyhow::anyhow!(
+                    "Failed to compile output schema for {} v{}: {}",
+                    registry,
+                    version,
+                    error
+                )
+            })?;
+            validators.insert((registry.to_string(), *version), validator);
+        }
+
+        Ok(Self { validators })
+    }
+
+    // Outputs declare the schema they follow with `schema_version`, defaulting to the first one.
+    pub fn validate(&self, registry: &str, data: &[u8]) -> Result<Value, Vec<String>> {
+        let output: Value = serde_json::from_slice(data)
+            .map_err(|error| vec![format!("Invalid JSON: {}", error)])?;
+
+        let version = match output.get("schema_version") {
+            None => DEFAULT_SCHEMA_VERSION,
+            Some(version) => version
+                .as_u64()
+                .ok_or_else(|| vec!["/schema_version: must be a positive integer".to_string()])?,
+        };
+        let Some(validator) = self.validators.get(&(registry.to_string(), version)) else {
+            return Err(vec![format!(
+                "No output schema for {} version {}",
+                registry, version
+            )]);
+        };
+
+        let errors: Vec<String> = validator
+            .iter_errors(&output)
+            .map(|error| {
+                let path = error.instance_path.to_string();
+                let path = if path.is_empty() { "/" } else { path.as_str() };
+                format!("{}: {}", path, error)
+            })
+            .collect();
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+
+        Ok(output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_accepts_valid_outputs() {
+        let schemas = OutputSchemas::load().unwrap();
+
+        assert!(schemas
+            .validate(
+                "crates.io",
+                br#"{"name":"serde","version":"1.0.0","downloads":10,"dependencies":[{"name":"serde_derive","req":"^1"}]}"#
+            )
+            .is_ok());
+        assert!(schemas
+            .validate("npmjs.com", br#"{"name":"@types/node","removed":true}"#)
+            .is_ok());
+    }
+
+    #[test]
+    fn test_validate_reports_every_violation() {
+        let schemas = OutputSchemas::load().unwrap();
+
+        let errors = schemas
+            .validate(
+                "jsr.io",
+                br#"{"name":"not scoped","version":"","downloads":-5,"published_at":"yesterday"}"#,
+            )
+            .unwrap_err();
+
+        assert_eq!(errors.len(), 4, "{:?}", errors);
+        assert!(errors.iter().any(|error| error.starts_with("/downloads: ")));
+        assert!(errors.iter().any(|error| error.starts_with("/name: ")));
+    }
+
+    #[test]
+    fn test_validate_selects_schema_by_registry_and_version() {
+        let schemas = OutputSchemas::load().unwrap();
+
+        assert_eq!(
+            schemas
+                .validate("crates.io", br#"{"schema_version":2,"name":"serde"}"#)
+                .unwrap_err(),
+            vec!["No output schema for crates.io version 2"]
+        );
+        assert_eq!(
+            schemas
+                .validate("unknown", br#"{"name":"serde"}"#)
+                .unwrap_err(),
+            vec!["No output schema for unknown version 1"]
+        );
+        assert!(schemas.validate("crates.io", b"not json").is_err());
+    }
+}
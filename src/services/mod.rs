@@ -1,2 +1,4 @@
 pub mod minio;
 pub mod rabbitmq;
+pub mod smtp;
+pub mod webhook;
@@ -1,8 +1,8 @@
 use anyhow::Result;
 use lapin::{
     options::{
-        BasicConsumeOptions, BasicPublishOptions, ExchangeDeclareOptions, QueueBindOptions,
-        QueueDeclareOptions,
+        BasicCancelOptions, BasicConsumeOptions, BasicPublishOptions, BasicQosOptions,
+        ExchangeDeclareOptions, QueueBindOptions, QueueDeclareOptions,
     },
     types::{AMQPValue, FieldTable},
     BasicProperties, Channel, Connection, ConnectionProperties, Consumer, ExchangeKind,
@@ -169,3 +169,21 @@ pub async fn create_consumer(channel: &Channel, queue_name: &str) -> Result<Cons
 
     Ok(consumer)
 }
+
+#[instrument(name = "set_prefetch_count", skip(channel))]
+pub async fn set_prefetch_count(channel: &Channel, prefetch_count: u16) -> Result<()> {
+    channel
+        .basic_qos(prefetch_count, BasicQosOptions::default())
+        .await?;
+
+    Ok(())
+}
+
+#[instrument(name = "cancel_consumer", skip(channel))]
+pub async fn cancel_consumer(channel: &Channel, consumer_tag: &str) -> Result<()> {
+    channel
+        .basic_cancel(consumer_tag, BasicCancelOptions::default())
+        .await?;
+
+    Ok(())
+}
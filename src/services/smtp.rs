@@ -0,0 +1,106 @@
+use anyhow::Result;
+use lettre::{
+    message::{header::ContentType, Mailbox},
+    transport::smtp::authentication::Credentials,
+    AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor,
+};
+use secrecy::ExposeSecret;
+use tracing::instrument;
+
+use crate::{config::SmtpConfig, models::notification::VersionChangedEvent};
+
+pub type Mailer = AsyncSmtpTransport<Tokio1Executor>;
+
+pub fn create_transport(settings: &SmtpConfig) -> Result<Mailer> {
+    let builder = if settings.tls {
+        Mailer::starttls_relay(&settings.host)?
+    } else {
+        Mailer::builder_dangerous(&settings.host)
+    };
+    let mut builder = builder.port(settings.port);
+    if let (Some(username), Some(password)) = (&settings.username, &settings.password) {
+        builder = builder.credentials(Credentials::new(
+            username.expose_secret().to_string(),
+            password.expose_secret().to_string(),
+        ));
+    }
+
+    Ok(builder.build())
+}
+
+pub fn version_changed_message(
+    from: &str,
+    to: &str,
+    event: &VersionChangedEvent,
+) -> Result<Message> {
+    let message = Message::builder()
+        .from(from.parse::<Mailbox>()?)
+        .to(to.parse::<Mailbox>()?)
+        .subject(format!(
+            "{} {} was released on {}",
+            event.package_name, event.new_version, event.registry
+        ))
+        .header(ContentType::TEXT_PLAIN)
+        .body(format!(
+            "{} on {} changed from {} to {} at {}.\n",
+            event.package_name,
+            event.registry,
+            event.old_version,
+            event.new_version,
+            event.detected_at.to_rfc3339()
+        ))?;
+
+    Ok(message)
+}
+
+#[instrument(name = "send_email", skip(mailer, message))]
+pub async fn send(mailer: &Mailer, message: Message) -> Result<()> {
+    mailer.send(message).await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use super::*;
+
+    #[test]
+    fn test_version_changed_message() -> Result<()> {
+        // Arrange
+        let event = VersionChangedEvent {
+            event: VersionChangedEvent::NAME.to_string(),
+            registry: "crates.io".to_string(),
+            package_name: "tokio".to_string(),
+            old_version: "1.35.0".to_string(),
+            new_version: "1.36.0".to_string(),
+            detected_at: Utc::now(),
+        };
+
+        // Act
+        let message = version_changed_message("bot@example.com", "team@example.com", &event)?;
+
+        // Assert
+        let formatted = String::from_utf8(message.formatted())?;
+        assert!(formatted.contains("Subject: tokio 1.36.0 was released on crates.io"));
+        assert!(formatted.contains("To: team@example.com"));
+        assert!(formatted.contains("changed from 1.35.0 to 1.36.0"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_version_changed_message_rejects_invalid_address() {
+        let event = VersionChangedEvent {
+            event: VersionChangedEvent::NAME.to_string(),
+            registry: "crates.io".to_string(),
+            package_name: "tokio".to_string(),
+            old_version: "1.35.0".to_string(),
+            new_version: "1.36.0".to_string(),
+            detected_at: Utc::now(),
+        };
+
+        assert!(version_changed_message("bot@example.com", "not an address", &event).is_err());
+    }
+}
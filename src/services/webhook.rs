@@ -0,0 +1,96 @@
+use std::time::Duration;
+
+use anyhow::Result;
+use reqwest::Client;
+use serde::Serialize;
+use tracing::instrument;
+
+pub fn create_client(timeout: Duration) -> Result<Client> {
+    let client = Client::builder()
+        .timeout(timeout)
+        .user_agent(concat!(
+            env!("CARGO_PKG_NAME"),
+            "/",
+            env!("CARGO_PKG_VERSION")
+        ))
+        .build()?;
+
+    Ok(client)
+}
+
+#[instrument(name = "send_webhook", skip(client, payload))]
+pub async fn send<T: Serialize>(client: &Client, url: &str, payload: &T) -> Result<()> {
+    client
+        .post(url)
+        .json(payload)
+        .send()
+        .await?
+        .error_for_status()?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use axum::{extract::State, http::StatusCode, routing::post, Json, Router};
+    use tokio::net::TcpListener;
+
+    use super::*;
+
+    async fn spawn_receiver(
+        status: StatusCode,
+    ) -> Result<(String, Arc<Mutex<Vec<serde_json::Value>>>)> {
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let router = Router::new()
+            .route(
+                "/hook",
+                post(
+                    move |State(received): State<Arc<Mutex<Vec<serde_json::Value>>>>,
+                          Json(payload): Json<serde_json::Value>| async move {
+                        received.lock().unwrap().push(payload);
+                        status
+                    },
+                ),
+            )
+            .with_state(received.clone());
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let address = listener.local_addr()?;
+        tokio::spawn(async move { axum::serve(listener, router).await });
+
+        Ok((format!("http://{}/hook", address), received))
+    }
+
+    #[tokio::test]
+    async fn test_send_posts_json_payload() -> Result<()> {
+        // Arrange
+        let (url, received) = spawn_receiver(StatusCode::NO_CONTENT).await?;
+        let client = create_client(Duration::from_secs(5))?;
+
+        // Act
+        send(&client, &url, &serde_json::json!({ "version": "1.0.0" })).await?;
+
+        // Assert
+        let received = received.lock().unwrap();
+        assert_eq!(received.len(), 1);
+        assert_eq!(received[0]["version"], "1.0.0");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_send_fails_on_error_status() -> Result<()> {
+        // Arrange
+        let (url, _) = spawn_receiver(StatusCode::INTERNAL_SERVER_ERROR).await?;
+        let client = create_client(Duration::from_secs(5))?;
+
+        // Act
+        let result = send(&client, &url, &serde_json::json!({})).await;
+
+        // Assert
+        assert!(result.is_err());
+
+        Ok(())
+    }
+}
@@ -1,6 +1,7 @@
 use anyhow::Result;
 use prometheus::{
-    register_counter_vec_with_registry, register_gauge_vec_with_registry,
+    exponential_buckets, histogram_opts, register_counter_vec_with_registry,
+    register_counter_with_registry, register_gauge_vec_with_registry, register_gauge_with_registry,
     register_histogram_vec_with_registry, Counter, CounterVec, Gauge, GaugeVec, Histogram,
     HistogramVec, Registry,
 };
@@ -11,6 +12,17 @@ pub struct Metrics {
     http_requests_pending: GaugeVec,
     http_requests_total: CounterVec,
     http_requests_duration_seconds: HistogramVec,
+    worker_messages_in_flight: Gauge,
+    worker_messages_waiting: Gauge,
+    worker_messages_consumed_total: CounterVec,
+    worker_messages_acked_total: CounterVec,
+    worker_messages_nacked_total: CounterVec,
+    worker_messages_quarantined_total: Counter,
+    worker_consume_duration_seconds: HistogramVec,
+    worker_output_bytes: HistogramVec,
+    job_duration_seconds: HistogramVec,
+    jobs: GaugeVec,
+    jobs_oldest_processing_age_seconds: Gauge,
 }
 
 impl Metrics {
@@ -36,11 +48,95 @@ impl Metrics {
             &registry
         )?;
 
+        let worker_messages_in_flight = register_gauge_with_registry!(
+            "worker_messages_in_flight",
+            "Number of queue messages currently being ingested",
+            &registry
+        )?;
+        let worker_messages_waiting = register_gauge_with_registry!(
+            "worker_messages_waiting",
+            "Number of delivered queue messages waiting for an ingestion slot",
+            &registry
+        )?;
+
+        let worker_messages_consumed_total = register_counter_vec_with_registry!(
+            "worker_messages_consumed_total",
+            "Total number of queue messages delivered to the worker",
+            &["registry"],
+            &registry
+        )?;
+        let worker_messages_acked_total = register_counter_vec_with_registry!(
+            "worker_messages_acked_total",
+            "Total number of queue messages acknowledged by the worker",
+            &["registry"],
+            &registry
+        )?;
+        let worker_messages_nacked_total = register_counter_vec_with_registry!(
+            "worker_messages_nacked_total",
+            "Total number of queue messages rejected by the worker",
+            &["registry", "requeue"],
+            &registry
+        )?;
+        let worker_messages_quarantined_total = register_counter_with_registry!(
+            "worker_messages_quarantined_total",
+            "Total number of unparseable queue messages moved to quarantine",
+            &registry
+        )?;
+        let worker_consume_duration_seconds = register_histogram_vec_with_registry!(
+            histogram_opts!(
+                "worker_consume_duration_seconds",
+                "Duration of queue message ingestion in seconds",
+                vec![0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0, 60.0, 300.0]
+            ),
+            &["registry"],
+            &registry
+        )?;
+        let worker_output_bytes = register_histogram_vec_with_registry!(
+            histogram_opts!(
+                "worker_output_bytes",
+                "Size of outputs fetched from MinIO in bytes, as stored",
+                exponential_buckets(1024.0, 4.0, 10)?
+            ),
+            &["registry"],
+            &registry
+        )?;
+        let job_duration_seconds = register_histogram_vec_with_registry!(
+            histogram_opts!(
+                "job_duration_seconds",
+                "Time from job creation to completion in seconds",
+                vec![1.0, 5.0, 15.0, 30.0, 60.0, 120.0, 300.0, 600.0, 1800.0, 3600.0, 21600.0]
+            ),
+            &["registry", "status"],
+            &registry
+        )?;
+        let jobs = register_gauge_vec_with_registry!(
+            "jobs",
+            "Number of jobs by status",
+            &["status"],
+            &registry
+        )?;
+        let jobs_oldest_processing_age_seconds = register_gauge_with_registry!(
+            "jobs_oldest_processing_age_seconds",
+            "Age of the oldest job still processing in seconds",
+            &registry
+        )?;
+
         Ok(Self {
             registry,
             http_requests_total,
             http_requests_pending,
             http_requests_duration_seconds,
+            worker_messages_in_flight,
+            worker_messages_waiting,
+            worker_messages_consumed_total,
+            worker_messages_acked_total,
+            worker_messages_nacked_total,
+            worker_messages_quarantined_total,
+            worker_consume_duration_seconds,
+            worker_output_bytes,
+            job_duration_seconds,
+            jobs,
+            jobs_oldest_processing_age_seconds,
         })
     }
 
@@ -63,4 +159,53 @@ impl Metrics {
         self.http_requests_duration_seconds
             .with_label_values(&[method, endpoint, status])
     }
+
+    pub fn worker_messages_in_flight(&self) -> &Gauge {
+        &self.worker_messages_in_flight
+    }
+
+    pub fn worker_messages_waiting(&self) -> &Gauge {
+        &self.worker_messages_waiting
+    }
+
+    pub fn worker_messages_consumed_total(&self, registry: &str) -> Counter {
+        self.worker_messages_consumed_total
+            .with_label_values(&[registry])
+    }
+
+    pub fn worker_messages_acked_total(&self, registry: &str) -> Counter {
+        self.worker_messages_acked_total
+            .with_label_values(&[registry])
+    }
+
+    pub fn worker_messages_nacked_total(&self, registry: &str, requeue: bool) -> Counter {
+        self.worker_messages_nacked_total
+            .with_label_values(&[registry, if requeue { "true" } else { "false" }])
+    }
+
+    pub fn worker_messages_quarantined_total(&self) -> &Counter {
+        &self.worker_messages_quarantined_total
+    }
+
+    pub fn worker_consume_duration_seconds(&self, registry: &str) -> Histogram {
+        self.worker_consume_duration_seconds
+            .with_label_values(&[registry])
+    }
+
+    pub fn worker_output_bytes(&self, registry: &str) -> Histogram {
+        self.worker_output_bytes.with_label_values(&[registry])
+    }
+
+    pub fn job_duration_seconds(&self, registry: &str, status: &str) -> Histogram {
+        self.job_duration_seconds
+            .with_label_values(&[registry, status])
+    }
+
+    pub fn jobs(&self, status: &str) -> Gauge {
+        self.jobs.with_label_values(&[status])
+    }
+
+    pub fn jobs_oldest_processing_age_seconds(&self) -> &Gauge {
+        &self.jobs_oldest_processing_age_seconds
+    }
 }
@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use anyhow::Result;
 use opentelemetry::{
     propagation::TextMapCompositePropagator,
@@ -14,14 +16,17 @@ use opentelemetry_sdk::{
 use opentelemetry_semantic_conventions::resource;
 use tokio::{spawn, task::JoinHandle};
 use tracing::{level_filters::LevelFilter, Span, Subscriber};
-use tracing_loki::BackgroundTask;
+use tracing_loki::{BackgroundTask, BackgroundTaskController};
 use tracing_opentelemetry::{OpenTelemetryLayer, OpenTelemetrySpanExt};
 use tracing_subscriber::{layer::SubscriberExt, EnvFilter};
 use tracing_subscriber::{registry::LookupSpan, Layer};
 use url::Url;
 
+const LOKI_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(5);
+
 pub struct TracingGuard {
     tracer_provider: SdkTracerProvider,
+    loki_controller: BackgroundTaskController,
     loki_handle: JoinHandle<()>,
 }
 
@@ -33,6 +38,11 @@ impl TracingGuard {
     pub fn loki_handle(&self) -> &JoinHandle<()> {
         &self.loki_handle
     }
+
+    pub async fn shutdown(mut self) {
+        self.loki_controller.shutdown().await;
+        let _ = tokio::time::timeout(LOKI_SHUTDOWN_TIMEOUT, &mut self.loki_handle).await;
+    }
 }
 
 impl Drop for TracingGuard {
@@ -49,7 +59,7 @@ pub fn init_subscribers() -> Result<TracingGuard> {
 
     // Layers
     let logger_text_layer = build_logger_text_layer();
-    let (loki_layer, background_task) = build_loki_layer()?;
+    let (loki_layer, loki_controller, background_task) = build_loki_layer()?;
     let (otel_layer, tracer_provider) = build_otel_layer()?;
 
     // Subscriber
@@ -65,6 +75,7 @@ pub fn init_subscribers() -> Result<TracingGuard> {
 
     Ok(TracingGuard {
         tracer_provider,
+        loki_controller,
         loki_handle,
     })
 }
@@ -99,20 +110,24 @@ where
     )
 }
 
-fn build_loki_layer() -> Result<(tracing_loki::Layer, BackgroundTask)> {
-    let (loki_layer, background_task) = tracing_loki::builder()
+fn build_loki_layer() -> Result<(
+    tracing_loki::Layer,
+    BackgroundTaskController,
+    BackgroundTask,
+)> {
+    let (loki_layer, loki_controller, background_task) = tracing_loki::builder()
         .label("service_name", env!("CARGO_PKG_NAME"))?
         .label("version", env!("CARGO_PKG_VERSION"))?
         .label(
             "environment",
             std::env::var("APP_ENVIRONMENT").unwrap_or_else(|_| "dev".to_string()),
         )?
-        .build_url(Url::parse(
+        .build_controller_url(Url::parse(
             &std::env::var("LOGGING_LOKI_URL")
                 .unwrap_or_else(|_| "http://127.0.0.1:3100".to_string()),
         )?)?;
 
-    Ok((loki_layer, background_task))
+    Ok((loki_layer, loki_controller, background_task))
 }
 
 fn build_otel_layer<S>() -> Result<(OpenTelemetryLayer<S, Tracer>, SdkTracerProvider)>
@@ -175,6 +190,7 @@ pub enum Operation {
     Update,
     Delete,
     Select,
+    Refresh,
 }
 
 impl std::fmt::Display for Operation {
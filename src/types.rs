@@ -1,4 +1,5 @@
-use serde::{Deserialize, Serialize};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use uuid::Uuid;
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -6,8 +7,59 @@ pub struct JobMessage {
     pub job_id: Uuid,
     pub registry: String,
     pub package_name: String,
+    #[serde(default)]
+    pub force: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output_key: Option<String>,
 }
 
 pub trait Cursor {
-    fn cursor(&self) -> String;
+    type Sort: Copy;
+
+    fn cursor(&self, sort: Self::Sort) -> String;
+}
+
+pub trait CsvRecord {
+    const HEADER: &'static [&'static str];
+
+    fn record(&self) -> Vec<String>;
+}
+
+pub fn encode_cursor<T: Serialize>(value: &T) -> String {
+    let json = serde_json::to_vec(value).expect("Cursor values are always serializable");
+    URL_SAFE_NO_PAD.encode(json)
+}
+
+pub fn decode_cursor<T: DeserializeOwned>(cursor: &str) -> Option<T> {
+    let json = URL_SAFE_NO_PAD.decode(cursor).ok()?;
+    serde_json::from_slice(&json).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cursor_round_trips() {
+        let value = (42_i64, Uuid::from_u64_pair(1, 2));
+
+        let cursor = encode_cursor(&value);
+
+        assert_eq!(decode_cursor::<(i64, Uuid)>(&cursor), Some(value));
+    }
+
+    #[test]
+    fn test_cursor_is_url_safe() {
+        let cursor = encode_cursor(&("a/b+c?d=e&f", Uuid::from_u64_pair(0, 0)));
+
+        assert!(cursor
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_'));
+    }
+
+    #[test]
+    fn test_decode_cursor_rejects_garbage() {
+        assert_eq!(decode_cursor::<(i64, Uuid)>("not a cursor"), None);
+        assert_eq!(decode_cursor::<(i64, Uuid)>(&encode_cursor(&"text")), None);
+    }
 }
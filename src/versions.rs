@@ -0,0 +1,112 @@
+use std::cmp::Ordering;
+
+use semver::Version;
+
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum VersionScheme {
+    Semver,
+    NpmSemver,
+    Unknown,
+}
+
+impl VersionScheme {
+    pub fn for_registry(registry: &str) -> Self {
+        match registry {
+            "crates.io" => Self::Semver,
+            "npmjs.com" | "jsr.io" => Self::NpmSemver,
+            _ => Self::Unknown,
+        }
+    }
+
+    pub fn parse(&self, version: &str) -> Option<Version> {
+        match self {
+            Self::Semver => Version::parse(version).ok(),
+            Self::NpmSemver => {
+                let version = version.trim();
+                let version = version.strip_prefix('=').unwrap_or(version);
+                let version = version.strip_prefix('v').unwrap_or(version);
+                Version::parse(version).ok()
+            }
+            Self::Unknown => None,
+        }
+    }
+
+    pub fn compare(&self, a: &str, b: &str) -> Option<Ordering> {
+        let a = self.parse(a)?;
+        let b = self.parse(b)?;
+        Some(a.cmp_precedence(&b))
+    }
+}
+
+pub fn is_downgrade(registry: &str, current: &str, incoming: &str) -> bool {
+    let scheme = VersionScheme::for_registry(registry);
+    match scheme.compare(incoming, current) {
+        Some(ordering) => ordering == Ordering::Less,
+        None => {
+            tracing::warn!(
+                registry,
+                current,
+                incoming,
+                "Unable to compare package versions"
+            );
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scheme_for_registry() {
+        assert_eq!(
+            VersionScheme::for_registry("crates.io"),
+            VersionScheme::Semver
+        );
+        assert_eq!(
+            VersionScheme::for_registry("npmjs.com"),
+            VersionScheme::NpmSemver
+        );
+        assert_eq!(
+            VersionScheme::for_registry("jsr.io"),
+            VersionScheme::NpmSemver
+        );
+        assert_eq!(
+            VersionScheme::for_registry("pypi.org"),
+            VersionScheme::Unknown
+        );
+    }
+
+    #[test]
+    fn test_is_downgrade_detects_older_release() {
+        assert!(is_downgrade("crates.io", "1.2.0", "1.1.9"));
+        assert!(!is_downgrade("crates.io", "1.2.0", "1.2.0"));
+        assert!(!is_downgrade("crates.io", "1.2.0", "1.10.0"));
+    }
+
+    #[test]
+    fn test_is_downgrade_orders_pre_releases_before_release() {
+        assert!(is_downgrade("crates.io", "2.0.0", "2.0.0-rc.1"));
+        assert!(is_downgrade("crates.io", "2.0.0-rc.2", "2.0.0-rc.1"));
+        assert!(!is_downgrade("crates.io", "2.0.0-rc.1", "2.0.0"));
+        assert!(!is_downgrade("crates.io", "1.9.0", "2.0.0-alpha"));
+    }
+
+    #[test]
+    fn test_is_downgrade_ignores_build_metadata() {
+        assert!(!is_downgrade("crates.io", "1.0.0+build.2", "1.0.0+build.1"));
+    }
+
+    #[test]
+    fn test_is_downgrade_accepts_npm_prefixes() {
+        assert!(is_downgrade("npmjs.com", "v1.2.0", "=1.1.0"));
+        assert!(!is_downgrade("crates.io", "v1.2.0", "1.1.0"));
+    }
+
+    #[test]
+    fn test_is_downgrade_allows_unparseable_versions() {
+        assert!(!is_downgrade("crates.io", "latest", "1.0.0"));
+        assert!(!is_downgrade("pypi.org", "2.0", "1.0"));
+    }
+}
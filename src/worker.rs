@@ -1,111 +1,328 @@
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 
-use anyhow::Result;
-use aws_sdk_s3::Client;
+use anyhow::{bail, Result};
+use bytes::Bytes;
+use chrono::{DateTime, Utc};
 use lapin::{
-    message::DeliveryResult,
+    message::{Delivery, DeliveryResult},
     options::{BasicAckOptions, BasicNackOptions},
     types::{AMQPValue, FieldTable, ShortString},
-    Connection,
+    Channel, Connection,
 };
 use opentelemetry::{global, propagation::Extractor};
 use serde::Deserialize;
-use sqlx::{Pool, Postgres};
+use sqlx::{Acquire, PgConnection, Pool, Postgres};
+use tokio::{
+    io::AsyncBufRead,
+    sync::{Notify, Semaphore},
+};
+use tokio_util::sync::CancellationToken;
 use tracing::{info_span, instrument, Instrument};
 use tracing_opentelemetry::OpenTelemetrySpanExt;
 use uuid::Uuid;
 
 use crate::{
+    broker::Broker,
+    config::WorkerConfig,
     db,
-    models::package::Package,
+    models::{dependency::Dependency, job::Job, package::Package, project::Project},
+    outputs::{OutputFormat, OutputStore, Records},
+    parsers::OutputParsers,
+    quarantine::Quarantine,
+    retry::Backoff,
+    schemas::OutputSchemas,
     services::rabbitmq,
-    types::{self, JobMessage},
+    telemetry::Metrics,
+    types::JobMessage,
+    versions,
 };
 
 pub struct Worker {
-    rabbitmq_connection: Arc<Connection>,
+    broker: Broker,
     consumer_queue: String,
-    minio_client: Client,
-    bucket_name: Arc<String>,
+    config: WorkerConfig,
+    ingestor: Ingestor,
+}
+
+#[derive(Clone)]
+struct Ingestor {
+    outputs: Arc<OutputStore>,
     db_pool: Pool<Postgres>,
+    rankings: Arc<Notify>,
+    schemas: Arc<OutputSchemas>,
+    parsers: Arc<OutputParsers>,
+    quarantine: Quarantine,
+    permits: Arc<Semaphore>,
+    metrics: Arc<Metrics>,
 }
 
 impl Worker {
+    #[allow(clippy::too_many_arguments)]
     pub async fn build(
-        rabbitmq_connection: Arc<Connection>,
+        broker: Broker,
         consumer_queue: String,
-        minio_client: Client,
-        bucket_name: String,
+        outputs: OutputStore,
+        quarantine: Quarantine,
         db_pool: Pool<Postgres>,
+        rankings: Arc<Notify>,
+        config: WorkerConfig,
+        metrics: Arc<Metrics>,
     ) -> Result<Self> {
+        if config.consumers == 0 {
+            bail!("worker.consumers must be at least 1");
+        }
+        if config.max_concurrency == 0 {
+            bail!("worker.max_concurrency must be at least 1");
+        }
+
+        let ingestor = Ingestor {
+            outputs: Arc::new(outputs),
+            db_pool,
+            rankings,
+            schemas: Arc::new(OutputSchemas::load()?),
+            parsers: Arc::new(OutputParsers::new()),
+            quarantine,
+            permits: Arc::new(Semaphore::new(config.max_concurrency)),
+            metrics,
+        };
+
         Ok(Self {
-            rabbitmq_connection,
+            broker,
             consumer_queue,
-            minio_client,
-            bucket_name: Arc::new(bucket_name),
-            db_pool,
+            config,
+            ingestor,
         })
     }
 
-    pub async fn run_until_stopped(self) -> Result<()> {
-        let channel = self.rabbitmq_connection.create_channel().await?;
-        let consumer = rabbitmq::create_consumer(&channel, &self.consumer_queue).await?;
-
-        consumer.set_delegate(move |delivery: DeliveryResult| {
-            let minio_client = self.minio_client.clone();
-            let db_pool = self.db_pool.clone();
-            let bucket_name = self.bucket_name.clone();
-
-            async move {
-                match delivery {
-                    Ok(Some(delivery)) => {
-                        match parse_and_run_consume(
-                            &delivery.data,
-                            delivery.properties.headers(),
-                            minio_client,
-                            &bucket_name,
-                            db_pool,
-                        )
-                        .await
-                        {
-                            Ok(_) => delivery
-                                .ack(BasicAckOptions::default())
-                                .await
-                                .expect("Failed to ack message"),
-                            Err(err) => {
-                                tracing::error!("Failed to process message: {:?}", err);
-                                delivery
-                                    .nack(BasicNackOptions {
-                                        multiple: false,
-                                        requeue: false,
-                                    })
-                                    .await
-                                    .expect("Failed to nack message");
-                            }
-                        }
-                    }
-                    Ok(None) => {}
-                    Err(error) => {
-                        tracing::error!("Failed to consume queue message {}:", error);
+    pub async fn run_until_stopped(self, shutdown: CancellationToken) -> Result<()> {
+        let mut backoff = Backoff::default();
+        let consumers = loop {
+            let link = tokio::select! {
+                link = self.broker.connected() => link,
+                _ = shutdown.cancelled() => break Vec::new(),
+            };
+
+            let consumers = match self.start_consumers(&link.connection, &shutdown).await {
+                Ok(consumers) => consumers,
+                Err(error) => {
+                    let delay = backoff.next_delay();
+                    tracing::error!(
+                        error = ?error,
+                        retry_in_ms = delay.as_millis() as u64,
+                        "Failed to start consumers"
+                    );
+                    tokio::select! {
+                        _ = tokio::time::sleep(delay) => continue,
+                        _ = shutdown.cancelled() => break Vec::new(),
                     }
                 }
+            };
+            backoff.reset();
+
+            tokio::select! {
+                _ = link.lost.cancelled() => {
+                    tracing::warn!("Lost RabbitMQ connection, waiting to re-create consumers");
+                }
+                _ = shutdown.cancelled() => break consumers,
             }
-        });
+        };
 
-        std::future::pending::<()>().await;
+        tracing::info!("Worker shutting down, cancelling consumers");
+
+        for (channel, consumer_tag) in &consumers {
+            if let Err(error) = rabbitmq::cancel_consumer(channel, consumer_tag).await {
+                tracing::error!(error = ?error, consumer_tag, "Failed to cancel consumer");
+            }
+        }
+
+        self.drain().await;
+
+        for (channel, _) in &consumers {
+            if let Err(error) = channel.close(200, "Worker shut down").await {
+                tracing::error!(error = ?error, "Failed to close channel");
+            }
+        }
 
         Ok(())
     }
+
+    async fn start_consumers(
+        &self,
+        connection: &Connection,
+        shutdown: &CancellationToken,
+    ) -> Result<Vec<(Channel, String)>> {
+        let mut consumers = Vec::with_capacity(self.config.consumers);
+        for _ in 0..self.config.consumers {
+            let channel = connection.create_channel().await?;
+            rabbitmq::set_prefetch_count(&channel, self.config.prefetch_count).await?;
+            let consumer = rabbitmq::create_consumer(&channel, &self.consumer_queue).await?;
+            let consumer_tag = consumer.tag().to_string();
+
+            let ingestor = self.ingestor.clone();
+            let delegate_shutdown = shutdown.clone();
+            consumer.set_delegate(move |delivery: DeliveryResult| {
+                let ingestor = ingestor.clone();
+                let shutdown = delegate_shutdown.clone();
+                async move { ingestor.handle(delivery, &shutdown).await }
+            });
+
+            consumers.push((channel, consumer_tag));
+        }
+
+        Ok(consumers)
+    }
+
+    async fn drain(&self) {
+        let deadline = Duration::from_secs(self.config.shutdown_timeout_seconds);
+        let permits = self.config.max_concurrency as u32;
+
+        match tokio::time::timeout(deadline, self.ingestor.permits.acquire_many(permits)).await {
+            Ok(_) => tracing::info!("Drained in-flight messages"),
+            Err(_) => tracing::warn!(
+                in_flight = self.config.max_concurrency - self.ingestor.permits.available_permits(),
+                "Shutdown deadline elapsed with messages still in flight"
+            ),
+        }
+    }
 }
 
-async fn parse_and_run_consume(
-    data: &[u8],
+impl Ingestor {
+    async fn handle(self, delivery: DeliveryResult, shutdown: &CancellationToken) {
+        let delivery = match delivery {
+            Ok(Some(delivery)) => delivery,
+            Ok(None) => return,
+            Err(error) => {
+                tracing::error!("Failed to consume queue message {}:", error);
+                return;
+            }
+        };
+
+        let message = match serde_json::from_slice::<JobMessage>(&delivery.data) {
+            Ok(message) => message,
+            Err(error) => {
+                tracing::error!(error = ?error, "Failed to parse queue message");
+                self.metrics
+                    .worker_messages_consumed_total(UNKNOWN_REGISTRY)
+                    .inc();
+                self.quarantine_message(&delivery, &error.to_string()).await;
+                return;
+            }
+        };
+        let registry = message.registry.clone();
+        self.metrics.worker_messages_consumed_total(&registry).inc();
+
+        self.metrics.worker_messages_waiting().inc();
+        let permit = tokio::select! {
+            biased;
+            _ = shutdown.cancelled() => None,
+            permit = self.permits.acquire() => Some(permit.expect("Ingestion semaphore closed")),
+        };
+        self.metrics.worker_messages_waiting().dec();
+        let Some(_permit) = permit else {
+            // Hand messages that never started back to the queue for the next worker.
+            self.reject(&delivery, &registry, true).await;
+            return;
+        };
+
+        self.metrics.worker_messages_in_flight().inc();
+        let timer = self
+            .metrics
+            .worker_consume_duration_seconds(&registry)
+            .start_timer();
+        let result = run_consume(
+            message,
+            delivery.properties.headers(),
+            &self.outputs,
+            self.db_pool.clone(),
+            &self.rankings,
+            &self.schemas,
+            &self.parsers,
+        )
+        .await;
+        timer.observe_duration();
+        self.metrics.worker_messages_in_flight().dec();
+
+        match result {
+            Ok(job) => {
+                let duration = (Utc::now() - job.created_at).num_milliseconds() as f64 / 1000.0;
+                self.metrics
+                    .job_duration_seconds(&registry, &job.status.to_string())
+                    .observe(duration.max(0.0));
+                ack(&delivery).await;
+                self.metrics.worker_messages_acked_total(&registry).inc();
+            }
+            Err(err) => {
+                tracing::error!("Failed to process message: {:?}", err);
+                self.reject(&delivery, &registry, false).await;
+            }
+        }
+    }
+
+    // Malformed messages will not parse on redelivery either, so once stored they are acked.
+    async fn quarantine_message(&self, delivery: &Delivery, error: &str) {
+        let stored = self
+            .quarantine
+            .store(
+                delivery.routing_key.as_str(),
+                delivery.properties.headers(),
+                &delivery.data,
+                error,
+            )
+            .await;
+
+        match stored {
+            Ok(message) => {
+                tracing::warn!(id = %message.id, error, "Quarantined malformed queue message");
+                self.metrics.worker_messages_quarantined_total().inc();
+                ack(delivery).await;
+                self.metrics
+                    .worker_messages_acked_total(UNKNOWN_REGISTRY)
+                    .inc();
+            }
+            Err(store_error) => {
+                tracing::error!(error = ?store_error, "Failed to quarantine queue message");
+                self.reject(delivery, UNKNOWN_REGISTRY, false).await;
+            }
+        }
+    }
+
+    async fn reject(&self, delivery: &Delivery, registry: &str, requeue: bool) {
+        nack(delivery, requeue).await;
+        self.metrics
+            .worker_messages_nacked_total(registry, requeue)
+            .inc();
+    }
+}
+
+// Messages that are not valid job messages carry no registry to label them with.
+const UNKNOWN_REGISTRY: &str = "unknown";
+
+// A failed ack or nack means the channel is gone; the broker redelivers the message.
+async fn ack(delivery: &Delivery) {
+    if let Err(error) = delivery.ack(BasicAckOptions::default()).await {
+        tracing::warn!(error = ?error, "Failed to ack message");
+    }
+}
+
+async fn nack(delivery: &Delivery, requeue: bool) {
+    let options = BasicNackOptions {
+        multiple: false,
+        requeue,
+    };
+    if let Err(error) = delivery.nack(options).await {
+        tracing::warn!(error = ?error, "Failed to nack message");
+    }
+}
+
+async fn run_consume(
+    message: JobMessage,
     headers: &Option<FieldTable>,
-    minio_client: Client,
-    bucket_name: &str,
+    outputs: &OutputStore,
     db_pool: Pool<Postgres>,
-) -> Result<()> {
-    let message = serde_json::from_slice::<types::JobMessage>(data)?;
+    rankings: &Notify,
+    schemas: &OutputSchemas,
+    parsers: &OutputParsers,
+) -> Result<Job> {
     let span = if let Some(headers) = headers {
         let extractor = FieldTableExtractor(headers);
         let context = global::get_text_map_propagator(|prop| prop.extract(&extractor));
@@ -118,7 +335,7 @@ async fn parse_and_run_consume(
     };
     let _ = span.enter();
 
-    consume_message(message, minio_client, bucket_name, db_pool)
+    consume_message(message, outputs, db_pool, rankings, schemas, parsers)
         .instrument(span)
         .await
 }
@@ -142,41 +359,531 @@ impl<'a> Extractor for FieldTableExtractor<'a> {
 #[instrument(name = "consume_message", skip_all)]
 pub async fn consume_message(
     message: JobMessage,
-    minio_client: Client,
-    bucket_name: &str,
+    outputs: &OutputStore,
     db_pool: Pool<Postgres>,
-) -> Result<()> {
-    let response = minio_client
-        .get_object()
-        .bucket(bucket_name)
-        .key(format!("outputs/{}.json", message.package_name))
-        .send()
-        .await?;
+    rankings: &Notify,
+    schemas: &OutputSchemas,
+    parsers: &OutputParsers,
+) -> Result<Job> {
+    let output = outputs.open(&message).await?;
+    if output.format == OutputFormat::Ndjson {
+        return consume_records(
+            message,
+            output.records(),
+            db_pool,
+            rankings,
+            schemas,
+            parsers,
+        )
+        .await;
+    }
+
+    let data = match output.into_bytes().await {
+        Ok(data) => data,
+        Err(error) => return reject_output(&db_pool, message.job_id, &[error.to_string()]).await,
+    };
+    let record = match prepare_record(&message.registry, data, schemas, parsers) {
+        Ok(record) => record,
+        Err(errors) => return reject_output(&db_pool, message.job_id, &errors).await,
+    };
+
+    let mut transaction = db_pool.begin().await?;
+    let ingested = apply_record(&mut transaction, &message, record).await?;
+    let skipped_version = match &ingested {
+        Ingested::Skipped { version, .. } => Some(version.as_str()),
+        Ingested::Upserted | Ingested::Removed => None,
+    };
+    let job = db::complete_job(&mut transaction, message.job_id, skipped_version).await?;
+    transaction.commit().await?;
 
-    let data = response.body.collect().await?;
-    let json_data = serde_json::from_slice::<PackageOutput>(&data.into_bytes())?;
+    if !matches!(ingested, Ingested::Skipped { .. }) {
+        rankings.notify_one();
+    }
+
+    Ok(job)
+}
 
+// Each record of a bulk output gets a savepoint, so one bad record only costs itself.
+async fn consume_records<R>(
+    message: JobMessage,
+    mut records: Records<R>,
+    db_pool: Pool<Postgres>,
+    rankings: &Notify,
+    schemas: &OutputSchemas,
+    parsers: &OutputParsers,
+) -> Result<Job>
+where
+    R: AsyncBufRead + Unpin,
+{
+    let mut report = RecordReport::default();
     let mut transaction = db_pool.begin().await?;
+    let mut pending = 0;
+    loop {
+        let (line, data) = match records.next().await {
+            Ok(Some((line, Ok(data)))) => (line, data),
+            Ok(Some((line, Err(error)))) => {
+                report.fail(line, &[error.to_string()]);
+                continue;
+            }
+            Ok(None) => break,
+            Err(error) => {
+                report.truncate(records.line() + 1, &error.to_string());
+                break;
+            }
+        };
+
+        let record = match prepare_record(&message.registry, data, schemas, parsers) {
+            Ok(record) => record,
+            Err(errors) => {
+                report.fail(line, &errors);
+                continue;
+            }
+        };
+
+        let mut savepoint = transaction.begin().await?;
+        match apply_record(&mut savepoint, &message, record).await {
+            Ok(Ingested::Skipped { name, version }) => {
+                savepoint.commit().await?;
+                report.skip(&name, &version);
+            }
+            Ok(Ingested::Upserted | Ingested::Removed) => {
+                savepoint.commit().await?;
+                report.ingested += 1;
+            }
+            Err(error) => {
+                savepoint.rollback().await?;
+                report.fail(line, &[format!("{:#}", error)]);
+            }
+        }
+
+        pending += 1;
+        if pending == RECORD_BATCH_SIZE {
+            transaction.commit().await?;
+            transaction = db_pool.begin().await?;
+            pending = 0;
+        }
+    }
+
+    // Records before a truncation are kept, but the job must not read as complete.
+    let job = if report.truncated
+        || (report.ingested == 0 && report.skipped.is_empty() && report.failed > 0)
+    {
+        db::fail_job(&mut transaction, message.job_id, &report.errors).await?
+    } else {
+        if !report.errors.is_empty() {
+            db::record_job_errors(&mut transaction, message.job_id, &report.errors).await?;
+        }
+        let skipped_versions = (!report.skipped.is_empty()).then(|| report.skipped.join(", "));
+        db::complete_job(
+            &mut transaction,
+            message.job_id,
+            skipped_versions.as_deref(),
+        )
+        .await?
+    };
+    transaction.commit().await?;
+
+    tracing::info!(
+        job_id = %message.job_id,
+        ingested = report.ingested,
+        skipped = report.skipped.len(),
+        failed = report.failed,
+        truncated = report.truncated,
+        "Consumed bulk output"
+    );
+    if report.ingested > 0 {
+        rankings.notify_one();
+    }
+
+    Ok(job)
+}
+
+pub const RECORD_BATCH_SIZE: usize = 100;
+pub const MAX_RECORDED_ERRORS: usize = 1000;
+
+#[derive(Debug, Default)]
+struct RecordReport {
+    ingested: u64,
+    failed: u64,
+    errors: Vec<String>,
+    skipped: Vec<String>,
+    truncated: bool,
+}
 
+impl RecordReport {
+    // Downgrades refused for a bulk output are recorded as `name@version`.
+    fn skip(&mut self, name: &str, version: &str) {
+        if self.skipped.len() < MAX_RECORDED_ERRORS {
+            self.skipped.push(format!("{}@{}", name, version));
+        }
+    }
+
+    // The reason the stream ended early is kept even once the error list is full.
+    fn truncate(&mut self, line: u64, error: &str) {
+        self.truncated = true;
+        self.errors
+            .push(format!("line {}: output truncated: {}", line, error));
+    }
+
+    fn fail(&mut self, line: u64, errors: &[String]) {
+        self.failed += 1;
+        for error in errors {
+            if self.errors.len() < MAX_RECORDED_ERRORS {
+                self.errors.push(format!("line {}: {}", line, error));
+            }
+        }
+    }
+}
+
+enum Record {
+    Removed(String),
+    Package(PackageOutput),
+}
+
+enum Ingested {
+    Upserted,
+    Removed,
+    Skipped { name: String, version: String },
+}
+
+fn prepare_record(
+    registry: &str,
+    data: Bytes,
+    schemas: &OutputSchemas,
+    parsers: &OutputParsers,
+) -> Result<Record, Vec<String>> {
+    let data = parsers
+        .normalize(registry, data)
+        .map_err(|error| vec![format!("{:#}", error)])?;
+    let output = schemas.validate(registry, &data)?;
+    let status = OutputStatus::deserialize(&output).map_err(|error| vec![error.to_string()])?;
+    if status.removed {
+        return Ok(Record::Removed(status.name));
+    }
+
+    PackageOutput::deserialize(&output)
+        .map(Record::Package)
+        .map_err(|error| vec![error.to_string()])
+}
+
+async fn apply_record(
+    conn: &mut PgConnection,
+    message: &JobMessage,
+    record: Record,
+) -> Result<Ingested> {
+    let json_data = match record {
+        Record::Removed(name) => {
+            if db::delete_package(
+                conn,
+                &message.registry,
+                &name,
+                REMOVED_UPSTREAM_REASON,
+                Utc::now(),
+                None,
+            )
+            .await?
+            .is_some()
+            {
+                tracing::info!(
+                    registry = message.registry,
+                    package_name = name,
+                    "Package removed upstream"
+                );
+            }
+
+            return Ok(Ingested::Removed);
+        }
+        Record::Package(json_data) => json_data,
+    };
+
+    let current = db::get_package_for_update(conn, &message.registry, &json_data.name).await?;
+    let previous_version = current.as_ref().map(|current| current.version.clone());
+    if let Some(current) = current {
+        if !message.force
+            && versions::is_downgrade(&message.registry, &current.version, &json_data.version)
+        {
+            tracing::warn!(
+                registry = message.registry,
+                package_name = current.name,
+                current_version = current.version,
+                skipped_version = json_data.version,
+                "Refusing to downgrade package"
+            );
+            db::mark_package_refreshed(conn, current.id, message.job_id, Utc::now()).await?;
+
+            return Ok(Ingested::Skipped {
+                name: json_data.name,
+                version: json_data.version,
+            });
+        }
+    }
+
+    let now = Utc::now();
     let package = Package {
         id: Uuid::now_v7(),
-        registry: message.registry,
+        registry: message.registry.clone(),
         name: json_data.name,
         version: json_data.version,
         downloads: json_data.downloads as i64,
+        updated_at: now,
+        description: json_data.description,
+        license: json_data.license,
+        repository_url: json_data.repository_url,
+        homepage: json_data.homepage,
+        keywords: json_data.keywords,
+        published_at: json_data.published_at,
+        deprecated: json_data.deprecated,
+        created_at: now,
+        last_refreshed_at: now,
+        last_job_id: Some(message.job_id),
+        deleted_at: None,
+        deletion_reason: None,
+        project_id: None,
+        revision: 1,
     };
+    let dependencies = json_data.dependencies;
+
+    let package = db::upsert_package(conn, package).await?;
+    db::insert_package_downloads(conn, package.id, package.downloads, package.updated_at).await?;
+    if let Some(dependencies) = dependencies {
+        let dependencies: Vec<Dependency> = dependencies.into_iter().map(Into::into).collect();
+        db::replace_package_dependencies(conn, package.id, &dependencies).await?;
+    }
+    link_project(conn, &package).await?;
+    if let Some(previous_version) = previous_version.filter(|v| *v != package.version) {
+        db::enqueue_version_notifications(
+            conn,
+            &package.registry,
+            &package.name,
+            &previous_version,
+            &package.version,
+            now,
+        )
+        .await?;
+    }
 
-    db::upsert_package(&mut transaction, package).await?;
-    db::complete_job(&mut transaction, message.job_id).await?;
+    Ok(Ingested::Upserted)
+}
 
-    transaction.commit().await?;
+// Invalid outputs will not become valid on redelivery, so the job is failed and the
+// message acknowledged.
+async fn reject_output(db_pool: &Pool<Postgres>, job_id: Uuid, errors: &[String]) -> Result<Job> {
+    tracing::warn!(job_id = %job_id, errors = ?errors, "Rejecting invalid output");
+    let mut conn = db_pool.acquire().await?;
+    db::fail_job(&mut conn, job_id, errors).await
+}
+
+async fn link_project(conn: &mut PgConnection, package: &Package) -> Result<()> {
+    let Some(repository_url) = package.repository_url.as_deref() else {
+        return Ok(());
+    };
+    if package.project_id.is_some() {
+        return Ok(());
+    }
+
+    // Only group packages once the same repository is published to another registry.
+    let project = match db::get_project_by_repository_url(conn, repository_url).await? {
+        Some(project) => project,
+        None if db::has_cross_registry_match(conn, package.id).await? => {
+            let project = Project {
+                id: Uuid::now_v7(),
+                name: package.name.clone(),
+                repository_url: Some(repository_url.to_string()),
+                created_at: Utc::now(),
+            };
+            db::upsert_project(conn, project).await?
+        }
+        None => return Ok(()),
+    };
+    db::link_packages_by_repository(conn, project.id, repository_url).await?;
 
     Ok(())
 }
 
+const REMOVED_UPSTREAM_REASON: &str = "Removed upstream";
+
+#[derive(Debug, Deserialize)]
+pub struct OutputStatus {
+    name: String,
+    #[serde(default)]
+    removed: bool,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct PackageOutput {
     name: String,
     version: String,
     downloads: u64,
+    description: Option<String>,
+    license: Option<String>,
+    #[serde(alias = "repository")]
+    repository_url: Option<String>,
+    homepage: Option<String>,
+    #[serde(default)]
+    keywords: Vec<String>,
+    published_at: Option<DateTime<Utc>>,
+    #[serde(default, alias = "yanked")]
+    deprecated: bool,
+    dependencies: Option<Vec<DependencyOutput>>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DependencyOutput {
+    name: String,
+    #[serde(alias = "req")]
+    requirement: String,
+    #[serde(default = "default_dependency_kind")]
+    kind: String,
+}
+
+fn default_dependency_kind() -> String {
+    "normal".to_string()
+}
+
+impl From<DependencyOutput> for Dependency {
+    fn from(output: DependencyOutput) -> Self {
+        Self {
+            name: output.name,
+            requirement: output.requirement,
+            kind: output.kind,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_package_output_accepts_minimal_shape() {
+        let output: PackageOutput =
+            serde_json::from_str(r#"{"name":"serde","version":"1.0.0","downloads":10}"#).unwrap();
+
+        assert_eq!(output.name, "serde");
+        assert_eq!(output.description, None);
+        assert!(output.keywords.is_empty());
+        assert!(!output.deprecated);
+        assert!(output.dependencies.is_none());
+    }
+
+    #[test]
+    fn test_output_status_reports_removed_package() {
+        let status: OutputStatus =
+            serde_json::from_str(r#"{"name":"left-pad","removed":true}"#).unwrap();
+        assert!(status.removed);
+
+        let status: OutputStatus =
+            serde_json::from_str(r#"{"name":"serde","version":"1.0.0","downloads":10}"#).unwrap();
+        assert!(!status.removed);
+    }
+
+    #[test]
+    fn test_package_output_accepts_dependencies() {
+        let output: PackageOutput = serde_json::from_str(
+            r#"{
+                "name": "serde_json",
+                "version": "1.0.0",
+                "downloads": 10,
+                "dependencies": [
+                    {"name": "serde", "req": "^1.0"},
+                    {"name": "serde_derive", "requirement": "^1.0", "kind": "dev"}
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        let dependencies: Vec<Dependency> = output
+            .dependencies
+            .unwrap()
+            .into_iter()
+            .map(Into::into)
+            .collect();
+        assert_eq!(dependencies.len(), 2);
+        assert_eq!(dependencies[0].requirement, "^1.0");
+        assert_eq!(dependencies[0].kind, "normal");
+        assert_eq!(dependencies[1].kind, "dev");
+    }
+
+    #[test]
+    fn test_package_output_accepts_metadata() {
+        let output: PackageOutput = serde_json::from_str(
+            r#"{
+                "name": "serde",
+                "version": "1.0.0",
+                "downloads": 10,
+                "description": "A serialization framework",
+                "license": "MIT OR Apache-2.0",
+                "repository": "https://github.com/serde-rs/serde",
+                "homepage": "https://serde.rs",
+                "keywords": ["serde", "serialization"],
+                "published_at": "2025-05-26T14:00:00Z",
+                "yanked": true
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            output.repository_url.as_deref(),
+            Some("https://github.com/serde-rs/serde")
+        );
+        assert_eq!(output.keywords, vec!["serde", "serialization"]);
+        assert_eq!(
+            output.published_at,
+            Some("2025-05-26T14:00:00Z".parse().unwrap())
+        );
+        assert!(output.deprecated);
+    }
+
+    #[test]
+    fn test_prepare_record_reports_validation_errors() {
+        let schemas = OutputSchemas::load().unwrap();
+        let parsers = OutputParsers::new();
+
+        let removed = prepare_record(
+            "crates.io",
+            Bytes::from_static(br#"{"name":"serde","removed":true}"#),
+            &schemas,
+            &parsers,
+        );
+        let invalid = prepare_record(
+            "crates.io",
+            Bytes::from_static(br#"{"name":"serde"}"#),
+            &schemas,
+            &parsers,
+        );
+
+        assert!(matches!(removed, Ok(Record::Removed(name)) if name == "serde"));
+        assert!(!invalid.err().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_record_report_keeps_truncation_past_the_error_cap() {
+        let mut report = RecordReport::default();
+        for line in 1..=MAX_RECORDED_ERRORS as u64 {
+            report.fail(line, &["/version: required".to_string()]);
+        }
+        report.skip("serde", "0.9.0");
+
+        report.truncate(MAX_RECORDED_ERRORS as u64 + 1, "unexpected end of file");
+
+        assert!(report.truncated);
+        assert_eq!(report.skipped, vec!["serde@0.9.0"]);
+        assert_eq!(
+            report.errors.last().unwrap(),
+            "line 1001: output truncated: unexpected end of file"
+        );
+    }
+
+    #[test]
+    fn test_record_report_prefixes_lines_and_caps_errors() {
+        let mut report = RecordReport::default();
+
+        for line in 1..=MAX_RECORDED_ERRORS as u64 + 5 {
+            report.fail(line, &["/version: required".to_string()]);
+        }
+
+        assert_eq!(report.failed, MAX_RECORDED_ERRORS as u64 + 5);
+        assert_eq!(report.errors.len(), MAX_RECORDED_ERRORS);
+        assert_eq!(report.errors[0], "line 1: /version: required");
+    }
 }
@@ -0,0 +1,81 @@
+use anyhow::Result;
+use http::StatusCode;
+
+use crate::helpers::spawn_app;
+
+#[tokio::test]
+async fn test_version_badge_renders_package_version() -> Result<()> {
+    // Arrange
+    let app = spawn_app().await?;
+    let client = reqwest::Client::new();
+    let (registry, _) = app.registry_queue()?;
+    let package = app.mock_create_package(&registry).await?;
+
+    // Act
+    let url = format!(
+        "{}/badges/{}/{}/version.svg",
+        app.address, package.registry, package.name
+    );
+    let response = client.get(url).send().await?;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response.headers()["content-type"],
+        "image/svg+xml;charset=utf-8"
+    );
+    assert_eq!(response.headers()["cache-control"], "public, max-age=3600");
+    let body = response.text().await?;
+    assert!(body.starts_with("<svg"));
+    assert!(body.contains(&package.version));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_downloads_badge_formats_downloads() -> Result<()> {
+    // Arrange
+    let app = spawn_app().await?;
+    let client = reqwest::Client::new();
+    let (registry, _) = app.registry_queue()?;
+    let package = app
+        .mock_create_package_with_downloads(&registry, 1_234_567)
+        .await?;
+
+    // Act
+    let url = format!(
+        "{}/badges/{}/{}/downloads.svg",
+        app.address, package.registry, package.name
+    );
+    let response = client.get(url).send().await?;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = response.text().await?;
+    assert!(body.contains("downloads: 1.2M"));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_badge_for_unknown_package_is_grey() -> Result<()> {
+    // Arrange
+    let app = spawn_app().await?;
+    let client = reqwest::Client::new();
+
+    // Act
+    let url = format!(
+        "{}/badges/crates.io/does-not-exist/version.svg",
+        app.address
+    );
+    let response = client.get(url).send().await?;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()["cache-control"], "public, max-age=300");
+    let body = response.text().await?;
+    assert!(body.contains("crates.io: unknown"));
+    assert!(body.contains("#9f9f9f"));
+
+    Ok(())
+}
@@ -124,6 +124,42 @@ async fn test_create_job_publishes_to_rabbitmq() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn test_create_job_with_force_is_stored_and_published() -> Result<()> {
+    // Arrange
+    let app = spawn_app().await?;
+    let client = reqwest::Client::new();
+    let (registry, queue_name) = app.registry_queue()?;
+
+    // Act
+    let url = format!("{}/jobs", app.address);
+    let response = client
+        .post(url)
+        .json(&json!({
+            "registry": registry,
+            "package_name": "serde",
+            "force": true,
+        }))
+        .send()
+        .await?;
+
+    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+
+    // Assert
+    let body = response.json::<serde_json::Value>().await?;
+    assert_eq!(body["data"]["force"], true);
+    assert!(body["data"]["skipped_version"].is_null());
+    let delivery = app
+        .channel
+        .basic_get(&queue_name, BasicGetOptions::default())
+        .await?
+        .context("No message received")?;
+    let payload = serde_json::from_slice::<serde_json::Value>(&delivery.data)?;
+    assert_eq!(payload["force"], true);
+
+    Ok(())
+}
+
 #[tokio::test]
 async fn test_get_jobs_returns_200() -> Result<()> {
     // Arrange
@@ -393,3 +429,154 @@ async fn test_get_job_by_id_returns_404_if_job_does_not_exist() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn test_export_jobs_streams_every_job_as_ndjson() -> Result<()> {
+    // Arrange
+    let app = spawn_app().await?;
+    let client = reqwest::Client::new();
+    let (registry, _) = app.registry_queue()?;
+    app.mock_create_jobs(&client, &registry, 150).await?;
+
+    // Act
+    let url = format!("{}/jobs/export?format=ndjson&order=asc", app.address);
+    let response = client.get(url).send().await?;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response.headers().get("Content-Type").unwrap(),
+        "application/x-ndjson"
+    );
+    let body = response.text().await?;
+    let jobs: Vec<serde_json::Value> = body
+        .lines()
+        .map(serde_json::from_str)
+        .collect::<Result<_, _>>()?;
+    assert_eq!(jobs.len(), 150);
+    assert!(jobs[0]["id"].as_str() < jobs[149]["id"].as_str());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_get_job_by_id_returns_304_for_matching_etag() -> Result<()> {
+    // Arrange
+    let app = spawn_app().await?;
+    let client = reqwest::Client::new();
+    let (registry, _) = app.registry_queue()?;
+    let job = app.mock_create_job(&client, &registry).await?;
+    let url = format!("{}/jobs/{}", app.address, job.data.id);
+    let response = client.get(&url).send().await?;
+    let etag = response
+        .headers()
+        .get("etag")
+        .context("Missing ETag")?
+        .clone();
+
+    // Act
+    let response = client
+        .get(&url)
+        .header("if-none-match", etag.clone())
+        .send()
+        .await?;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+    assert_eq!(response.headers().get("etag"), Some(&etag));
+    assert!(response.bytes().await?.is_empty());
+
+    let response = client
+        .get(&url)
+        .header("if-none-match", "\"stale\"")
+        .send()
+        .await?;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_get_jobs_list_page_has_etag() -> Result<()> {
+    // Arrange
+    let app = spawn_app().await?;
+    let client = reqwest::Client::new();
+    let (registry, _) = app.registry_queue()?;
+    app.mock_create_jobs(&client, &registry, 2).await?;
+    let url = format!("{}/jobs?limit=1&order=desc", app.address);
+    let first = client.get(&url).send().await?;
+    let etag = first.headers().get("etag").context("Missing ETag")?.clone();
+
+    // Act
+    app.mock_create_job(&client, &registry).await?;
+    let response = client
+        .get(&url)
+        .header("if-none-match", etag.clone())
+        .send()
+        .await?;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_ne!(response.headers().get("etag"), Some(&etag));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_get_job_by_id_returns_validation_errors_of_failed_job() -> Result<()> {
+    // Arrange
+    let app = spawn_app().await?;
+    let client = reqwest::Client::new();
+    let job = app
+        .mock_ingest_output(
+            "crates.io",
+            &json!({ "name": "serde", "version": "1.0.0", "downloads": -1 }),
+            false,
+        )
+        .await?;
+
+    // Act
+    let response = client
+        .get(format!("{}/jobs/{}", app.address, job.id))
+        .send()
+        .await?;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::OK);
+    let body: serde_json::Value = response.json().await?;
+    assert_eq!(body["data"]["status"], "failed");
+    let errors = body["data"]["errors"].as_array().unwrap();
+    assert_eq!(errors.len(), 1);
+    assert!(errors[0].as_str().unwrap().starts_with("/downloads: "));
+    let packages = sqlx::query!("SELECT id FROM packages;")
+        .fetch_all(&app.db_pool)
+        .await?;
+    assert!(packages.is_empty());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_create_job_assigns_registry_scoped_output_key() -> Result<()> {
+    // Arrange
+    let app = spawn_app().await?;
+    let client = reqwest::Client::new();
+    let (registry, queue) = app.registry_queue()?;
+
+    // Act
+    let job = app.mock_create_job(&client, &registry).await?;
+
+    // Assert
+    let output_key = format!("outputs/{}/{}.json", registry, job.data.id);
+    assert_eq!(job.data.output_key.as_deref(), Some(output_key.as_str()));
+
+    let delivery = app
+        .channel
+        .basic_get(&queue, BasicGetOptions::default())
+        .await?
+        .context("No message received")?;
+    let payload = serde_json::from_slice::<serde_json::Value>(&delivery.data)?;
+    assert_eq!(payload["output_key"], output_key);
+
+    Ok(())
+}
@@ -1,9 +1,16 @@
+pub mod badges;
 pub mod jobs;
 pub mod openapi;
 pub mod packages;
+pub mod projects;
+pub mod quarantine;
+pub mod registries;
+pub mod watchlists;
 
 #[cfg(test)]
 mod tests {
+    use std::time::Duration;
+
     use anyhow::Result;
     use http::StatusCode;
     use uuid::Uuid;
@@ -60,4 +67,40 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_metrics_exposes_worker_in_flight_gauges() -> Result<()> {
+        // Arrange
+        let app = spawn_app().await?;
+        let client = reqwest::Client::new();
+
+        // Act
+        let url = format!("{}/metrics", app.address);
+        let body = client.get(url).send().await?.text().await?;
+
+        // Assert
+        assert!(body.contains("worker_messages_in_flight 0"));
+        assert!(body.contains("worker_messages_waiting 0"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_stops_accepting_requests() -> Result<()> {
+        // Arrange
+        let app = spawn_app().await?;
+        let client = reqwest::Client::new();
+        let url = format!("{}/health", app.address);
+        assert_eq!(client.get(&url).send().await?.status().as_u16(), 204);
+
+        // Act
+        app.shutdown.cancel();
+        let result = tokio::time::timeout(Duration::from_secs(10), app.handle).await??;
+
+        // Assert
+        assert!(result.is_ok());
+        assert!(reqwest::Client::new().get(&url).send().await.is_err());
+
+        Ok(())
+    }
 }
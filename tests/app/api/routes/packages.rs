@@ -1,7 +1,12 @@
 use anyhow::{Context, Result};
+use chrono::{Duration, Utc};
 use http::StatusCode;
+use integrations_api::{
+    db,
+    models::{dependency::Dependency, package::Package},
+};
 
-use crate::helpers::spawn_app;
+use crate::helpers::{spawn_app, TestApp};
 
 #[tokio::test]
 async fn test_get_packages_returns_200() -> Result<()> {
@@ -68,3 +73,786 @@ async fn test_get_packages_returns_paginated_packages() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn test_get_packages_paginates_by_downloads_with_ties() -> Result<()> {
+    const LIMIT: usize = 3;
+    // Arrange
+    let app = spawn_app().await?;
+    let client = reqwest::Client::new();
+    let (registry, _) = app.registry_queue()?;
+    let mut packages = Vec::new();
+    for downloads in [5, 1, 5, 3, 5, 1, 2] {
+        packages.push(
+            app.mock_create_package_with_downloads(&registry, downloads)
+                .await?,
+        );
+    }
+    packages.sort_by(|a, b| b.downloads.cmp(&a.downloads).then(b.id.cmp(&a.id)));
+
+    // Act
+    let mut ids = Vec::new();
+    let mut after: Option<String> = None;
+    loop {
+        let url = match &after {
+            Some(after) => format!(
+                "{}/packages?limit={}&sort=downloads&order=desc&after={}",
+                app.address, LIMIT, after
+            ),
+            None => format!(
+                "{}/packages?limit={}&sort=downloads&order=desc",
+                app.address, LIMIT
+            ),
+        };
+        let response = client.get(url).send().await?;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body: serde_json::Value = response.json().await?;
+        for package in body["data"].as_array().unwrap() {
+            ids.push(package["id"].as_str().unwrap().to_string());
+        }
+        after = body["next_cursor"]
+            .as_str()
+            .map(|cursor| cursor.to_string());
+        if after.is_none() {
+            break;
+        }
+    }
+
+    // Assert
+    let expected: Vec<String> = packages.iter().map(|p| p.id.to_string()).collect();
+    assert_eq!(ids, expected);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_get_packages_filters_by_registry_and_downloads() -> Result<()> {
+    // Arrange
+    let app = spawn_app().await?;
+    let client = reqwest::Client::new();
+    let (registry, _) = app.registry_queue()?;
+    app.mock_create_package_with_downloads(&registry, 10)
+        .await?;
+    let expected = app
+        .mock_create_package_with_downloads(&registry, 50)
+        .await?;
+    app.mock_create_package_with_downloads(&registry, 100)
+        .await?;
+    app.mock_create_package_with_downloads("other", 50).await?;
+
+    // Act
+    let url = format!(
+        "{}/packages?registry={}&min_downloads=20&max_downloads=60",
+        app.address, registry
+    );
+    let response = client.get(url).send().await?;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::OK);
+    let body: serde_json::Value = response.json().await?;
+    let data = body["data"].as_array().unwrap();
+    assert_eq!(data.len(), 1);
+    assert_eq!(data[0]["id"], expected.id.to_string());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_get_packages_returns_400_for_invalid_cursor() -> Result<()> {
+    // Arrange
+    let app = spawn_app().await?;
+    let client = reqwest::Client::new();
+
+    // Act
+    let url = format!("{}/packages?sort=name&after=invalid", app.address);
+    let response = client.get(url).send().await?;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_get_packages_returns_400_if_cursor_does_not_match_sort() -> Result<()> {
+    // Arrange
+    let app = spawn_app().await?;
+    let client = reqwest::Client::new();
+    let (registry, _) = app.registry_queue()?;
+    app.mock_create_packages(&registry, 2).await?;
+    let url = format!("{}/packages?limit=1&sort=downloads", app.address);
+    let body: serde_json::Value = client.get(url).send().await?.json().await?;
+    let cursor = body["next_cursor"].as_str().context("Missing cursor")?;
+
+    // Act
+    let url = format!("{}/packages?sort=name&after={}", app.address, cursor);
+    let response = client.get(url).send().await?;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_get_packages_returns_400_if_min_downloads_exceeds_max() -> Result<()> {
+    // Arrange
+    let app = spawn_app().await?;
+    let client = reqwest::Client::new();
+
+    // Act
+    let url = format!("{}/packages?min_downloads=10&max_downloads=5", app.address);
+    let response = client.get(url).send().await?;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_get_packages_returns_package_metadata() -> Result<()> {
+    // Arrange
+    let app = spawn_app().await?;
+    let client = reqwest::Client::new();
+    let (registry, _) = app.registry_queue()?;
+    app.mock_create_package(&registry).await?;
+
+    // Act
+    let url = format!("{}/packages", app.address);
+    let response = client.get(url).send().await?;
+
+    // Assert
+    let body: serde_json::Value = response.json().await?;
+    let package = &body["data"][0];
+    for field in [
+        "description",
+        "license",
+        "repository_url",
+        "homepage",
+        "published_at",
+    ] {
+        assert!(package[field].is_null(), "{field} should be null");
+    }
+    assert_eq!(package["keywords"], serde_json::json!([]));
+    assert_eq!(package["deprecated"], false);
+
+    Ok(())
+}
+
+async fn mock_dependency_chain(app: &TestApp) -> Result<Vec<Package>> {
+    let (registry, _) = app.registry_queue()?;
+    let packages = app.mock_create_packages(&registry, 3).await?;
+    let mut conn = app.db_pool.acquire().await?;
+    for pair in packages.windows(2) {
+        let dependency = Dependency {
+            name: pair[1].name.clone(),
+            requirement: "^1.0".to_string(),
+            kind: "normal".to_string(),
+        };
+        db::replace_package_dependencies(&mut conn, pair[0].id, &[dependency]).await?;
+    }
+
+    Ok(packages)
+}
+
+#[tokio::test]
+async fn test_get_package_dependencies_follows_transitive_dependencies() -> Result<()> {
+    // Arrange
+    let app = spawn_app().await?;
+    let client = reqwest::Client::new();
+    let packages = mock_dependency_chain(&app).await?;
+    let root = &packages[0];
+
+    // Act
+    let direct: serde_json::Value = client
+        .get(format!(
+            "{}/packages/{}/{}/dependencies",
+            app.address, root.registry, root.name
+        ))
+        .send()
+        .await?
+        .json()
+        .await?;
+    let transitive: serde_json::Value = client
+        .get(format!(
+            "{}/packages/{}/{}/dependencies?depth=2",
+            app.address, root.registry, root.name
+        ))
+        .send()
+        .await?
+        .json()
+        .await?;
+
+    // Assert
+    assert_eq!(direct["data"].as_array().unwrap().len(), 1);
+    assert_eq!(direct["data"][0]["dependency_name"], packages[1].name);
+    let transitive = transitive["data"].as_array().unwrap();
+    assert_eq!(transitive.len(), 2);
+    assert_eq!(transitive[1]["package_name"], packages[1].name);
+    assert_eq!(transitive[1]["dependency_name"], packages[2].name);
+    assert_eq!(transitive[1]["depth"], 2);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_replace_package_dependencies_accepts_duplicated_dependency() -> Result<()> {
+    // Arrange
+    let app = spawn_app().await?;
+    let client = reqwest::Client::new();
+    let (registry, _) = app.registry_queue()?;
+    let package = app.mock_create_package(&registry).await?;
+    let dependencies = ["^0.2.100", "^0.2.150"].map(|requirement| Dependency {
+        name: "libc".to_string(),
+        requirement: requirement.to_string(),
+        kind: "normal".to_string(),
+    });
+
+    // Act
+    let mut conn = app.db_pool.acquire().await?;
+    db::replace_package_dependencies(&mut conn, package.id, &dependencies).await?;
+
+    // Assert
+    let body: serde_json::Value = client
+        .get(format!(
+            "{}/packages/{}/{}/dependencies",
+            app.address, package.registry, package.name
+        ))
+        .send()
+        .await?
+        .json()
+        .await?;
+    let data = body["data"].as_array().unwrap();
+    assert_eq!(data.len(), 1);
+    assert_eq!(data[0]["requirement"], "^0.2.150");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_get_package_dependencies_reports_diamond_edges_once() -> Result<()> {
+    // Arrange
+    let app = spawn_app().await?;
+    let client = reqwest::Client::new();
+    let (registry, _) = app.registry_queue()?;
+    let packages = app.mock_create_packages(&registry, 4).await?;
+    let mut conn = app.db_pool.acquire().await?;
+    for (from, to) in [(0, vec![1, 2]), (1, vec![3]), (2, vec![3])] {
+        let dependencies: Vec<Dependency> = to
+            .into_iter()
+            .map(|to: usize| Dependency {
+                name: packages[to].name.clone(),
+                requirement: "^1.0".to_string(),
+                kind: "normal".to_string(),
+            })
+            .collect();
+        db::replace_package_dependencies(&mut conn, packages[from].id, &dependencies).await?;
+    }
+    let root = &packages[0];
+
+    // Act
+    let body: serde_json::Value = client
+        .get(format!(
+            "{}/packages/{}/{}/dependencies?depth=5",
+            app.address, root.registry, root.name
+        ))
+        .send()
+        .await?
+        .json()
+        .await?;
+
+    // Assert
+    let data = body["data"].as_array().unwrap();
+    assert_eq!(data.len(), 4);
+    assert!(data[2..]
+        .iter()
+        .all(|edge| edge["dependency_name"] == packages[3].name && edge["depth"] == 2));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_get_package_dependents_follows_transitive_dependents() -> Result<()> {
+    // Arrange
+    let app = spawn_app().await?;
+    let client = reqwest::Client::new();
+    let packages = mock_dependency_chain(&app).await?;
+    let leaf = &packages[2];
+
+    // Act
+    let response = client
+        .get(format!(
+            "{}/packages/{}/{}/dependents?depth=5",
+            app.address, leaf.registry, leaf.name
+        ))
+        .send()
+        .await?;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::OK);
+    let body: serde_json::Value = response.json().await?;
+    let data = body["data"].as_array().unwrap();
+    assert_eq!(data.len(), 2);
+    assert_eq!(data[0]["package_name"], packages[1].name);
+    assert_eq!(data[0]["depth"], 1);
+    assert_eq!(data[1]["package_name"], packages[0].name);
+    assert_eq!(data[1]["depth"], 2);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_get_package_dependencies_returns_404_for_unknown_package() -> Result<()> {
+    // Arrange
+    let app = spawn_app().await?;
+    let client = reqwest::Client::new();
+
+    // Act
+    let url = format!("{}/packages/unknown/unknown/dependencies", app.address);
+    let response = client.get(url).send().await?;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_get_package_dependencies_returns_400_for_invalid_depth() -> Result<()> {
+    // Arrange
+    let app = spawn_app().await?;
+    let client = reqwest::Client::new();
+
+    // Act
+    let url = format!(
+        "{}/packages/unknown/unknown/dependencies?depth=0",
+        app.address
+    );
+    let response = client.get(url).send().await?;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_delete_package_hides_it_from_listings() -> Result<()> {
+    // Arrange
+    let app = spawn_app().await?;
+    let client = reqwest::Client::new();
+    let (registry, _) = app.registry_queue()?;
+    let deleted = app.mock_create_package(&registry).await?;
+    let kept = app.mock_create_package(&registry).await?;
+
+    // Act
+    let url = format!(
+        "{}/packages/{}/{}",
+        app.address, deleted.registry, deleted.name
+    );
+    let response = client
+        .delete(url)
+        .json(&serde_json::json!({ "reason": "Ingested by mistake" }))
+        .send()
+        .await?;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::OK);
+    let body: serde_json::Value = response.json().await?;
+    assert_eq!(body["data"]["deletion_reason"], "Ingested by mistake");
+    assert!(body["data"]["deleted_at"].is_string());
+
+    let url = format!("{}/packages?registry={}", app.address, registry);
+    let body: serde_json::Value = client.get(url).send().await?.json().await?;
+    let data = body["data"].as_array().unwrap();
+    assert_eq!(data.len(), 1);
+    assert_eq!(data[0]["id"], kept.id.to_string());
+
+    let url = format!(
+        "{}/packages?registry={}&include_deleted=true",
+        app.address, registry
+    );
+    let body: serde_json::Value = client.get(url).send().await?.json().await?;
+    assert_eq!(body["data"].as_array().unwrap().len(), 2);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_reingesting_a_deleted_package_keeps_it_deleted() -> Result<()> {
+    // Arrange
+    let app = spawn_app().await?;
+    let client = reqwest::Client::new();
+    let (registry, _) = app.registry_queue()?;
+    let package = app.mock_create_package(&registry).await?;
+    let url = format!(
+        "{}/packages/{}/{}",
+        app.address, package.registry, package.name
+    );
+    client
+        .delete(&url)
+        .json(&serde_json::json!({ "reason": "Ingested by mistake" }))
+        .send()
+        .await?;
+
+    // Act
+    let mut conn = app.db_pool.acquire().await?;
+    let name = package.name.clone();
+    db::upsert_package(
+        &mut conn,
+        Package {
+            version: "2.0.0".to_string(),
+            ..package
+        },
+    )
+    .await?;
+    let url = format!("{}/packages/import?format=ndjson", app.address);
+    let body =
+        serde_json::json!({"registry": registry, "name": name, "version": "3.0.0", "downloads": 1});
+    client.post(url).body(body.to_string()).send().await?;
+
+    // Assert
+    let row = sqlx::query!(
+        "SELECT version, deletion_reason FROM packages WHERE registry = $1 AND name = $2;",
+        registry,
+        name,
+    )
+    .fetch_one(&app.db_pool)
+    .await?;
+    assert_eq!(row.version, "3.0.0");
+    assert_eq!(row.deletion_reason.as_deref(), Some("Ingested by mistake"));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_delete_package_returns_404_if_already_deleted() -> Result<()> {
+    // Arrange
+    let app = spawn_app().await?;
+    let client = reqwest::Client::new();
+    let (registry, _) = app.registry_queue()?;
+    let package = app.mock_create_package(&registry).await?;
+    let url = format!(
+        "{}/packages/{}/{}",
+        app.address, package.registry, package.name
+    );
+    let payload = serde_json::json!({ "reason": "Duplicate" });
+    client.delete(&url).json(&payload).send().await?;
+
+    // Act
+    let response = client.delete(&url).json(&payload).send().await?;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_delete_package_returns_400_without_reason() -> Result<()> {
+    // Arrange
+    let app = spawn_app().await?;
+    let client = reqwest::Client::new();
+    let (registry, _) = app.registry_queue()?;
+    let package = app.mock_create_package(&registry).await?;
+
+    // Act
+    let url = format!(
+        "{}/packages/{}/{}",
+        app.address, package.registry, package.name
+    );
+    let response = client
+        .delete(url)
+        .json(&serde_json::json!({ "reason": " " }))
+        .send()
+        .await?;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_export_packages_streams_filtered_packages_as_csv() -> Result<()> {
+    // Arrange
+    let app = spawn_app().await?;
+    let client = reqwest::Client::new();
+    let (registry, _) = app.registry_queue()?;
+    for downloads in 0..120 {
+        app.mock_create_package_with_downloads(&registry, downloads)
+            .await?;
+    }
+    app.mock_create_package_with_downloads("other", 50).await?;
+
+    // Act
+    let url = format!(
+        "{}/packages/export?format=csv&registry={}&min_downloads=10",
+        app.address, registry
+    );
+    let response = client.get(url).send().await?;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers().get("Content-Type").unwrap(), "text/csv");
+    let body = response.text().await?;
+    let mut lines = body.lines();
+    assert!(lines
+        .next()
+        .unwrap()
+        .starts_with("id,registry,name,version,downloads"));
+    assert_eq!(lines.count(), 110);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_export_packages_returns_400_if_min_downloads_exceeds_max() -> Result<()> {
+    // Arrange
+    let app = spawn_app().await?;
+    let client = reqwest::Client::new();
+
+    // Act
+    let url = format!(
+        "{}/packages/export?min_downloads=10&max_downloads=5",
+        app.address
+    );
+    let response = client.get(url).send().await?;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_import_packages_upserts_rows_and_reports_invalid_lines() -> Result<()> {
+    // Arrange
+    let app = spawn_app().await?;
+    let client = reqwest::Client::new();
+    let (registry, _) = app.registry_queue()?;
+    let existing = app.mock_create_package(&registry).await?;
+    let body = format!(
+        "{}\n{}\nnot json\n{}\n",
+        serde_json::json!({"registry": registry, "name": existing.name, "version": "2.0.0", "downloads": 42}),
+        serde_json::json!({"registry": registry, "name": "imported", "version": "1.0.0", "downloads": 7, "keywords": ["a", "b"]}),
+        serde_json::json!({"registry": registry, "name": "negative", "version": "1.0.0", "downloads": -1}),
+    );
+
+    // Act
+    let url = format!("{}/packages/import?format=ndjson", app.address);
+    let response = client.post(url).body(body).send().await?;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::OK);
+    let report: serde_json::Value = response.json().await?;
+    assert_eq!(report["data"]["imported"], 2);
+    assert_eq!(report["data"]["failed"], 2);
+    assert_eq!(report["data"]["errors"][0]["line"], 3);
+    assert_eq!(report["data"]["errors"][1]["line"], 4);
+
+    let mut conn = app.db_pool.acquire().await?;
+    let updated = db::get_package(&mut conn, &registry, &existing.name)
+        .await?
+        .context("Package not found")?;
+    assert_eq!(updated.id, existing.id);
+    assert_eq!(updated.version, "2.0.0");
+    let imported = db::get_package(&mut conn, &registry, "imported")
+        .await?
+        .context("Package not found")?;
+    assert_eq!(imported.keywords, vec!["a", "b"]);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_import_packages_accepts_csv() -> Result<()> {
+    // Arrange
+    let app = spawn_app().await?;
+    let client = reqwest::Client::new();
+    let (registry, _) = app.registry_queue()?;
+    let body = format!(
+        "registry,name,version,downloads,description,keywords\n{0},first,1.0.0,10,\"Spans\ntwo lines\",\"a,b\"\n{0},second,1.0.0,x,,\n",
+        registry
+    );
+
+    // Act
+    let url = format!("{}/packages/import?format=csv", app.address);
+    let response = client.post(url).body(body).send().await?;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::OK);
+    let report: serde_json::Value = response.json().await?;
+    assert_eq!(report["data"]["imported"], 1);
+    assert_eq!(report["data"]["errors"][0]["line"], 4);
+
+    let mut conn = app.db_pool.acquire().await?;
+    let package = db::get_package(&mut conn, &registry, "first")
+        .await?
+        .context("Package not found")?;
+    assert_eq!(package.description.as_deref(), Some("Spans\ntwo lines"));
+    assert_eq!(package.keywords, vec!["a", "b"]);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_get_trending_packages_ranks_by_relative_and_absolute_growth() -> Result<()> {
+    // Arrange
+    let app = spawn_app().await?;
+    let client = reqwest::Client::new();
+    let (registry, _) = app.registry_queue()?;
+    let week_ago = Utc::now() - Duration::days(8);
+    let month_ago = Utc::now() - Duration::days(31);
+    let popular = app
+        .mock_create_package_with_downloads(&registry, 100_000)
+        .await?;
+    let rising = app
+        .mock_create_package_with_downloads(&registry, 3000)
+        .await?;
+    let tiny = app
+        .mock_create_package_with_downloads(&registry, 100)
+        .await?;
+    let mut conn = app.db_pool.acquire().await?;
+    db::insert_package_downloads(&mut conn, popular.id, 90_000, week_ago).await?;
+    db::insert_package_downloads(&mut conn, popular.id, 10_000, month_ago).await?;
+    db::insert_package_downloads(&mut conn, rising.id, 1500, week_ago).await?;
+    db::insert_package_downloads(&mut conn, rising.id, 1000, month_ago).await?;
+    db::insert_package_downloads(&mut conn, tiny.id, 10, week_ago).await?;
+    db::refresh_package_growth(&mut conn).await?;
+
+    // Act
+    let url = format!("{}/packages/trending?registry={}", app.address, registry);
+    let relative: serde_json::Value = client.get(url).send().await?.json().await?;
+    let url = format!(
+        "{}/packages/trending?registry={}&by=absolute",
+        app.address, registry
+    );
+    let absolute: serde_json::Value = client.get(url).send().await?.json().await?;
+    let url = format!(
+        "{}/packages/trending?registry={}&window=30d",
+        app.address, registry
+    );
+    let monthly: serde_json::Value = client.get(url).send().await?.json().await?;
+
+    // Assert
+    let data = relative["data"].as_array().unwrap();
+    assert_eq!(data.len(), 2);
+    assert_eq!(data[0]["id"], rising.id.to_string());
+    assert_eq!(data[0]["rank"], 1);
+    assert_eq!(data[0]["previous_downloads"], 1500);
+    assert_eq!(data[0]["downloads_growth"], 1500);
+    assert_eq!(data[0]["relative_growth"], 1.0);
+    assert_eq!(data[1]["id"], popular.id.to_string());
+
+    let data = absolute["data"].as_array().unwrap();
+    assert_eq!(data[0]["id"], popular.id.to_string());
+    assert_eq!(data[0]["downloads_growth"], 10_000);
+    assert_eq!(data[1]["id"], rising.id.to_string());
+
+    let data = monthly["data"].as_array().unwrap();
+    assert_eq!(data[0]["id"], popular.id.to_string());
+    assert_eq!(data[0]["relative_growth"], 9.0);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_get_trending_packages_rejects_invalid_window() -> Result<()> {
+    // Arrange
+    let app = spawn_app().await?;
+    let client = reqwest::Client::new();
+
+    // Act
+    let url = format!("{}/packages/trending?window=1y", app.address);
+    let response = client.get(url).send().await?;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_get_package_etag_changes_when_package_is_updated() -> Result<()> {
+    // Arrange
+    let app = spawn_app().await?;
+    let client = reqwest::Client::new();
+    let (registry, _) = app.registry_queue()?;
+    let package = app.mock_create_package(&registry).await?;
+    let url = format!(
+        "{}/packages/{}/{}",
+        app.address, package.registry, package.name
+    );
+    let response = client.get(&url).send().await?;
+    assert_eq!(response.status(), StatusCode::OK);
+    let etag = response
+        .headers()
+        .get("etag")
+        .context("Missing ETag")?
+        .clone();
+
+    // Act
+    sqlx::query!(
+        "UPDATE packages SET downloads = downloads + 1, updated_at = now() WHERE id = $1;",
+        package.id,
+    )
+    .execute(&app.db_pool)
+    .await?;
+    let response = client
+        .get(&url)
+        .header("if-none-match", etag.clone())
+        .send()
+        .await?;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_ne!(response.headers().get("etag"), Some(&etag));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_delete_package_honours_if_match() -> Result<()> {
+    // Arrange
+    let app = spawn_app().await?;
+    let client = reqwest::Client::new();
+    let (registry, _) = app.registry_queue()?;
+    let package = app.mock_create_package(&registry).await?;
+    let url = format!(
+        "{}/packages/{}/{}",
+        app.address, package.registry, package.name
+    );
+    let etag = client
+        .get(&url)
+        .send()
+        .await?
+        .headers()
+        .get("etag")
+        .context("Missing ETag")?
+        .clone();
+
+    // Act
+    let stale = client
+        .delete(&url)
+        .header("if-match", "\"stale\"")
+        .json(&serde_json::json!({ "reason": "Duplicate" }))
+        .send()
+        .await?;
+    let current = client
+        .delete(&url)
+        .header("if-match", etag)
+        .json(&serde_json::json!({ "reason": "Duplicate" }))
+        .send()
+        .await?;
+
+    // Assert
+    assert_eq!(stale.status(), StatusCode::PRECONDITION_FAILED);
+    assert_eq!(current.status(), StatusCode::OK);
+    let response = client.get(&url).send().await?;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    Ok(())
+}
@@ -0,0 +1,183 @@
+use anyhow::Result;
+use http::StatusCode;
+use integrations_api::models::package::Package;
+use uuid::Uuid;
+
+use crate::helpers::{spawn_app, TestApp};
+
+async fn mock_create_package_with_repository(
+    app: &TestApp,
+    registry: &str,
+    downloads: i64,
+    repository_url: &str,
+) -> Result<Package> {
+    let package = app
+        .mock_create_package_with_downloads(registry, downloads)
+        .await?;
+    let package = sqlx::query_as!(
+        Package,
+        "UPDATE packages SET repository_url = $2 WHERE id = $1 RETURNING *;",
+        package.id,
+        repository_url,
+    )
+    .fetch_one(&app.db_pool)
+    .await?;
+
+    Ok(package)
+}
+
+#[tokio::test]
+async fn test_create_project_links_packages_with_same_repository() -> Result<()> {
+    // Arrange
+    let app = spawn_app().await?;
+    let client = reqwest::Client::new();
+    mock_create_package_with_repository(&app, "npmjs.com", 100, "https://github.com/acme/lib")
+        .await?;
+    mock_create_package_with_repository(&app, "jsr.io", 20, "git+https://github.com/Acme/lib.git")
+        .await?;
+    mock_create_package_with_repository(&app, "jsr.io", 5, "https://github.com/acme/other").await?;
+
+    // Act
+    let url = format!("{}/projects", app.address);
+    let response = client
+        .post(url)
+        .json(&serde_json::json!({
+            "name": "lib",
+            "repository_url": "https://github.com/acme/lib/"
+        }))
+        .send()
+        .await?;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body: serde_json::Value = response.json().await?;
+    assert_eq!(
+        body["data"]["repository_url"],
+        "https://github.com/acme/lib"
+    );
+
+    let url = format!(
+        "{}/projects/{}",
+        app.address,
+        body["data"]["id"].as_str().unwrap()
+    );
+    let body: serde_json::Value = client.get(url).send().await?.json().await?;
+    assert_eq!(body["data"]["downloads"], 120);
+    let packages = body["data"]["packages"].as_array().unwrap();
+    assert_eq!(packages.len(), 2);
+    assert_eq!(packages[0]["registry"], "jsr.io");
+    assert_eq!(packages[1]["registry"], "npmjs.com");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_link_package_adds_it_to_the_project() -> Result<()> {
+    // Arrange
+    let app = spawn_app().await?;
+    let client = reqwest::Client::new();
+    let (registry, _) = app.registry_queue()?;
+    let package = app.mock_create_package(&registry).await?;
+    let url = format!("{}/projects", app.address);
+    let body: serde_json::Value = client
+        .post(url)
+        .json(&serde_json::json!({ "name": "lib" }))
+        .send()
+        .await?
+        .json()
+        .await?;
+    let project_id = body["data"]["id"].as_str().unwrap().to_string();
+
+    // Act
+    let url = format!("{}/projects/{}/packages", app.address, project_id);
+    let response = client
+        .post(url)
+        .json(&serde_json::json!({ "registry": registry, "name": package.name }))
+        .send()
+        .await?;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::OK);
+    let url = format!("{}/projects/{}", app.address, project_id);
+    let body: serde_json::Value = client.get(url).send().await?.json().await?;
+    let packages = body["data"]["packages"].as_array().unwrap();
+    assert_eq!(packages.len(), 1);
+    assert_eq!(packages[0]["id"], package.id.to_string());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_link_package_returns_404_for_unknown_package() -> Result<()> {
+    // Arrange
+    let app = spawn_app().await?;
+    let client = reqwest::Client::new();
+    let url = format!("{}/projects", app.address);
+    let body: serde_json::Value = client
+        .post(url)
+        .json(&serde_json::json!({ "name": "lib" }))
+        .send()
+        .await?
+        .json()
+        .await?;
+
+    // Act
+    let url = format!(
+        "{}/projects/{}/packages",
+        app.address,
+        body["data"]["id"].as_str().unwrap()
+    );
+    let response = client
+        .post(url)
+        .json(&serde_json::json!({ "registry": "unknown", "name": "unknown" }))
+        .send()
+        .await?;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_create_project_returns_400_for_duplicate_repository() -> Result<()> {
+    // Arrange
+    let app = spawn_app().await?;
+    let client = reqwest::Client::new();
+    let url = format!("{}/projects", app.address);
+    client
+        .post(&url)
+        .json(
+            &serde_json::json!({ "name": "lib", "repository_url": "https://github.com/acme/lib" }),
+        )
+        .send()
+        .await?;
+
+    // Act
+    let response = client
+        .post(&url)
+        .json(&serde_json::json!({ "name": "lib", "repository_url": "https://github.com/acme/lib.git" }))
+        .send()
+        .await?;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_get_project_returns_404_for_unknown_project() -> Result<()> {
+    // Arrange
+    let app = spawn_app().await?;
+    let client = reqwest::Client::new();
+
+    // Act
+    let url = format!("{}/projects/{}", app.address, Uuid::now_v7());
+    let response = client.get(url).send().await?;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    Ok(())
+}
@@ -0,0 +1,226 @@
+use std::time::Duration;
+
+use anyhow::Result;
+use http::StatusCode;
+use lapin::{options::BasicPublishOptions, BasicProperties};
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::helpers::spawn_app;
+
+#[tokio::test]
+async fn test_worker_quarantines_unparseable_messages() -> Result<()> {
+    // Arrange
+    let app = spawn_app().await?;
+
+    // Act
+    app.channel
+        .basic_publish(
+            "",
+            &app.queue_consumer,
+            BasicPublishOptions::default(),
+            b"{\"job_id\": \"not-a-uuid\"}",
+            BasicProperties::default(),
+        )
+        .await?
+        .await?;
+
+    // Assert
+    let mut quarantined = Vec::new();
+    for _ in 0..50 {
+        quarantined = sqlx::query!("SELECT routing_key, error, size FROM quarantined_messages;")
+            .fetch_all(&app.db_pool)
+            .await?;
+        if !quarantined.is_empty() {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    assert_eq!(quarantined.len(), 1);
+    assert_eq!(quarantined[0].routing_key, app.queue_consumer);
+    assert_eq!(quarantined[0].size, 24);
+    assert!(!quarantined[0].error.is_empty());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_get_quarantined_messages_lists_stored_messages() -> Result<()> {
+    // Arrange
+    let app = spawn_app().await?;
+    let client = reqwest::Client::new();
+    let stored = app
+        .quarantine
+        .store("integrations", &None, b"not json", "expected value")
+        .await?;
+
+    // Act
+    let url = format!("{}/quarantine", app.address);
+    let response = client.get(url).send().await?;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = response.json::<serde_json::Value>().await?;
+    assert_eq!(body["data"].as_array().unwrap().len(), 1);
+    assert_eq!(body["data"][0]["id"], stored.id.to_string());
+    assert_eq!(body["data"][0]["error"], "expected value");
+    assert!(body["data"][0]["reinjected_at"].is_null());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_get_quarantined_payload_returns_raw_bytes() -> Result<()> {
+    // Arrange
+    let app = spawn_app().await?;
+    let client = reqwest::Client::new();
+    let stored = app
+        .quarantine
+        .store("integrations", &None, b"\x00not json", "expected value")
+        .await?;
+
+    // Act
+    let url = format!("{}/quarantine/{}/payload", app.address, stored.id);
+    let response = client.get(url).send().await?;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.bytes().await?.as_ref(), b"\x00not json");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_get_quarantined_message_returns_404_for_unknown_id() -> Result<()> {
+    // Arrange
+    let app = spawn_app().await?;
+    let client = reqwest::Client::new();
+
+    // Act
+    let url = format!("{}/quarantine/{}", app.address, Uuid::now_v7());
+    let response = client.get(url).send().await?;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_reinject_rejects_payload_that_still_does_not_parse() -> Result<()> {
+    // Arrange
+    let app = spawn_app().await?;
+    let client = reqwest::Client::new();
+    let stored = app
+        .quarantine
+        .store("integrations", &None, b"not json", "expected value")
+        .await?;
+
+    // Act
+    let url = format!("{}/quarantine/{}/reinject", app.address, stored.id);
+    let response = client.post(url).send().await?;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_reinject_publishes_corrected_message() -> Result<()> {
+    // Arrange
+    let app = spawn_app().await?;
+    let client = reqwest::Client::new();
+    let (registry, _) = app.registry_queue()?;
+    let job = app.mock_create_job(&client, &registry).await?.data;
+    let stored = app
+        .quarantine
+        .store(
+            "integrations",
+            &None,
+            b"{\"job\": 1}",
+            "missing field `job_id`",
+        )
+        .await?;
+
+    // Act
+    let url = format!("{}/quarantine/{}/reinject", app.address, stored.id);
+    let response = client
+        .post(url)
+        .json(&json!({
+            "job_id": job.id,
+            "registry": job.registry,
+            "package_name": job.package_name,
+        }))
+        .send()
+        .await?;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::ACCEPTED);
+    let body = response.json::<serde_json::Value>().await?;
+    assert!(!body["data"]["reinjected_at"].is_null());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_reinject_rejects_message_for_unknown_job() -> Result<()> {
+    // Arrange
+    let app = spawn_app().await?;
+    let client = reqwest::Client::new();
+    let stored = app
+        .quarantine
+        .store("integrations", &None, b"{}", "missing field `job_id`")
+        .await?;
+
+    // Act
+    let url = format!("{}/quarantine/{}/reinject", app.address, stored.id);
+    let response = client
+        .post(url)
+        .json(&json!({
+            "job_id": Uuid::now_v7(),
+            "registry": "crates.io",
+            "package_name": "serde",
+        }))
+        .send()
+        .await?;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_reinject_rejects_message_that_was_already_reinjected() -> Result<()> {
+    // Arrange
+    let app = spawn_app().await?;
+    let client = reqwest::Client::new();
+    let (registry, _) = app.registry_queue()?;
+    let job = app.mock_create_job(&client, &registry).await?.data;
+    let payload = json!({
+        "job_id": job.id,
+        "registry": job.registry,
+        "package_name": job.package_name,
+    });
+    let stored = app
+        .quarantine
+        .store(
+            "integrations",
+            &None,
+            payload.to_string().as_bytes(),
+            "expected value",
+        )
+        .await?;
+    let url = format!("{}/quarantine/{}/reinject", app.address, stored.id);
+    let first = client.post(&url).send().await?;
+
+    // Act
+    let second = client.post(&url).send().await?;
+
+    // Assert
+    assert_eq!(first.status(), StatusCode::ACCEPTED);
+    assert_eq!(second.status(), StatusCode::CONFLICT);
+
+    Ok(())
+}
@@ -0,0 +1,111 @@
+use anyhow::Result;
+use chrono::{Duration, Utc};
+use http::StatusCode;
+use integrations_api::db;
+
+use crate::helpers::spawn_app;
+
+#[tokio::test]
+async fn test_get_top_packages_ranks_by_downloads() -> Result<()> {
+    // Arrange
+    let app = spawn_app().await?;
+    let client = reqwest::Client::new();
+    let (registry, _) = app.registry_queue()?;
+    let low = app
+        .mock_create_package_with_downloads(&registry, 10)
+        .await?;
+    let high = app
+        .mock_create_package_with_downloads(&registry, 1000)
+        .await?;
+    let mid = app
+        .mock_create_package_with_downloads(&registry, 100)
+        .await?;
+    app.mock_create_package_with_downloads("other", 5000)
+        .await?;
+    let mut conn = app.db_pool.acquire().await?;
+    db::refresh_package_rankings(&mut conn).await?;
+
+    // Act
+    let url = format!("{}/registries/{}/top", app.address, registry);
+    let response = client.get(url).send().await?;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::OK);
+    let body: serde_json::Value = response.json().await?;
+    let data = body["data"].as_array().unwrap();
+    assert_eq!(data.len(), 3);
+    for (i, package) in [&high, &mid, &low].iter().enumerate() {
+        assert_eq!(data[i]["id"], package.id.to_string());
+        assert_eq!(data[i]["rank"], i + 1);
+    }
+    assert_eq!(data[0]["percentile"], 100.0);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_get_top_packages_ranks_by_growth() -> Result<()> {
+    // Arrange
+    let app = spawn_app().await?;
+    let client = reqwest::Client::new();
+    let (registry, _) = app.registry_queue()?;
+    let week_ago = Utc::now() - Duration::days(8);
+    let popular = app
+        .mock_create_package_with_downloads(&registry, 1000)
+        .await?;
+    let growing = app
+        .mock_create_package_with_downloads(&registry, 500)
+        .await?;
+    let mut conn = app.db_pool.acquire().await?;
+    db::insert_package_downloads(&mut conn, popular.id, 990, week_ago).await?;
+    db::insert_package_downloads(&mut conn, growing.id, 100, week_ago).await?;
+    db::refresh_package_rankings(&mut conn).await?;
+
+    // Act
+    let url = format!("{}/registries/{}/top?by=growth", app.address, registry);
+    let response = client.get(url).send().await?;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::OK);
+    let body: serde_json::Value = response.json().await?;
+    let data = body["data"].as_array().unwrap();
+    assert_eq!(data[0]["id"], growing.id.to_string());
+    assert_eq!(data[0]["downloads_growth"], 400);
+    assert_eq!(data[1]["id"], popular.id.to_string());
+    assert_eq!(data[1]["downloads_growth"], 10);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_get_top_packages_returns_404_for_unknown_registry() -> Result<()> {
+    // Arrange
+    let app = spawn_app().await?;
+    let client = reqwest::Client::new();
+
+    // Act
+    let url = format!("{}/registries/unknown/top", app.address);
+    let response = client.get(url).send().await?;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_get_top_packages_returns_400_for_invalid_by() -> Result<()> {
+    // Arrange
+    let app = spawn_app().await?;
+    let client = reqwest::Client::new();
+    let (registry, _) = app.registry_queue()?;
+
+    // Act
+    let url = format!("{}/registries/{}/top?by=invalid", app.address, registry);
+    let response = client.get(url).send().await?;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    Ok(())
+}
@@ -0,0 +1,345 @@
+use anyhow::Result;
+use chrono::Utc;
+use http::StatusCode;
+use integrations_api::db;
+use serde_json::Value;
+
+use crate::helpers::{spawn_app, TestApp};
+
+async fn create_watchlist(app: &TestApp, payload: Value) -> Result<reqwest::Response> {
+    let url = format!("{}/watchlists", app.address);
+    let response = reqwest::Client::new()
+        .post(url)
+        .json(&payload)
+        .send()
+        .await?;
+
+    Ok(response)
+}
+
+#[tokio::test]
+async fn test_create_watchlist_returns_packages_and_targets() -> Result<()> {
+    // Arrange
+    let app = spawn_app().await?;
+
+    // Act
+    let response = create_watchlist(
+        &app,
+        serde_json::json!({
+            "name": "frontend",
+            "packages": [
+                { "registry": "npmjs.com", "name": "react" },
+                { "registry": "npmjs.com", "name": "react" },
+                { "registry": "crates.io", "name": "serde" }
+            ],
+            "targets": [
+                { "channel": "webhook", "target": "https://example.com/hooks/versions" },
+                { "channel": "email", "target": "team@example.com" }
+            ]
+        }),
+    )
+    .await?;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let body: Value = response.json().await?;
+    assert_eq!(body["data"]["name"], "frontend");
+    let packages = body["data"]["packages"].as_array().unwrap();
+    assert_eq!(packages.len(), 2);
+    assert_eq!(packages[0]["registry"], "crates.io");
+    let targets = body["data"]["targets"].as_array().unwrap();
+    assert_eq!(targets.len(), 2);
+    assert_eq!(targets[0]["channel"], "webhook");
+    assert_eq!(targets[1]["channel"], "email");
+
+    let url = format!(
+        "{}/watchlists/{}",
+        app.address,
+        body["data"]["id"].as_str().unwrap()
+    );
+    let response = reqwest::Client::new().get(url).send().await?;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_create_watchlist_rejects_invalid_targets() -> Result<()> {
+    // Arrange
+    let app = spawn_app().await?;
+    let payloads = [
+        serde_json::json!({ "name": " " }),
+        serde_json::json!({
+            "name": "invalid",
+            "targets": [{ "channel": "webhook", "target": "ftp://example.com" }]
+        }),
+        serde_json::json!({
+            "name": "invalid",
+            "targets": [{ "channel": "email", "target": "not an address" }]
+        }),
+    ];
+
+    for payload in payloads {
+        // Act
+        let response = create_watchlist(&app, payload).await?;
+
+        // Assert
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_watchlist_packages_can_be_added_and_removed() -> Result<()> {
+    // Arrange
+    let app = spawn_app().await?;
+    let client = reqwest::Client::new();
+    let body: Value = create_watchlist(&app, serde_json::json!({ "name": "backend" }))
+        .await?
+        .json()
+        .await?;
+    let id = body["data"]["id"].as_str().unwrap();
+
+    // Act
+    let url = format!("{}/watchlists/{}/packages", app.address, id);
+    let response = client
+        .post(url)
+        .json(&serde_json::json!({
+            "packages": [{ "registry": "crates.io", "name": "tokio" }]
+        }))
+        .send()
+        .await?;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::OK);
+    let body: Value = response.json().await?;
+    assert_eq!(body["data"].as_array().unwrap().len(), 1);
+
+    let url = format!("{}/watchlists/{}/packages/crates.io/tokio", app.address, id);
+    let response = client.delete(&url).send().await?;
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    let response = client.delete(&url).send().await?;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    Ok(())
+}
+
+async fn create_tokio_watchlist(app: &TestApp) -> Result<String> {
+    let body: Value = create_watchlist(
+        app,
+        serde_json::json!({
+            "name": "runtime",
+            "packages": [{ "registry": "crates.io", "name": "tokio" }],
+            "targets": [
+                { "channel": "webhook", "target": "http://127.0.0.1:9/hook" },
+                { "channel": "email", "target": "team@example.com" }
+            ]
+        }),
+    )
+    .await?
+    .json()
+    .await?;
+
+    Ok(body["data"]["id"].as_str().unwrap().to_string())
+}
+
+fn tokio_output(version: &str) -> Value {
+    serde_json::json!({ "name": "tokio", "version": version, "downloads": 1 })
+}
+
+#[tokio::test]
+async fn test_version_change_enqueues_notifications_for_watchers() -> Result<()> {
+    // Arrange
+    let app = spawn_app().await?;
+    let id = create_tokio_watchlist(&app).await?;
+    app.mock_ingest_output("crates.io", &tokio_output("1.35.0"), false)
+        .await?;
+
+    // Act
+    app.mock_ingest_output("crates.io", &tokio_output("1.36.0"), false)
+        .await?;
+
+    // Assert
+    let url = format!("{}/watchlists/{}/notifications", app.address, id);
+    let body: Value = reqwest::Client::new().get(url).send().await?.json().await?;
+    let notifications = body["data"].as_array().unwrap();
+    assert_eq!(notifications.len(), 2);
+    assert_eq!(notifications[0]["status"], "pending");
+    assert_eq!(notifications[0]["old_version"], "1.35.0");
+    assert_eq!(notifications[0]["new_version"], "1.36.0");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_unchanged_version_does_not_enqueue_notifications() -> Result<()> {
+    // Arrange
+    let app = spawn_app().await?;
+    create_tokio_watchlist(&app).await?;
+    app.mock_ingest_output("crates.io", &tokio_output("1.35.0"), false)
+        .await?;
+
+    // Act
+    app.mock_ingest_output("crates.io", &tokio_output("1.35.0"), false)
+        .await?;
+
+    // Assert
+    let count = sqlx::query_scalar!("SELECT COUNT(*) FROM notifications;")
+        .fetch_one(&app.db_pool)
+        .await?;
+    assert_eq!(count, Some(0));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_claim_pending_notifications_leases_claimed_rows() -> Result<()> {
+    // Arrange
+    let app = spawn_app().await?;
+    create_watchlist(
+        &app,
+        serde_json::json!({
+            "name": "runtime",
+            "packages": [{ "registry": "crates.io", "name": "tokio" }],
+            "targets": [{ "channel": "webhook", "target": "http://127.0.0.1:9/hook" }]
+        }),
+    )
+    .await?;
+    let mut conn = app.db_pool.acquire().await?;
+    let now = Utc::now();
+    db::enqueue_version_notifications(&mut conn, "crates.io", "tokio", "1.35.0", "1.36.0", now)
+        .await?;
+    let lease_until = now + chrono::TimeDelta::minutes(15);
+
+    // Act
+    let claimed = db::claim_pending_notifications(&mut conn, now, lease_until, 10).await?;
+    let reclaimed = db::claim_pending_notifications(&mut conn, now, lease_until, 10).await?;
+    let expired = db::claim_pending_notifications(&mut conn, lease_until, lease_until, 10).await?;
+
+    // Assert
+    assert_eq!(claimed.len(), 1);
+    assert!(reclaimed.is_empty());
+    assert_eq!(expired.len(), 1);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_delete_watchlist_returns_204() -> Result<()> {
+    // Arrange
+    let app = spawn_app().await?;
+    let client = reqwest::Client::new();
+    let body: Value = create_watchlist(&app, serde_json::json!({ "name": "temporary" }))
+        .await?
+        .json()
+        .await?;
+    let url = format!(
+        "{}/watchlists/{}",
+        app.address,
+        body["data"]["id"].as_str().unwrap()
+    );
+
+    // Act
+    let response = client.delete(&url).send().await?;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    let response = client.get(&url).send().await?;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_delete_watchlist_rejects_etag_from_before_a_package_was_added() -> Result<()> {
+    // Arrange
+    let app = spawn_app().await?;
+    let client = reqwest::Client::new();
+    let body: Value = create_watchlist(&app, serde_json::json!({ "name": "temporary" }))
+        .await?
+        .json()
+        .await?;
+    let url = format!(
+        "{}/watchlists/{}",
+        app.address,
+        body["data"]["id"].as_str().unwrap()
+    );
+    let etag = client.get(&url).send().await?.headers()["etag"].clone();
+    client
+        .post(format!("{}/packages", url))
+        .json(&serde_json::json!({
+            "packages": [{ "registry": "crates.io", "name": "serde" }]
+        }))
+        .send()
+        .await?;
+
+    // Act
+    let response = client
+        .delete(&url)
+        .header("if-match", etag.clone())
+        .send()
+        .await?;
+
+    // Assert
+    assert_eq!(response.status(), StatusCode::PRECONDITION_FAILED);
+    let current = response.headers()["etag"].clone();
+    assert_ne!(current, etag);
+    let response = client
+        .delete(&url)
+        .header("if-match", current)
+        .send()
+        .await?;
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_watchlist_package_edits_reject_stale_etag() -> Result<()> {
+    // Arrange
+    let app = spawn_app().await?;
+    let client = reqwest::Client::new();
+    let body: Value = create_watchlist(&app, serde_json::json!({ "name": "shared" }))
+        .await?
+        .json()
+        .await?;
+    let url = format!(
+        "{}/watchlists/{}",
+        app.address,
+        body["data"]["id"].as_str().unwrap()
+    );
+    let etag = client.get(&url).send().await?.headers()["etag"].clone();
+    let add = |package: &str, etag| {
+        client
+            .post(format!("{}/packages", url))
+            .header("if-match", etag)
+            .json(&serde_json::json!({
+                "packages": [{ "registry": "crates.io", "name": package }]
+            }))
+            .send()
+    };
+
+    // Act
+    let first = add("serde", etag.clone()).await?;
+    let second = add("tokio", etag.clone()).await?;
+    let stale_remove = client
+        .delete(format!("{}/packages/crates.io/serde", url))
+        .header("if-match", etag)
+        .send()
+        .await?;
+
+    // Assert
+    assert_eq!(first.status(), StatusCode::OK);
+    assert_eq!(second.status(), StatusCode::PRECONDITION_FAILED);
+    assert_eq!(stale_remove.status(), StatusCode::PRECONDITION_FAILED);
+    let current = stale_remove.headers()["etag"].clone();
+    let response = client
+        .delete(format!("{}/packages/crates.io/serde", url))
+        .header("if-match", current)
+        .send()
+        .await?;
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+    Ok(())
+}
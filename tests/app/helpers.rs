@@ -1,19 +1,31 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, time::Duration};
 
 use anyhow::{Context, Result};
+use aws_sdk_s3::primitives::ByteStream;
+use chrono::Utc;
 use fake::{faker::name::en::Name, Fake};
 use integrations_api::{
     api::types::ApiResponse,
-    app::Application,
+    app::{Application, Role},
+    broker::Broker,
     config::{Config, DatabaseConfig},
-    models::{job::Job, package::Package},
-    services::rabbitmq,
+    dispatcher::Dispatcher,
+    models::{
+        job::{Job, JobStatus},
+        package::Package,
+    },
+    outputs::OutputKeys,
+    quarantine::Quarantine,
+    services::minio,
     telemetry::Metrics,
+    types::JobMessage,
 };
-use lapin::Channel;
+use lapin::{options::BasicPublishOptions, BasicProperties, Channel};
 use reqwest::Client;
 use serde_json::json;
 use sqlx::{Connection, Executor, PgConnection, PgPool};
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
 use uuid::Uuid;
 
 pub struct TestApp {
@@ -21,6 +33,14 @@ pub struct TestApp {
     pub db_pool: PgPool,
     pub channel: Channel,
     pub integration_queues: HashMap<String, String>,
+    pub dispatcher: Dispatcher,
+    pub quarantine: Quarantine,
+    pub queue_consumer: String,
+    pub minio_client: aws_sdk_s3::Client,
+    pub bucket_name: String,
+    pub output_keys: OutputKeys,
+    pub shutdown: CancellationToken,
+    pub handle: JoinHandle<Result<()>>,
 }
 
 impl TestApp {
@@ -69,25 +89,147 @@ impl TestApp {
         Ok(jobs)
     }
 
+    // Jobs carry the output key the dispatcher would have given them.
+    pub async fn mock_create_output_job(
+        &self,
+        registry: &str,
+        package_name: &str,
+        force: bool,
+    ) -> Result<Job> {
+        let id = Uuid::now_v7();
+        let mut conn = self.db_pool.acquire().await?;
+        let job = sqlx::query_as!(
+            Job,
+            "INSERT INTO jobs (id, registry, package_name, status, created_at, force, source, output_key) VALUES ($1, $2, $3, 'processing', $4, $5, 'api', $6) RETURNING *;",
+            id,
+            registry,
+            package_name,
+            Utc::now(),
+            force,
+            self.output_keys.key(registry, package_name, id),
+        )
+        .fetch_one(&mut *conn)
+        .await?;
+
+        Ok(job)
+    }
+
+    pub async fn put_output(
+        &self,
+        key: &str,
+        body: Vec<u8>,
+        content_encoding: Option<&str>,
+    ) -> Result<()> {
+        self.minio_client
+            .put_object()
+            .bucket(&self.bucket_name)
+            .key(key)
+            .set_content_encoding(content_encoding.map(str::to_string))
+            .body(ByteStream::from(body))
+            .send()
+            .await?;
+
+        Ok(())
+    }
+
+    // Publishes the job straight to the worker's queue, the way the dispatcher would.
+    pub async fn publish_job(&self, job: &Job) -> Result<()> {
+        let message = JobMessage {
+            job_id: job.id,
+            registry: job.registry.clone(),
+            package_name: job.package_name.clone(),
+            force: job.force,
+            output_key: job.output_key.clone(),
+        };
+        self.channel
+            .basic_publish(
+                "",
+                &self.queue_consumer,
+                BasicPublishOptions::default(),
+                &serde_json::to_vec(&message)?,
+                BasicProperties::default(),
+            )
+            .await?
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn wait_for_job(&self, id: Uuid) -> Result<Job> {
+        for _ in 0..100 {
+            let job = sqlx::query_as!(Job, "SELECT * FROM jobs WHERE id = $1;", id)
+                .fetch_one(&self.db_pool)
+                .await?;
+            if !matches!(job.status, JobStatus::Processing) {
+                return Ok(job);
+            }
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+
+        anyhow::bail!("Job {} is still processing", id)
+    }
+
+    pub async fn mock_ingest_output(
+        &self,
+        registry: &str,
+        output: &serde_json::Value,
+        force: bool,
+    ) -> Result<Job> {
+        let package_name = output["name"].as_str().context("Output has no name")?;
+        let job = self
+            .mock_create_output_job(registry, package_name, force)
+            .await?;
+        let key = job.output_key.as_deref().context("Job has no output key")?;
+        self.put_output(key, serde_json::to_vec(output)?, None)
+            .await?;
+        self.publish_job(&job).await?;
+
+        self.wait_for_job(job.id).await
+    }
+
     pub async fn mock_create_package(&self, registry: &str) -> Result<Package> {
+        self.mock_create_package_with_downloads(registry, 0).await
+    }
+
+    pub async fn mock_create_package_with_downloads(
+        &self,
+        registry: &str,
+        downloads: i64,
+    ) -> Result<Package> {
         let package_name: String = Name().fake();
         let package = Package {
             id: Uuid::now_v7(),
             registry: registry.to_string(),
             name: package_name,
             version: "1.0.0".to_string(),
-            downloads: 0,
+            downloads,
+            updated_at: Utc::now(),
+            description: None,
+            license: None,
+            repository_url: None,
+            homepage: None,
+            keywords: Vec::new(),
+            published_at: None,
+            deprecated: false,
+            created_at: Utc::now(),
+            last_refreshed_at: Utc::now(),
+            last_job_id: None,
+            deleted_at: None,
+            deletion_reason: None,
+            project_id: None,
+            revision: 1,
         };
 
         let mut conn = self.db_pool.acquire().await?;
         let package = sqlx::query_as!(
             Package,
-            "INSERT INTO packages (id, registry, name, version, downloads) VALUES ($1, $2, $3, $4, $5) RETURNING *;",
+            "INSERT INTO packages (id, registry, name, version, downloads, updated_at) VALUES ($1, $2, $3, $4, $5, $6) RETURNING *;",
             package.id,
             package.registry,
             package.name,
             package.version,
             package.downloads,
+            package.updated_at,
         )
         .fetch_one(&mut *conn)
         .await?;
@@ -127,29 +269,56 @@ pub async fn spawn_app() -> Result<TestApp> {
         configuration.rabbitmq.queue_consumer = queue_consumer.clone();
         configuration.rabbitmq.registry_queues = registry_queues.clone();
         configuration.minio.bucket_name = Uuid::new_v4().to_string();
+        configuration.refresher.enabled = false;
+        configuration.notifications.enabled = false;
         configuration
     };
 
     let db_pool = configure_database(&configuration.database).await?;
 
-    let rabbitmq_connection = rabbitmq::connect(&configuration.rabbitmq).await?;
-    let channel = rabbitmq_connection.create_channel().await?;
+    let broker = Broker::connect(&configuration.rabbitmq).await?;
+    let channel = broker.create_channel().await?;
 
     let integration_queues: HashMap<String, String> = registry_queues.into_iter().collect();
+    let dispatcher = Dispatcher::new(
+        db_pool.clone(),
+        broker,
+        exchange_name,
+        integration_queues.clone(),
+        configuration.minio.output_keys()?,
+    );
+
+    let minio_client = minio::create_client(&configuration.minio).await?;
+    let bucket_name = configuration.minio.bucket_name.clone();
+    let output_keys = configuration.minio.output_keys()?;
+    let quarantine = Quarantine::with_client(
+        minio_client.clone(),
+        configuration.minio.clone(),
+        db_pool.clone(),
+    );
 
     let metrics = Metrics::build()?;
-    let application = Application::build(configuration, metrics)
+    let application = Application::build(configuration, metrics, Role::All)
         .await
         .context("Failed to build application.")?;
     let port = application.api.port();
     let address = format!("http://127.0.0.1:{}", port);
-    let _ = tokio::spawn(application.run_until_stopped());
+    let shutdown = CancellationToken::new();
+    let handle = tokio::spawn(application.run_until(shutdown.clone().cancelled_owned()));
 
     Ok(TestApp {
         address,
         db_pool,
         channel,
         integration_queues,
+        dispatcher,
+        quarantine,
+        queue_consumer,
+        minio_client,
+        bucket_name,
+        output_keys,
+        shutdown,
+        handle,
     })
 }
 
@@ -0,0 +1,55 @@
+use std::{sync::Arc, time::Duration};
+
+use anyhow::Result;
+use chrono::{TimeDelta, Utc};
+use integrations_api::{job_stats::JobStatsCollector, telemetry::Metrics};
+use prometheus::{Encoder, TextEncoder};
+
+use crate::helpers::spawn_app;
+
+fn encode(metrics: &Metrics) -> Result<String> {
+    let mut buffer = Vec::new();
+    TextEncoder::new().encode(&metrics.registry.gather(), &mut buffer)?;
+
+    Ok(String::from_utf8(buffer)?)
+}
+
+#[tokio::test]
+async fn test_collect_publishes_job_counts_by_status() -> Result<()> {
+    // Arrange
+    let app = spawn_app().await?;
+    let client = reqwest::Client::new();
+    let (registry, _) = app.registry_queue()?;
+    let jobs = app.mock_create_jobs(&client, &registry, 3).await?;
+    sqlx::query!(
+        "UPDATE jobs SET status = 'failed' WHERE id = $1;",
+        jobs[0].data.id
+    )
+    .execute(&app.db_pool)
+    .await?;
+    sqlx::query!(
+        "UPDATE jobs SET created_at = $2 WHERE id = $1;",
+        jobs[1].data.id,
+        Utc::now() - TimeDelta::hours(1),
+    )
+    .execute(&app.db_pool)
+    .await?;
+    let metrics = Arc::new(Metrics::build()?);
+    let collector = JobStatsCollector::build(
+        app.db_pool.clone(),
+        Duration::from_secs(30),
+        metrics.clone(),
+    );
+
+    // Act
+    collector.collect().await?;
+
+    // Assert
+    let body = encode(&metrics)?;
+    assert!(body.contains(r#"jobs{status="processing"} 2"#));
+    assert!(body.contains(r#"jobs{status="failed"} 1"#));
+    assert!(body.contains(r#"jobs{status="completed"} 0"#));
+    assert!(metrics.jobs_oldest_processing_age_seconds().get() >= 3600.0);
+
+    Ok(())
+}
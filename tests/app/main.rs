@@ -1,2 +1,5 @@
 mod api;
 mod helpers;
+mod job_stats;
+mod refresher;
+mod worker;
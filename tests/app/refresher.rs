@@ -0,0 +1,125 @@
+use std::collections::HashMap;
+
+use anyhow::Result;
+use chrono::{TimeDelta, Utc};
+use integrations_api::{config::RefresherConfig, db, refresher::PackageRefresher};
+
+use crate::helpers::{spawn_app, TestApp};
+
+fn refresher(app: &TestApp, max_pending_per_registry: u64) -> PackageRefresher {
+    PackageRefresher::build(
+        app.db_pool.clone(),
+        app.dispatcher.clone(),
+        RefresherConfig {
+            enabled: true,
+            interval_seconds: 60,
+            max_age_seconds: 3600,
+            registry_max_age_seconds: HashMap::new(),
+            max_pending_per_registry,
+            pending_timeout_seconds: 3600,
+        },
+    )
+}
+
+async fn mock_stale_packages(app: &TestApp, count: u64) -> Result<()> {
+    let (registry, _) = app.registry_queue()?;
+    for package in app.mock_create_packages(&registry, count).await? {
+        sqlx::query!(
+            "UPDATE packages SET last_refreshed_at = $2 WHERE id = $1;",
+            package.id,
+            Utc::now() - TimeDelta::days(2),
+        )
+        .execute(&app.db_pool)
+        .await?;
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_refresh_once_enqueues_jobs_for_stale_packages() -> Result<()> {
+    // Arrange
+    let app = spawn_app().await?;
+    let (registry, _) = app.registry_queue()?;
+    mock_stale_packages(&app, 2).await?;
+    app.mock_create_package(&registry).await?;
+
+    // Act
+    let dispatched = refresher(&app, 10).refresh_once().await?;
+
+    // Assert
+    assert_eq!(dispatched, 2);
+    let jobs = sqlx::query!("SELECT source FROM jobs;")
+        .fetch_all(&app.db_pool)
+        .await?;
+    assert_eq!(jobs.len(), 2);
+    assert!(jobs.iter().all(|job| job.source == "refresher"));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_refresh_once_caps_pending_refreshes_per_registry() -> Result<()> {
+    // Arrange
+    let app = spawn_app().await?;
+    mock_stale_packages(&app, 5).await?;
+    let refresher = refresher(&app, 3);
+
+    // Act
+    let first = refresher.refresh_once().await?;
+    let second = refresher.refresh_once().await?;
+
+    // Assert
+    assert_eq!(first, 3);
+    assert_eq!(second, 0);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_refresh_once_skips_packages_whose_refresh_recently_failed() -> Result<()> {
+    // Arrange
+    let app = spawn_app().await?;
+    mock_stale_packages(&app, 2).await?;
+    let refresher = refresher(&app, 1);
+
+    // Act
+    let mut dispatched = Vec::new();
+    for _ in 0..3 {
+        dispatched.push(refresher.refresh_once().await?);
+        sqlx::query!("UPDATE jobs SET status = 'failed';")
+            .execute(&app.db_pool)
+            .await?;
+    }
+
+    // Assert
+    assert_eq!(dispatched, vec![1, 1, 0]);
+    let refreshed = sqlx::query_scalar!("SELECT COUNT(DISTINCT package_name) FROM jobs;")
+        .fetch_one(&app.db_pool)
+        .await?;
+    assert_eq!(refreshed, Some(2));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_refresh_once_skips_registries_locked_by_another_refresher() -> Result<()> {
+    // Arrange
+    let app = spawn_app().await?;
+    let (registry, _) = app.registry_queue()?;
+    mock_stale_packages(&app, 2).await?;
+    let refresher = refresher(&app, 10);
+    let mut other = app.db_pool.begin().await?;
+    assert!(db::try_lock_refresh(&mut other, &registry).await?);
+
+    // Act
+    let locked = refresher.refresh_once().await?;
+    other.commit().await?;
+    let unlocked = refresher.refresh_once().await?;
+
+    // Assert
+    assert_eq!(locked, 0);
+    assert_eq!(unlocked, 2);
+
+    Ok(())
+}
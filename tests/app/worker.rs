@@ -0,0 +1,315 @@
+use std::time::Duration;
+
+use anyhow::Result;
+use async_compression::tokio::bufread::{GzipEncoder, ZstdEncoder};
+use integrations_api::{
+    models::{
+        job::{Job, JobStatus},
+        package::Package,
+    },
+    worker::{MAX_RECORDED_ERRORS, RECORD_BATCH_SIZE},
+};
+use serde_json::json;
+use tokio::io::AsyncReadExt;
+use uuid::Uuid;
+
+use crate::helpers::{spawn_app, TestApp};
+
+const REGISTRY: &str = "crates.io";
+
+async fn mock_bulk_job(app: &TestApp, lines: &[String]) -> Result<Job> {
+    let mut job = app.mock_create_output_job(REGISTRY, "bulk", false).await?;
+    let key = format!("outputs/{}/{}.ndjson", REGISTRY, job.id);
+    app.put_output(&key, lines.join("\n").into_bytes(), None)
+        .await?;
+    job.output_key = Some(key);
+
+    Ok(job)
+}
+
+fn package_line(name: &str, version: &str) -> String {
+    json!({ "name": name, "version": version, "downloads": 1 }).to_string()
+}
+
+async fn count_ingested(app: &TestApp, job_id: Uuid) -> Result<i64> {
+    let count = sqlx::query_scalar!(
+        "SELECT COUNT(*) FROM packages WHERE last_job_id = $1;",
+        job_id
+    )
+    .fetch_one(&app.db_pool)
+    .await?;
+
+    Ok(count.unwrap_or_default())
+}
+
+#[tokio::test]
+async fn test_worker_records_per_line_errors_of_bulk_output() -> Result<()> {
+    // Arrange
+    let app = spawn_app().await?;
+    let lines = vec![
+        package_line("first", "1.0.0"),
+        "not json".to_string(),
+        json!({ "name": "no-version", "downloads": 1 }).to_string(),
+        package_line("second", "1.0.0"),
+    ];
+    let job = mock_bulk_job(&app, &lines).await?;
+
+    // Act
+    app.publish_job(&job).await?;
+
+    // Assert
+    let job = app.wait_for_job(job.id).await?;
+    assert!(matches!(job.status, JobStatus::Completed));
+    let errors = job.errors.unwrap_or_default();
+    assert_eq!(errors.len(), 2);
+    assert!(errors[0].starts_with("line 2: "));
+    assert!(errors[1].starts_with("line 3: "));
+    assert_eq!(count_ingested(&app, job.id).await?, 2);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_worker_commits_bulk_output_in_batches() -> Result<()> {
+    // Arrange
+    let app = spawn_app().await?;
+    app.mock_ingest_output(
+        REGISTRY,
+        &json!({ "name": "locked", "version": "1.0.0", "downloads": 1 }),
+        false,
+    )
+    .await?;
+    let mut lines: Vec<String> = (0..RECORD_BATCH_SIZE + 10)
+        .map(|i| package_line(&format!("bulk-{}", i), "1.0.0"))
+        .collect();
+    lines.push(package_line("locked", "2.0.0"));
+    let job = mock_bulk_job(&app, &lines).await?;
+
+    // Hold the last record's row so the worker stops inside the second batch.
+    let mut lock = app.db_pool.begin().await?;
+    sqlx::query!(
+        "SELECT id FROM packages WHERE registry = $1 AND name = 'locked' FOR UPDATE;",
+        REGISTRY
+    )
+    .fetch_one(&mut *lock)
+    .await?;
+
+    // Act
+    app.publish_job(&job).await?;
+
+    // Assert
+    let mut ingested = 0;
+    for _ in 0..100 {
+        ingested = count_ingested(&app, job.id).await?;
+        if ingested > 0 {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    assert_eq!(ingested, RECORD_BATCH_SIZE as i64);
+    tokio::time::sleep(Duration::from_millis(500)).await;
+    assert_eq!(
+        count_ingested(&app, job.id).await?,
+        RECORD_BATCH_SIZE as i64
+    );
+
+    lock.rollback().await?;
+    let job = app.wait_for_job(job.id).await?;
+    assert!(matches!(job.status, JobStatus::Completed));
+    assert_eq!(count_ingested(&app, job.id).await?, lines.len() as i64);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_worker_caps_recorded_errors_of_bulk_output() -> Result<()> {
+    // Arrange
+    let app = spawn_app().await?;
+    let mut lines = vec!["not json".to_string(); MAX_RECORDED_ERRORS + 10];
+    lines.push(package_line("valid", "1.0.0"));
+    let job = mock_bulk_job(&app, &lines).await?;
+
+    // Act
+    app.publish_job(&job).await?;
+
+    // Assert
+    let job = app.wait_for_job(job.id).await?;
+    assert!(matches!(job.status, JobStatus::Completed));
+    let errors = job.errors.unwrap_or_default();
+    assert_eq!(errors.len(), MAX_RECORDED_ERRORS);
+    assert!(errors[0].starts_with("line 1: "));
+    assert_eq!(count_ingested(&app, job.id).await?, 1);
+
+    Ok(())
+}
+
+async fn get_package(app: &TestApp, name: &str) -> Result<Package> {
+    let package = sqlx::query_as!(
+        Package,
+        "SELECT * FROM packages WHERE registry = $1 AND name = $2;",
+        REGISTRY,
+        name
+    )
+    .fetch_one(&app.db_pool)
+    .await?;
+
+    Ok(package)
+}
+
+#[tokio::test]
+async fn test_worker_refuses_to_downgrade_package() -> Result<()> {
+    // Arrange
+    let app = spawn_app().await?;
+    app.mock_ingest_output(
+        REGISTRY,
+        &json!({ "name": "serde", "version": "2.0.0", "downloads": 10 }),
+        false,
+    )
+    .await?;
+    let current = get_package(&app, "serde").await?;
+
+    // Act
+    let job = app
+        .mock_ingest_output(
+            REGISTRY,
+            &json!({ "name": "serde", "version": "1.0.0", "downloads": 20 }),
+            false,
+        )
+        .await?;
+
+    // Assert
+    assert!(matches!(job.status, JobStatus::Completed));
+    assert_eq!(job.skipped_version.as_deref(), Some("1.0.0"));
+    let package = get_package(&app, "serde").await?;
+    assert_eq!(package.version, "2.0.0");
+    assert_eq!(package.downloads, 10);
+    assert_eq!(package.updated_at, current.updated_at);
+    assert_eq!(package.last_job_id, Some(job.id));
+    assert!(package.last_refreshed_at > current.last_refreshed_at);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_worker_downgrades_package_when_forced() -> Result<()> {
+    // Arrange
+    let app = spawn_app().await?;
+    app.mock_ingest_output(
+        REGISTRY,
+        &json!({ "name": "serde", "version": "2.0.0", "downloads": 10 }),
+        false,
+    )
+    .await?;
+
+    // Act
+    let job = app
+        .mock_ingest_output(
+            REGISTRY,
+            &json!({ "name": "serde", "version": "1.0.0", "downloads": 20 }),
+            true,
+        )
+        .await?;
+
+    // Assert
+    assert!(matches!(job.status, JobStatus::Completed));
+    assert_eq!(job.skipped_version, None);
+    let package = get_package(&app, "serde").await?;
+    assert_eq!(package.version, "1.0.0");
+    assert_eq!(package.downloads, 20);
+    assert_eq!(package.last_job_id, Some(job.id));
+
+    Ok(())
+}
+
+fn serde_output() -> Vec<u8> {
+    json!({ "name": "serde", "version": "1.0.0", "downloads": 1 })
+        .to_string()
+        .into_bytes()
+}
+
+#[tokio::test]
+async fn test_worker_reads_output_from_templated_key() -> Result<()> {
+    // Arrange
+    let app = spawn_app().await?;
+    let mut job = app.mock_create_output_job(REGISTRY, "serde", false).await?;
+    job.output_key = None;
+    let key = app.output_keys.key(REGISTRY, "serde", job.id);
+    assert!(key.contains(&job.id.to_string()));
+    app.put_output(&key, serde_output(), None).await?;
+
+    // Act
+    app.publish_job(&job).await?;
+
+    // Assert
+    let job = app.wait_for_job(job.id).await?;
+    assert!(matches!(job.status, JobStatus::Completed));
+    assert_eq!(get_package(&app, "serde").await?.last_job_id, Some(job.id));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_worker_falls_back_to_legacy_output_key() -> Result<()> {
+    // Arrange
+    let app = spawn_app().await?;
+    let job = app.mock_create_output_job(REGISTRY, "serde", false).await?;
+    app.put_output("outputs/serde.json", serde_output(), None)
+        .await?;
+
+    // Act
+    app.publish_job(&job).await?;
+
+    // Assert
+    let job = app.wait_for_job(job.id).await?;
+    assert!(matches!(job.status, JobStatus::Completed));
+    assert_eq!(get_package(&app, "serde").await?.last_job_id, Some(job.id));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_worker_decompresses_gzip_output_by_content_encoding() -> Result<()> {
+    // Arrange
+    let app = spawn_app().await?;
+    let job = app.mock_create_output_job(REGISTRY, "serde", false).await?;
+    let mut compressed = Vec::new();
+    GzipEncoder::new(&serde_output()[..])
+        .read_to_end(&mut compressed)
+        .await?;
+    app.put_output(job.output_key.as_deref().unwrap(), compressed, Some("gzip"))
+        .await?;
+
+    // Act
+    app.publish_job(&job).await?;
+
+    // Assert
+    let job = app.wait_for_job(job.id).await?;
+    assert!(matches!(job.status, JobStatus::Completed));
+    assert_eq!(get_package(&app, "serde").await?.version, "1.0.0");
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_worker_decompresses_zstd_output_by_key_suffix() -> Result<()> {
+    // Arrange
+    let app = spawn_app().await?;
+    let mut job = app.mock_create_output_job(REGISTRY, "serde", false).await?;
+    let key = format!("outputs/{}/{}.json.zst", REGISTRY, job.id);
+    let mut compressed = Vec::new();
+    ZstdEncoder::new(&serde_output()[..])
+        .read_to_end(&mut compressed)
+        .await?;
+    app.put_output(&key, compressed, None).await?;
+    job.output_key = Some(key);
+
+    // Act
+    app.publish_job(&job).await?;
+
+    // Assert
+    let job = app.wait_for_job(job.id).await?;
+    assert!(matches!(job.status, JobStatus::Completed));
+    assert_eq!(get_package(&app, "serde").await?.version, "1.0.0");
+
+    Ok(())
+}